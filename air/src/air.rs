@@ -9,6 +9,13 @@ pub trait BaseAir<F>: Sync {
     /// The number of columns (a.k.a. registers) in this AIR.
     fn width(&self) -> usize;
 
+    /// The number of consecutive rows each evaluation of the constraints can see, i.e. the height
+    /// of `AirBuilder::main`. The default of 2 is the local and next rows; an AIR which needs
+    /// further rotations, like a recurrence over three terms, can widen it.
+    fn window_size(&self) -> usize {
+        2
+    }
+
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
@@ -9,6 +9,7 @@ use p3_field::{
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// The Baby Bear prime
@@ -86,6 +87,13 @@ impl Serialize for BabyBear {
 impl<'de> Deserialize<'de> for BabyBear {
     fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         let val = u32::deserialize(d)?;
+        // The value may come from anywhere, so it can't be assumed to be canonical.
+        if val >= P {
+            return Err(D::Error::custom(format_args!(
+                "{} is not a canonical Baby Bear element",
+                val
+            )));
+        }
         Ok(BabyBear::from_canonical_u32(val))
     }
 }
@@ -571,6 +579,9 @@ mod tests {
         let m2_serialized = serde_json::to_string(&m2).unwrap();
         let m2_deserialized: F = serde_json::from_str(&m2_serialized).unwrap();
         assert_eq!(m2, m2_deserialized);
+
+        assert!(serde_json::from_str::<F>(&P.to_string()).is_err());
+        assert!(serde_json::from_str::<F>(&u32::MAX.to_string()).is_err());
     }
 
     test_field!(crate::BabyBear);
@@ -1,7 +1,9 @@
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 use p3_code::{
-    Code, CodeOrFamily, LinearCode, SystematicCode, SystematicCodeOrFamily, SystematicLinearCode,
+    Code, CodeOrFamily, DecodeError, LinearCode, SystematicCode, SystematicCodeOrFamily,
+    SystematicLinearCode,
 };
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
@@ -73,6 +75,15 @@ where
         <BrakedownCode<F, IC> as Code<F, In>>::message_len(self)
             + <BrakedownCode<F, IC> as SystematicCode<F, In>>::parity_len(self)
     }
+
+    /// Brakedown codes are used for their fast encoding, and have no efficient decoder, so this
+    /// only detects errors; see `SystematicCode::decode_without_correction`.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        <BrakedownCode<F, IC> as SystematicCode<F, In>>::decode_without_correction(self, word)
+    }
 }
 
 impl<F, IC, In> SystematicCodeOrFamily<F, In> for BrakedownCode<F, IC>
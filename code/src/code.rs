@@ -1,19 +1,143 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use p3_field::Field;
-use p3_matrix::MatrixRows;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
 
 /// A code (in the coding theory sense), or a family thereof.
 pub trait CodeOrFamily<F: Field, In: MatrixRows<F>> {
     type Out: MatrixRows<F>;
 
+    /// Encode a single message.
+    fn encode(&self, message: &[F]) -> Vec<F>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        self.encode_batch(RowMajorMatrix::new_col(message.to_vec()).into())
+            .to_row_major_matrix()
+            .values
+    }
+
+    /// Encode a batch of messages, one per column of `messages`, into a matrix whose columns are
+    /// the corresponding codewords.
+    ///
+    /// This is the only method an implementer needs to define. Implementations are free to encode
+    /// all columns at once, e.g. with a batched DFT.
     fn encode_batch(&self, messages: In) -> Self::Out;
 }
 
 /// A code (in the coding theory sense).
+///
+/// `Code` is object-safe, so codes of different types can be stored behind trait objects, as in
+/// `NamedCodeRegistry`, as long as they share an output matrix type.
+///
+/// ```
+/// use p3_code::{Code, CodeOrFamily, IdentityCode};
+/// use p3_field::AbstractField;
+/// use p3_matrix::dense::RowMajorMatrix;
+/// use p3_mersenne_31::Mersenne31;
+///
+/// type F = Mersenne31;
+/// type M = RowMajorMatrix<F>;
+///
+/// let code: Box<dyn Code<F, M, Out = M>> = Box::new(IdentityCode { len: 3 });
+/// let message = [1, 2, 3].map(F::from_canonical_u8);
+/// assert_eq!(code.encode(&message), message);
+/// assert_eq!(code.decode(&message), Ok(message.to_vec()));
+/// assert_eq!(code.rate(), (3, 3));
+/// ```
 pub trait Code<F: Field, In: MatrixRows<F>>: CodeOrFamily<F, In> {
     /// The input length of this code's encoder. In other words, the dimension of the code.
     fn message_len(&self) -> usize;
 
     fn codeword_len(&self) -> usize;
+
+    /// Encode a single message, checking first that it has length `message_len`, where `encode`
+    /// may panic or return garbage.
+    fn try_encode(&self, message: &[F]) -> Result<Vec<F>, EncodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if message.len() != self.message_len() {
+            return Err(EncodeError::WrongLength {
+                expected: self.message_len(),
+                actual: message.len(),
+            });
+        }
+        Ok(self.encode(message))
+    }
+
+    /// Decode a single word of length `codeword_len` into the message whose codeword it is, or is
+    /// closest to, as far as this code's decoder can tell.
+    ///
+    /// Implementations return `DecodeError::WrongLength` for a word of the wrong length, and
+    /// `DecodeError::TooManyErrors` for a word they can't decode, rather than a garbage message.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>;
+
+    /// Decode a batch of codewords, one per column of `codewords`, into a matrix whose columns are
+    /// the corresponding messages. This is the inverse of `encode_batch`.
+    ///
+    /// Unlike `decode`, this doesn't correct errors: it returns `DecodeError::TooManyErrors` if any
+    /// column isn't a codeword. The default implementation decodes each column and checks that
+    /// re-encoding the message gives the column back; implementers with a cheaper membership test
+    /// may override it.
+    fn decode_batch(&self, codewords: &RowMajorMatrix<F>) -> Result<RowMajorMatrix<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if codewords.height() != self.codeword_len() {
+            return Err(DecodeError::WrongLength {
+                expected: self.codeword_len(),
+                actual: codewords.height(),
+            });
+        }
+        let width = codewords.width();
+        let mut messages = vec![F::zero(); self.message_len() * width];
+        for c in 0..width {
+            let word = (0..codewords.height())
+                .map(|r| codewords.values[r * width + c])
+                .collect::<Vec<_>>();
+            let message = self.decode(&word)?;
+            if self.encode(&message) != word {
+                return Err(DecodeError::TooManyErrors);
+            }
+            for (r, x) in message.into_iter().enumerate() {
+                messages[r * width + c] = x;
+            }
+        }
+        Ok(RowMajorMatrix::new(messages, width))
+    }
+
+    /// The length of the codeword for a message of length `message_len`, computed without
+    /// encoding anything, e.g. to preallocate a buffer.
+    ///
+    /// The default suits fixed-length codes, and ignores `message_len` in favour of
+    /// `codeword_len`. Codes which accept messages of varying length, such as rateless codes,
+    /// should override it.
+    fn encoded_len(&self, _message_len: usize) -> usize {
+        self.codeword_len()
+    }
+
+    /// The rate `k/n` of this code, as the unreduced pair `(message_len, codeword_len)`.
+    fn rate(&self) -> (usize, usize) {
+        (self.message_len(), self.codeword_len())
+    }
+
+    /// A lower bound on the minimum Hamming distance between two distinct codewords.
+    ///
+    /// The default of 1 holds for any code with an injective encoder. Implementers which know
+    /// their distance, such as MDS codes, should override it.
+    fn distance(&self) -> usize {
+        1
+    }
+
+    /// `distance` as a fraction of `codeword_len`.
+    fn relative_distance(&self) -> f64 {
+        self.distance() as f64 / self.codeword_len() as f64
+    }
 }
 
 /// A family of codes (in the coding theory sense).
@@ -28,3 +152,19 @@ pub trait LinearCode<F: Field, In: MatrixRows<F>>: Code<F, In> {}
 
 /// A family of linear codes.
 pub trait LinearCodeFamily<F: Field, In: MatrixRows<F>>: CodeFamily<F, In> {}
+
+/// An error returned when a message can't be encoded.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The message's length doesn't match the code's `message_len`.
+    WrongLength { expected: usize, actual: usize },
+}
+
+/// An error returned when a codeword can't be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The codeword's length doesn't match the code's `codeword_len`.
+    WrongLength { expected: usize, actual: usize },
+    /// The word contains more errors than the decoder can correct.
+    TooManyErrors,
+}
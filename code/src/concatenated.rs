@@ -0,0 +1,189 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
+
+use crate::{Code, CodeOrFamily, DecodeError, LinearCode};
+
+/// The concatenation of an outer code with an inner code.
+///
+/// A message is read as `outer.message_len()` symbols, each a block of `inner.message_len()` field
+/// elements. The outer code encodes these symbols into `outer.codeword_len()` symbols, acting on
+/// each position within a block independently, and the inner code then encodes each resulting
+/// block into `inner.codeword_len()` elements. The message and codeword lengths are thus the
+/// products of the outer and inner ones.
+pub struct ConcatenatedCode<Outer, Inner> {
+    pub outer: Outer,
+    pub inner: Inner,
+}
+
+impl<Outer, Inner> ConcatenatedCode<Outer, Inner> {
+    pub fn new(outer: Outer, inner: Inner) -> Self {
+        Self { outer, inner }
+    }
+}
+
+impl<F, Outer, Inner, In> CodeOrFamily<F, In> for ConcatenatedCode<Outer, Inner>
+where
+    F: Field,
+    Outer: Code<F, RowMajorMatrix<F>>,
+    Inner: Code<F, RowMajorMatrix<F>>,
+    In: MatrixRows<F>,
+{
+    type Out = RowMajorMatrix<F>;
+
+    fn encode_batch(&self, messages: In) -> Self::Out {
+        let block_len = self.inner.message_len();
+        let inner_codeword_len = self.inner.codeword_len();
+        assert_eq!(messages.height(), self.outer.message_len() * block_len);
+        let width = messages.width();
+
+        // Each block of rows becomes a single row, so the outer code sees one column per position
+        // within a block and per message.
+        let symbols = RowMajorMatrix::new(messages.to_row_major_matrix().values, block_len * width);
+        let outer_codewords = self.outer.encode_batch(symbols).to_row_major_matrix();
+        let num_blocks = outer_codewords.height();
+
+        // Lay the blocks side by side, so the inner code encodes all of them in one batch.
+        let mut blocks = Vec::with_capacity(block_len * num_blocks * width);
+        for r in 0..block_len {
+            for block in outer_codewords.rows() {
+                blocks.extend_from_slice(&block[r * width..(r + 1) * width]);
+            }
+        }
+        let inner_codewords = self
+            .inner
+            .encode_batch(RowMajorMatrix::new(blocks, num_blocks * width))
+            .to_row_major_matrix();
+
+        // Stack the encoded blocks back on top of each other.
+        let mut values = Vec::with_capacity(num_blocks * inner_codeword_len * width);
+        for b in 0..num_blocks {
+            for row in inner_codewords.rows() {
+                values.extend_from_slice(&row[b * width..(b + 1) * width]);
+            }
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F, Outer, Inner, In> Code<F, In> for ConcatenatedCode<Outer, Inner>
+where
+    F: Field,
+    Outer: Code<F, RowMajorMatrix<F>>,
+    Inner: Code<F, RowMajorMatrix<F>>,
+    In: MatrixRows<F>,
+{
+    fn message_len(&self) -> usize {
+        self.outer.message_len() * self.inner.message_len()
+    }
+
+    fn codeword_len(&self) -> usize {
+        self.outer.codeword_len() * self.inner.codeword_len()
+    }
+
+    /// The product of the outer and inner distances.
+    fn distance(&self) -> usize {
+        self.outer.distance() * self.inner.distance()
+    }
+
+    /// Decodes each block with the inner code, then each position within a block, across the
+    /// blocks, with the outer code.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        let codeword_len = self.outer.codeword_len() * self.inner.codeword_len();
+        if word.len() != codeword_len {
+            return Err(DecodeError::WrongLength {
+                expected: codeword_len,
+                actual: word.len(),
+            });
+        }
+        let blocks = word
+            .chunks_exact(self.inner.codeword_len())
+            .map(|block| self.inner.decode(block))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let block_len = self.inner.message_len();
+        let mut message = vec![F::zero(); self.outer.message_len() * block_len];
+        for r in 0..block_len {
+            let symbols = blocks.iter().map(|block| block[r]).collect::<Vec<_>>();
+            for (s, x) in self.outer.decode(&symbols)?.into_iter().enumerate() {
+                message[s * block_len + r] = x;
+            }
+        }
+        Ok(message)
+    }
+}
+
+impl<F, Outer, Inner, In> LinearCode<F, In> for ConcatenatedCode<Outer, Inner>
+where
+    F: Field,
+    Outer: LinearCode<F, RowMajorMatrix<F>>,
+    Inner: LinearCode<F, RowMajorMatrix<F>>,
+    In: MatrixRows<F>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+    use crate::{IdentityCode, ParityMatrixCode};
+
+    type F = Mersenne31;
+    type In = RowMajorMatrix<F>;
+    type Concatenated = ConcatenatedCode<IdentityCode, IdentityCode>;
+
+    #[test]
+    fn test_identity_of_identity() {
+        let code = ConcatenatedCode::new(IdentityCode { len: 2 }, IdentityCode { len: 3 });
+        assert_eq!(<Concatenated as Code<F, In>>::message_len(&code), 6);
+        assert_eq!(<Concatenated as Code<F, In>>::codeword_len(&code), 6);
+
+        let messages = RowMajorMatrix::new((0..12).map(F::from_canonical_u32).collect(), 2);
+        assert_eq!(
+            <Concatenated as CodeOrFamily<F, In>>::encode_batch(&code, messages.clone()),
+            messages
+        );
+
+        let message = messages.values[..6].to_vec();
+        assert_eq!(
+            <Concatenated as Code<F, In>>::decode(&code, &message),
+            Ok(message)
+        );
+    }
+
+    #[test]
+    fn test_decode_corrects_outer_errors() {
+        // The outer repetition code of length 3 outvotes a corrupted block.
+        let p = RowMajorMatrix::new(vec![F::one(), F::one()], 2);
+        let code = ConcatenatedCode::new(
+            ParityMatrixCode::from_parity_matrix(p),
+            IdentityCode { len: 2 },
+        );
+        let message = [3, 4].map(F::from_canonical_u32);
+        let codeword =
+            <ConcatenatedCode<_, IdentityCode> as CodeOrFamily<F, In>>::encode(&code, &message);
+        assert_eq!(codeword, [3, 4, 3, 4, 3, 4].map(F::from_canonical_u32));
+
+        let mut word = codeword;
+        word[3] += F::one();
+        assert_eq!(
+            <ConcatenatedCode<_, IdentityCode> as Code<F, In>>::decode(&code, &word),
+            Ok(message.to_vec())
+        );
+        assert_eq!(
+            <ConcatenatedCode<_, IdentityCode> as Code<F, In>>::decode(&code, &word[1..]),
+            Err(DecodeError::WrongLength {
+                expected: 6,
+                actual: 5
+            })
+        );
+    }
+}
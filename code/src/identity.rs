@@ -1,8 +1,12 @@
+use alloc::vec::Vec;
+
 use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::MatrixRows;
 
 use crate::{
-    Code, CodeOrFamily, LinearCode, SystematicCode, SystematicCodeOrFamily, SystematicLinearCode,
+    Code, CodeOrFamily, DecodeError, LinearCode, SystematicCode, SystematicCodeOrFamily,
+    SystematicLinearCode,
 };
 
 /// The trivial code whose encoder is the identity function.
@@ -26,6 +30,25 @@ impl<F: Field, In: MatrixRows<F>> Code<F, In> for IdentityCode {
     fn codeword_len(&self) -> usize {
         self.len
     }
+
+    /// The encoder leaves messages of any length unchanged, so this is just `message_len`.
+    fn encoded_len(&self, message_len: usize) -> usize {
+        message_len
+    }
+
+    /// Every word of the right length is a codeword, and is its own message.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if word.len() != self.len {
+            return Err(DecodeError::WrongLength {
+                expected: self.len,
+                actual: word.len(),
+            });
+        }
+        Ok(word.to_vec())
+    }
 }
 
 impl<F: Field, In: MatrixRows<F>> SystematicCodeOrFamily<F, In> for IdentityCode {}
@@ -38,10 +61,7 @@ impl<F: Field, In: MatrixRows<F>> SystematicLinearCode<F, In> for IdentityCode {
 
 #[cfg(test)]
 mod tests {
-    use alloc::vec::Vec;
-
     use p3_field::AbstractField;
-    use p3_matrix::dense::RowMajorMatrix;
     use p3_mersenne_31::Mersenne31;
 
     use super::*;
@@ -72,4 +92,110 @@ mod tests {
             CODE_LEN
         );
     }
+
+    #[test]
+    fn test_encode() {
+        let message = [3_u16, 1, 4, 1, 5]
+            .iter()
+            .map(|t| F::from_canonical_u16(*t))
+            .collect::<Vec<_>>();
+        let identity_code = IdentityCode { len: message.len() };
+        assert_eq!(
+            <IdentityCode as CodeOrFamily<F, In>>::encode(&identity_code, &message),
+            message
+        );
+    }
+
+    #[test]
+    fn test_rate_and_distance() {
+        let identity_code = IdentityCode { len: 1 };
+        assert_eq!(<IdentityCode as Code<F, In>>::rate(&identity_code), (1, 1));
+        assert_eq!(<IdentityCode as Code<F, In>>::distance(&identity_code), 1);
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::relative_distance(&identity_code),
+            1.0
+        );
+
+        // Codewords differing in one symbol are distinct, so the distance stays 1 as `len` grows.
+        let identity_code = IdentityCode { len: 4 };
+        assert_eq!(<IdentityCode as Code<F, In>>::rate(&identity_code), (4, 4));
+        assert_eq!(<IdentityCode as Code<F, In>>::distance(&identity_code), 1);
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::relative_distance(&identity_code),
+            0.25
+        );
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        let identity_code = IdentityCode { len: CODE_LEN };
+        for message_len in [0, 1, CODE_LEN, 7] {
+            assert_eq!(
+                <IdentityCode as Code<F, In>>::encoded_len(&identity_code, message_len),
+                message_len
+            );
+        }
+    }
+
+    #[test]
+    fn test_parity_only() {
+        let message = [2_u16, 7, 1, 8]
+            .iter()
+            .map(|t| F::from_canonical_u16(*t))
+            .collect::<Vec<_>>();
+        let identity_code = IdentityCode { len: message.len() };
+        assert!(
+            <IdentityCode as SystematicCode<F, In>>::parity_only(&identity_code, &message)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let message = [1_u16, 6, 1, 8]
+            .iter()
+            .map(|t| F::from_canonical_u16(*t))
+            .collect::<Vec<_>>();
+        let identity_code = IdentityCode { len: message.len() };
+        let codeword = <IdentityCode as CodeOrFamily<F, In>>::encode(&identity_code, &message);
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::decode(&identity_code, &codeword),
+            Ok(message)
+        );
+
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::decode(&identity_code, &codeword[1..]),
+            Err(DecodeError::WrongLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let messages = RowMajorMatrix::new(
+            [1_u16, 4, 1, 4, 2, 1]
+                .iter()
+                .map(|t| F::from_canonical_u16(*t))
+                .collect::<Vec<_>>(),
+            2,
+        );
+        let identity_code = IdentityCode { len: 3 };
+        let codewords =
+            <IdentityCode as CodeOrFamily<F, In>>::encode_batch(&identity_code, messages.clone());
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::decode_batch(&identity_code, &codewords),
+            Ok(messages)
+        );
+
+        let identity_code = IdentityCode { len: 2 };
+        assert_eq!(
+            <IdentityCode as Code<F, In>>::decode_batch(&identity_code, &codewords),
+            Err(DecodeError::WrongLength {
+                expected: 2,
+                actual: 3
+            })
+        );
+    }
 }
@@ -5,11 +5,15 @@
 extern crate alloc;
 
 mod code;
+mod concatenated;
 mod identity;
+mod punctured;
 mod registry;
 mod systematic;
 
 pub use code::*;
+pub use concatenated::*;
 pub use identity::*;
+pub use punctured::*;
 pub use registry::*;
 pub use systematic::*;
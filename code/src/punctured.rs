@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
+
+use crate::{Code, CodeOrFamily, DecodeError, LinearCode};
+
+/// A code punctured at some positions, i.e. one whose codewords are those of `inner` with the
+/// symbols at those positions dropped. This raises the rate, at the cost of up to one unit of
+/// distance per dropped position.
+///
+/// Positions past the end of a codeword are ignored, so that for codes whose codewords vary in
+/// length, only the positions a given codeword has are dropped from it.
+pub struct PuncturedCode<C> {
+    pub inner: C,
+    /// The dropped positions, in increasing order and without repeats.
+    dropped: Vec<usize>,
+}
+
+impl<C> PuncturedCode<C> {
+    /// `inner`, punctured at each position in `dropped`. The positions may be given in any order,
+    /// and repeats are ignored.
+    pub fn new(inner: C, mut dropped: Vec<usize>) -> Self {
+        dropped.sort_unstable();
+        dropped.dedup();
+        Self { inner, dropped }
+    }
+
+    /// The dropped positions, in increasing order.
+    pub fn dropped(&self) -> &[usize] {
+        &self.dropped
+    }
+
+    /// The number of positions dropped from an unpunctured codeword of length `len`.
+    fn num_dropped(&self, len: usize) -> usize {
+        self.dropped.partition_point(|&i| i < len)
+    }
+
+    /// Reinserts the dropped positions into a punctured codeword, as erasures, giving a word of
+    /// the inner code in which `None` marks the symbols that were dropped. This is the form an
+    /// erasure decoder for the inner code would take.
+    pub fn depuncture<F, In>(&self, word: &[F]) -> Result<Vec<Option<F>>, DecodeError>
+    where
+        F: Field,
+        In: MatrixRows<F>,
+        C: Code<F, In>,
+    {
+        let inner_len = self.inner.codeword_len();
+        let expected = inner_len - self.num_dropped(inner_len);
+        if word.len() != expected {
+            return Err(DecodeError::WrongLength {
+                expected,
+                actual: word.len(),
+            });
+        }
+        let mut symbols = word.iter();
+        let mut dropped = self.dropped.iter().peekable();
+        Ok((0..inner_len)
+            .map(|i| {
+                if dropped.next_if_eq(&&i).is_some() {
+                    None
+                } else {
+                    symbols.next().copied()
+                }
+            })
+            .collect())
+    }
+}
+
+impl<F, C, In> CodeOrFamily<F, In> for PuncturedCode<C>
+where
+    F: Field,
+    C: Code<F, In>,
+    In: MatrixRows<F>,
+{
+    type Out = RowMajorMatrix<F>;
+
+    fn encode_batch(&self, messages: In) -> Self::Out {
+        let codewords = self.inner.encode_batch(messages).to_row_major_matrix();
+        let width = codewords.width();
+        let height = codewords.height();
+
+        let mut values = Vec::with_capacity((height - self.num_dropped(height)) * width);
+        let mut dropped = self.dropped.iter().peekable();
+        for (i, row) in codewords.rows().enumerate() {
+            if dropped.next_if_eq(&&i).is_none() {
+                values.extend_from_slice(row);
+            }
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F, C, In> Code<F, In> for PuncturedCode<C>
+where
+    F: Field,
+    C: Code<F, In>,
+    In: MatrixRows<F>,
+{
+    fn message_len(&self) -> usize {
+        self.inner.message_len()
+    }
+
+    fn codeword_len(&self) -> usize {
+        let inner_len = self.inner.codeword_len();
+        inner_len - self.num_dropped(inner_len)
+    }
+
+    fn encoded_len(&self, message_len: usize) -> usize {
+        let inner_len = self.inner.encoded_len(message_len);
+        inner_len - self.num_dropped(inner_len)
+    }
+
+    /// Each dropped position can bring two codewords one symbol closer. Once as many positions
+    /// are dropped as the inner distance, distinct messages may share a codeword, and this is 0.
+    fn distance(&self) -> usize {
+        let num_dropped = self.num_dropped(self.inner.codeword_len());
+        self.inner.distance().saturating_sub(num_dropped)
+    }
+
+    /// Fills the dropped positions with zeros, decodes that with the inner code, which may correct
+    /// them as errors, and then checks that the message's codeword matches `word`. If several
+    /// messages share the codeword, as when `distance` is 0, any of them may be returned.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        let inner_word = self
+            .depuncture::<F, In>(word)?
+            .into_iter()
+            .map(|x| x.unwrap_or_else(F::zero))
+            .collect::<Vec<_>>();
+        let message = self.inner.decode(&inner_word)?;
+        if <Self as CodeOrFamily<F, In>>::encode(self, &message) != word {
+            return Err(DecodeError::TooManyErrors);
+        }
+        Ok(message)
+    }
+}
+
+impl<F, C, In> LinearCode<F, In> for PuncturedCode<C>
+where
+    F: Field,
+    C: LinearCode<F, In>,
+    In: MatrixRows<F>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_field::AbstractField;
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+    use crate::IdentityCode;
+
+    type F = Mersenne31;
+    type In = RowMajorMatrix<F>;
+    type Punctured = PuncturedCode<IdentityCode>;
+
+    #[test]
+    fn test_puncture_identity() {
+        let code = PuncturedCode::new(IdentityCode { len: 4 }, vec![1]);
+        assert_eq!(<Punctured as Code<F, In>>::message_len(&code), 4);
+        assert_eq!(<Punctured as Code<F, In>>::codeword_len(&code), 3);
+        assert_eq!(<Punctured as Code<F, In>>::encoded_len(&code, 4), 3);
+
+        let message = [1, 2, 3, 4].map(F::from_canonical_u32);
+        let codeword = <Punctured as CodeOrFamily<F, In>>::encode(&code, &message);
+        assert_eq!(codeword, [message[0], message[2], message[3]]);
+
+        assert_eq!(
+            code.depuncture::<F, In>(&codeword),
+            Ok(vec![
+                Some(message[0]),
+                None,
+                Some(message[2]),
+                Some(message[3])
+            ])
+        );
+        assert_eq!(
+            code.depuncture::<F, In>(&message),
+            Err(DecodeError::WrongLength {
+                expected: 3,
+                actual: 4
+            })
+        );
+
+        // The dropped symbol can't be recovered, but the rest of the message is.
+        assert_eq!(
+            <Punctured as Code<F, In>>::decode(&code, &codeword),
+            Ok(vec![message[0], F::zero(), message[2], message[3]])
+        );
+    }
+}
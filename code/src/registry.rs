@@ -1,12 +1,13 @@
 use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec::Vec;
 
 use p3_field::Field;
 use p3_matrix::{Matrix, MatrixRows};
 
 use crate::{
-    CodeFamily, CodeOrFamily, LinearCodeFamily, SystematicCodeFamily, SystematicCodeOrFamily,
-    SystematicLinearCode,
+    Code, CodeFamily, CodeOrFamily, IdentityCode, LinearCodeFamily, SystematicCodeFamily,
+    SystematicCodeOrFamily, SystematicLinearCode,
 };
 
 /// A registry of systematic, linear codes for various message sizes.
@@ -103,6 +104,96 @@ where
 {
 }
 
+/// A registry of codes keyed by name, e.g. for selecting a code from a config file at runtime.
+pub struct NamedCodeRegistry<F: Field, In: MatrixRows<F>, Out: MatrixRows<F>> {
+    /// Ordered by insertion.
+    codes: Vec<(String, Box<dyn Code<F, In, Out = Out>>)>,
+}
+
+impl<F, In, Out> NamedCodeRegistry<F, In, Out>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    Out: MatrixRows<F>,
+{
+    pub fn new() -> Self {
+        Self { codes: Vec::new() }
+    }
+
+    /// Registers `code` under `name`, or returns an error if a code is already registered under
+    /// that name, leaving it in place. See `replace_named` for replacing it.
+    pub fn register_named(
+        &mut self,
+        name: &str,
+        code: Box<dyn Code<F, In, Out = Out>>,
+    ) -> Result<(), RegistryError> {
+        if self.get(name).is_some() {
+            return Err(RegistryError::DuplicateName(String::from(name)));
+        }
+        self.codes.push((String::from(name), code));
+        Ok(())
+    }
+
+    /// Registers `code` under `name`, replacing any code previously registered under that name.
+    /// A replaced code keeps its place in `names`.
+    pub fn replace_named(&mut self, name: &str, code: Box<dyn Code<F, In, Out = Out>>) {
+        match self.codes.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = code,
+            None => self.codes.push((String::from(name), code)),
+        }
+    }
+
+    /// The code registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&dyn Code<F, In, Out = Out>> {
+        self.codes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, c)| &**c)
+    }
+
+    /// The names of all registered codes, in the order they were first registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.codes.iter().map(|(n, _)| n.as_str()).collect()
+    }
+}
+
+/// An error returned when a code can't be added to a `NamedCodeRegistry`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A code is already registered under this name.
+    DuplicateName(String),
+}
+
+impl<F, M> NamedCodeRegistry<F, M, M>
+where
+    F: Field,
+    M: MatrixRows<F>,
+{
+    /// A registry holding each code defined in this crate, under its canonical name, for messages
+    /// of length `message_len`. Currently this is just `"identity"`, the `IdentityCode`.
+    ///
+    /// Codes defined in other crates aren't included; see e.g. `p3_reed_solomon` for a registry
+    /// which also holds a Reed-Solomon code.
+    pub fn with_builtins(message_len: usize) -> Self {
+        let mut registry = Self::new();
+        registry
+            .register_named("identity", Box::new(IdentityCode { len: message_len }))
+            .expect("the registry starts out empty");
+        registry
+    }
+}
+
+impl<F, In, Out> Default for NamedCodeRegistry<F, In, Out>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    Out: MatrixRows<F>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use p3_field::AbstractField;
@@ -110,7 +201,7 @@ mod tests {
     use p3_mersenne_31::Mersenne31;
 
     use super::*;
-    use crate::{Code, LinearCode, SystematicCode};
+    use crate::{DecodeError, LinearCode, SystematicCode};
 
     type F = Mersenne31;
     type In = RowMajorMatrix<F>;
@@ -153,7 +244,17 @@ mod tests {
         fn message_len(&self) -> usize {
             self.len
         }
-    }
+
+        fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError> {
+            if word.len() != self.len {
+                return Err(DecodeError::WrongLength {
+                    expected: self.len,
+                    actual: word.len(),
+                });
+            }
+            let len_inv = F::from_canonical_usize(self.len).inverse();
+            Ok(word.iter().map(|&x| x * len_inv).collect())
+        }
 
     impl SystematicCodeOrFamily<F, In> for TestSystematicLinearCode {}
 
@@ -279,4 +380,48 @@ mod tests {
         assert_eq!(sl_code_registry.codeword_len(5), Some(REGISTRY_LEN_2));
         assert_eq!(sl_code_registry.codeword_len(10), None);
     }
+
+    #[test]
+    fn test_named_code_registry() {
+        let mut registry = NamedCodeRegistry::<F, In, Out>::new();
+        registry
+            .register_named("identity", Box::new(IdentityCode { len: 4 }))
+            .unwrap();
+        registry
+            .register_named("test", Box::new(TestSystematicLinearCode { len: 3 }))
+            .unwrap();
+
+        assert_eq!(registry.get("identity").unwrap().message_len(), 4);
+        assert_eq!(registry.get("test").unwrap().message_len(), 3);
+        assert!(registry.get("reed_solomon").is_none());
+
+        assert_eq!(registry.names(), ["identity", "test"]);
+    }
+
+    #[test]
+    fn test_named_code_registry_duplicate_name() {
+        let mut registry = NamedCodeRegistry::<F, In, Out>::new();
+        registry
+            .register_named("test", Box::new(TestSystematicLinearCode { len: 3 }))
+            .unwrap();
+
+        // Registering under an existing name fails, and keeps the old code.
+        assert_eq!(
+            registry.register_named("test", Box::new(TestSystematicLinearCode { len: 5 })),
+            Err(RegistryError::DuplicateName(String::from("test")))
+        );
+        assert_eq!(registry.get("test").unwrap().message_len(), 3);
+
+        registry.replace_named("test", Box::new(TestSystematicLinearCode { len: 5 }));
+        assert_eq!(registry.get("test").unwrap().message_len(), 5);
+        registry.replace_named("identity", Box::new(IdentityCode { len: 4 }));
+        assert_eq!(registry.names(), ["test", "identity"]);
+    }
+
+    #[test]
+    fn test_named_code_registry_with_builtins() {
+        let registry = NamedCodeRegistry::<F, In, In>::with_builtins(4);
+        assert_eq!(registry.names(), ["identity"]);
+        assert_eq!(registry.get("identity").unwrap().codeword_len(), 4);
+    }
 }
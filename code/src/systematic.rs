@@ -1,7 +1,11 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use p3_field::Field;
-use p3_matrix::MatrixRows;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
 
-use crate::{Code, CodeFamily, CodeOrFamily, LinearCode};
+use crate::{Code, CodeFamily, CodeOrFamily, DecodeError, LinearCode};
 
 /// A systematic code, or a family thereof.
 // TODO: Remove? Not really used.
@@ -22,6 +26,91 @@ pub trait SystematicCode<F: Field, In: MatrixRows<F>>:
                 );
             })
     }
+
+    /// Encode a single message, returning only the parity symbols, i.e. the codeword without its
+    /// systematic prefix.
+    ///
+    /// The default implementation encodes the full codeword and discards the prefix; implementers
+    /// which can compute the parity directly should override it.
+    fn parity_only(&self, message: &[F]) -> Vec<F>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        let mut codeword = self.encode(message);
+        codeword.drain(..self.message_len());
+        codeword
+    }
+
+    /// Whether `word` is a codeword of this code.
+    ///
+    /// The default implementation re-encodes the systematic prefix and compares the parity
+    /// symbols; implementers with a parity-check matrix `H` may override it to check `H * word = 0`.
+    fn is_codeword(&self, word: &[F]) -> bool
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if word.len() != self.codeword_len() {
+            return false;
+        }
+        let (message, parity) = word.split_at(self.message_len());
+        self.parity_only(message) == parity
+    }
+
+    /// Decode a single word by checking that it's a codeword, with `is_codeword`, and stripping its
+    /// parity symbols. This detects errors, but doesn't correct any, so codes without a decoder of
+    /// their own can implement `Code::decode` with it.
+    fn decode_without_correction(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if word.len() != self.codeword_len() {
+            return Err(DecodeError::WrongLength {
+                expected: self.codeword_len(),
+                actual: word.len(),
+            });
+        }
+        if !self.is_codeword(word) {
+            return Err(DecodeError::TooManyErrors);
+        }
+        Ok(word[..self.message_len()].to_vec())
+    }
+
+    /// This code with its parity symbols moved from the end of the codeword, the `i`th landing at
+    /// `parity_positions[i]`. The message symbols fill the other positions, in order.
+    ///
+    /// # Panics
+    /// Panics unless `parity_positions` holds `parity_len` distinct positions, each less than
+    /// `codeword_len`.
+    fn with_positions(self, parity_positions: Vec<usize>) -> PositionedSystematicCode<Self>
+    where
+        Self: Sized,
+    {
+        let codeword_len = self.codeword_len();
+        assert_eq!(
+            parity_positions.len(),
+            self.parity_len(),
+            "expected a position for each of the {} parity symbols, got {}",
+            self.parity_len(),
+            parity_positions.len()
+        );
+        let mut is_parity = vec![false; codeword_len];
+        for &i in &parity_positions {
+            assert!(
+                i < codeword_len,
+                "parity position {} is out of bounds for codewords of length {}",
+                i,
+                codeword_len
+            );
+            assert!(!is_parity[i], "parity position {} is repeated", i);
+            is_parity[i] = true;
+        }
+        let message_positions = (0..codeword_len).filter(|&i| !is_parity[i]).collect();
+        PositionedSystematicCode {
+            code: self,
+            message_positions,
+            parity_positions,
+        }
+    }
 }
 
 pub trait SystematicLinearCode<F: Field, In: MatrixRows<F>>:
@@ -34,3 +123,469 @@ pub trait SystematicCodeFamily<F: Field, In: MatrixRows<F>>:
     SystematicCodeOrFamily<F, In> + CodeFamily<F, In>
 {
 }
+
+/// A systematic code whose parity symbols are at arbitrary positions of the codeword, rather than
+/// at its end, with the message symbols filling the rest in order. See
+/// `SystematicCode::with_positions`.
+pub struct PositionedSystematicCode<C> {
+    code: C,
+    message_positions: Vec<usize>,
+    parity_positions: Vec<usize>,
+}
+
+impl<C> PositionedSystematicCode<C> {
+    /// The code in its standard form, `[message || parity]`.
+    pub fn inner(&self) -> &C {
+        &self.code
+    }
+
+    /// The position in the codeword of each message symbol, in order.
+    pub fn message_positions(&self) -> &[usize] {
+        &self.message_positions
+    }
+
+    /// The position in the codeword of each parity symbol, in order.
+    pub fn parity_positions(&self) -> &[usize] {
+        &self.parity_positions
+    }
+}
+
+impl<F, In, C> CodeOrFamily<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicCode<F, In>,
+{
+    type Out = RowMajorMatrix<F>;
+
+    fn encode_batch(&self, messages: In) -> Self::Out {
+        let standard = self.code.encode_batch(messages).to_row_major_matrix();
+        let width = standard.width();
+
+        // Row `i` of `[message || parity]` moves to the `i`th position of the concatenated layout.
+        let mut values = vec![F::zero(); standard.values.len()];
+        let positions = self.message_positions.iter().chain(&self.parity_positions);
+        for (row, &i) in standard.rows().zip(positions) {
+            values[i * width..(i + 1) * width].copy_from_slice(row);
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F, In, C> Code<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicCode<F, In>,
+{
+    fn message_len(&self) -> usize {
+        self.code.message_len()
+    }
+
+    fn codeword_len(&self) -> usize {
+        self.code.codeword_len()
+    }
+
+    /// Moving symbols around doesn't change the distance between codewords.
+    fn distance(&self) -> usize {
+        self.code.distance()
+    }
+
+    /// Moves the symbols back into the standard layout, `[message || parity]`, and decodes that
+    /// with the inner code.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if word.len() != self.codeword_len() {
+            return Err(DecodeError::WrongLength {
+                expected: self.codeword_len(),
+                actual: word.len(),
+            });
+        }
+        let standard = self
+            .message_positions
+            .iter()
+            .chain(&self.parity_positions)
+            .map(|&i| word[i])
+            .collect::<Vec<_>>();
+        self.code.decode(&standard)
+    }
+}
+
+impl<F, In, C> SystematicCodeOrFamily<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicCode<F, In>,
+{
+}
+
+impl<F, In, C> SystematicCode<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicCode<F, In>,
+{
+    fn parity_only(&self, message: &[F]) -> Vec<F>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        self.code.parity_only(message)
+    }
+
+    fn is_codeword(&self, word: &[F]) -> bool
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        if word.len() != self.codeword_len() {
+            return false;
+        }
+        let message = self
+            .message_positions
+            .iter()
+            .map(|&i| word[i])
+            .collect::<Vec<_>>();
+        let parity = self.parity_positions.iter().map(|&i| word[i]);
+        self.code.parity_only(&message).into_iter().eq(parity)
+    }
+}
+
+impl<F, In, C> LinearCode<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicLinearCode<F, In>,
+{
+}
+
+impl<F, In, C> SystematicLinearCode<F, In> for PositionedSystematicCode<C>
+where
+    F: Field,
+    In: MatrixRows<F>,
+    C: SystematicLinearCode<F, In>,
+{
+}
+
+/// A systematic linear code given by an explicit generator matrix `[I | P]`, where `P` is the
+/// `k x (n - k)` parity matrix. A message `m`, viewed as a row vector, is encoded as `m || m P`.
+pub struct ParityMatrixCode<F: Field> {
+    parity_matrix: RowMajorMatrix<F>,
+}
+
+impl<F: Field> ParityMatrixCode<F> {
+    /// The code with generator matrix `[I | p]`. Its message length is `p.height()`, and its
+    /// codeword length is `p.height() + p.width()`.
+    pub fn from_parity_matrix(p: RowMajorMatrix<F>) -> Self {
+        Self { parity_matrix: p }
+    }
+
+    pub fn parity_matrix(&self) -> &RowMajorMatrix<F> {
+        &self.parity_matrix
+    }
+}
+
+impl<F: Field, In: MatrixRows<F>> CodeOrFamily<F, In> for ParityMatrixCode<F> {
+    type Out = RowMajorMatrix<F>;
+
+    fn encode_batch(&self, messages: In) -> Self::Out {
+        let p = &self.parity_matrix;
+        assert_eq!(messages.height(), p.height());
+        let width = messages.width();
+        let mut values = messages.to_row_major_matrix().values;
+
+        // Parity row `j` is the sum over message rows `i` of `P[i][j]` times row `i`.
+        let mut parity = vec![F::zero(); p.width() * width];
+        for (i, p_row) in p.rows().enumerate() {
+            let message_row = &values[i * width..(i + 1) * width];
+            for (&p_ij, parity_row) in p_row.iter().zip(parity.chunks_exact_mut(width)) {
+                for (x, &m) in parity_row.iter_mut().zip(message_row) {
+                    *x += p_ij * m;
+                }
+            }
+        }
+
+        values.extend(parity);
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F: Field, In: MatrixRows<F>> Code<F, In> for ParityMatrixCode<F> {
+    fn message_len(&self) -> usize {
+        self.parity_matrix.height()
+    }
+
+    fn codeword_len(&self) -> usize {
+        self.parity_matrix.height() + self.parity_matrix.width()
+    }
+
+    /// Decode a single word, correcting at most one erroneous symbol by syndrome decoding.
+    ///
+    /// The parity-check matrix of this code is `H = [-P^T | I]`, and the syndrome `H w` of a word
+    /// `w` with a single error `e` at position `i` is `e` times the `i`th column of `H`. The error
+    /// is located by finding the column the syndrome is a multiple of, which is unique as long as
+    /// no two columns of `H` are parallel, as holds for Hamming codes. A syndrome which isn't a
+    /// multiple of any column gives `DecodeError::TooManyErrors`; a word with more errors may
+    /// also be miscorrected.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        let p = &self.parity_matrix;
+        let (k, r) = (p.height(), p.width());
+        if word.len() != k + r {
+            return Err(DecodeError::WrongLength {
+                expected: k + r,
+                actual: word.len(),
+            });
+        }
+        let (message, parity) = word.split_at(k);
+
+        // The syndrome `m P - parity`, i.e. `-H w`.
+        let mut syndrome = parity.iter().map(|&x| -x).collect::<Vec<_>>();
+        for (p_row, &m) in p.rows().zip(message) {
+            for (s, &p_ij) in syndrome.iter_mut().zip(p_row) {
+                *s += p_ij * m;
+            }
+        }
+
+        let mut message = message.to_vec();
+        let Some(j) = syndrome.iter().position(|s| !s.is_zero()) else {
+            return Ok(message);
+        };
+        let s_j = syndrome[j];
+
+        // An error `e` in message symbol `i` gives the syndrome `e P[i]`.
+        for (i, p_row) in p.rows().enumerate() {
+            if p_row[j].is_zero() {
+                continue;
+            }
+            let e = s_j / p_row[j];
+            if p_row.iter().zip(&syndrome).all(|(&p_ij, &s)| e * p_ij == s) {
+                message[i] -= e;
+                return Ok(message);
+            }
+        }
+
+        // An error in a parity symbol gives a syndrome with a single nonzero entry, and leaves the
+        // message intact.
+        if syndrome[j + 1..].iter().all(|s| s.is_zero()) {
+            return Ok(message);
+        }
+        Err(DecodeError::TooManyErrors)
+    }
+}
+
+impl<F: Field, In: MatrixRows<F>> SystematicCodeOrFamily<F, In> for ParityMatrixCode<F> {}
+
+impl<F: Field, In: MatrixRows<F>> SystematicCode<F, In> for ParityMatrixCode<F> {}
+
+impl<F: Field, In: MatrixRows<F>> LinearCode<F, In> for ParityMatrixCode<F> {}
+
+impl<F: Field, In: MatrixRows<F>> SystematicLinearCode<F, In> for ParityMatrixCode<F> {}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::{AbstractField, PrimeField32};
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+    use crate::EncodeError;
+
+    type F = Mersenne31;
+    type In = RowMajorMatrix<F>;
+
+    fn to_field(xs: &[u32]) -> Vec<F> {
+        xs.iter().copied().map(F::from_canonical_u32).collect()
+    }
+
+    #[test]
+    fn test_hamming_7_4() {
+        // The parity matrix of the [7, 4] Hamming code over GF(2). Over a prime field it defines
+        // another [7, 4] code, and a binary message's codeword reduces mod 2 to its Hamming one.
+        #[rustfmt::skip]
+        let p = RowMajorMatrix::new(to_field(&[
+            1, 1, 0,
+            1, 0, 1,
+            0, 1, 1,
+            1, 1, 1,
+        ]), 3);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+        assert_eq!(<ParityMatrixCode<F> as Code<F, In>>::message_len(&code), 4);
+        assert_eq!(<ParityMatrixCode<F> as Code<F, In>>::codeword_len(&code), 7);
+
+        let codeword =
+            <ParityMatrixCode<F> as CodeOrFamily<F, In>>::encode(&code, &to_field(&[1, 0, 1, 1]));
+        assert_eq!(codeword, to_field(&[1, 0, 1, 1, 2, 3, 2]));
+        let reduced: Vec<u32> = codeword.iter().map(|x| x.as_canonical_u32() % 2).collect();
+        assert_eq!(reduced, [1, 0, 1, 1, 0, 1, 0]);
+        assert!(<ParityMatrixCode<F> as SystematicCode<F, In>>::is_codeword(
+            &code, &codeword
+        ));
+    }
+
+    #[test]
+    fn test_hamming_7_4_corrects_single_errors() {
+        #[rustfmt::skip]
+        let p = RowMajorMatrix::new(to_field(&[
+            1, 1, 0,
+            1, 0, 1,
+            0, 1, 1,
+            1, 1, 1,
+        ]), 3);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+        let message = to_field(&[1, 0, 1, 1]);
+        let codeword = <ParityMatrixCode<F> as CodeOrFamily<F, In>>::encode(&code, &message);
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &codeword),
+            Ok(message.clone())
+        );
+
+        // Flip each bit of the binary lift, and also try a non-binary error.
+        for i in 0..7 {
+            for e in [F::one(), -F::one(), F::from_canonical_u32(5)] {
+                let mut word = codeword.clone();
+                word[i] += e;
+                assert_eq!(
+                    <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &word),
+                    Ok(message.clone()),
+                    "error at {}",
+                    i
+                );
+            }
+        }
+
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &codeword[1..]),
+            Err(DecodeError::WrongLength {
+                expected: 7,
+                actual: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_batch_rejects_corrected_word() {
+        let p = RowMajorMatrix::new(to_field(&[1, 1]), 2);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+        let messages = RowMajorMatrix::new(to_field(&[3, 5]), 2);
+        let mut codewords =
+            <ParityMatrixCode<F> as CodeOrFamily<F, In>>::encode_batch(&code, messages.clone());
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode_batch(&code, &codewords),
+            Ok(messages)
+        );
+
+        // `decode` would correct the single error in the second word, but it isn't a codeword.
+        codewords.row_mut(1)[1] += F::one();
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &to_field(&[5, 6, 5])),
+            Ok(to_field(&[5]))
+        );
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode_batch(&code, &codewords),
+            Err(DecodeError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_decode_too_many_errors() {
+        // The repetition code of length 3. A word with three distinct symbols is at distance 2 from
+        // every codeword, whereas one with two equal symbols decodes to them.
+        let p = RowMajorMatrix::new(to_field(&[1, 1]), 2);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &to_field(&[0, 1, 1])),
+            Ok(to_field(&[1]))
+        );
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &to_field(&[2, 1, 2])),
+            Ok(to_field(&[2]))
+        );
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::decode(&code, &to_field(&[0, 1, 2])),
+            Err(DecodeError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_try_encode() {
+        let p = RowMajorMatrix::new(to_field(&[1, 2, 3, 4]), 2);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::try_encode(&code, &to_field(&[1, 1])),
+            Ok(to_field(&[1, 1, 4, 6]))
+        );
+        // `encode` would panic on this message, which is one symbol short.
+        assert_eq!(
+            <ParityMatrixCode<F> as Code<F, In>>::try_encode(&code, &to_field(&[1])),
+            Err(EncodeError::WrongLength {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_with_positions_interleaved() {
+        let p = RowMajorMatrix::new(to_field(&[1, 2, 0, 0, 1, 2, 2, 0, 1]), 3);
+        let standard = ParityMatrixCode::from_parity_matrix(p.clone());
+        let message = to_field(&[1, 2, 3]);
+        let standard_codeword =
+            <ParityMatrixCode<F> as CodeOrFamily<F, In>>::encode(&standard, &message);
+        assert_eq!(standard_codeword, to_field(&[1, 2, 3, 7, 4, 7]));
+
+        // Every other symbol is a parity symbol, starting with the second.
+        let code = <ParityMatrixCode<F> as SystematicCode<F, In>>::with_positions(
+            ParityMatrixCode::from_parity_matrix(p),
+            vec![1, 3, 5],
+        );
+        assert_eq!(code.message_positions(), [0, 2, 4]);
+        let codeword =
+            <PositionedSystematicCode<_> as CodeOrFamily<F, In>>::encode(&code, &message);
+        assert_eq!(codeword, to_field(&[1, 7, 2, 4, 3, 7]));
+        assert_eq!(
+            <PositionedSystematicCode<_> as SystematicCode<F, In>>::parity_only(&code, &message),
+            to_field(&[7, 4, 7])
+        );
+        assert!(
+            <PositionedSystematicCode<_> as SystematicCode<F, In>>::is_codeword(&code, &codeword)
+        );
+        assert!(
+            !<PositionedSystematicCode<_> as SystematicCode<F, In>>::is_codeword(
+                &code,
+                &standard_codeword
+            )
+        );
+        assert_eq!(
+            <PositionedSystematicCode<_> as Code<F, In>>::decode_batch(
+                &code,
+                &RowMajorMatrix::new_col(codeword)
+            ),
+            Ok(RowMajorMatrix::new_col(message))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "parity position 2 is repeated")]
+    fn test_with_positions_repeated() {
+        let p = RowMajorMatrix::new(to_field(&[1, 1]), 2);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+        <ParityMatrixCode<F> as SystematicCode<F, In>>::with_positions(code, vec![2, 2]);
+    }
+
+    #[test]
+    fn test_encode_batch() {
+        let p = RowMajorMatrix::new(to_field(&[1, 2, 3, 4]), 2);
+        let code = ParityMatrixCode::from_parity_matrix(p);
+
+        // Each column is encoded independently.
+        let messages = RowMajorMatrix::new(to_field(&[1, 0, 0, 1]), 2);
+        let codewords = <ParityMatrixCode<F> as CodeOrFamily<F, In>>::encode_batch(&code, messages);
+        assert_eq!(
+            codewords,
+            RowMajorMatrix::new(to_field(&[1, 0, 0, 1, 1, 3, 2, 4]), 2)
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::ExtensionField;
+use p3_matrix::dense::RowMajorMatrix;
+use serde::{Deserialize, Serialize};
+
+use crate::{OpenedValues, OpeningError, Pcs, Val};
+
+/// A PCS adapter which removes duplicate openings before delegating to an inner PCS.
+///
+/// Callers sometimes ask for the same opening more than once, e.g. when several parts of a
+/// protocol query the same out-of-domain point, or open the same commitment in separate rounds.
+/// Rounds over the same prover data are merged into one, and each matrix is opened at each
+/// distinct point once, so the inner PCS opens every commitment once, with one set of proofs per
+/// query, and never computes or proves the same value twice. The opened values are then expanded
+/// back to the requested shape. On the verifier side, rounds are merged as the proof records, and
+/// repeated claims about the same point are checked to agree before the distinct ones are passed on.
+///
+/// Matrices under different commitments, like a trace and its quotient, each still need their own
+/// values at a shared point; the inner PCS can share per-point work between them, as
+/// `TwoAdicFriPcs` does.
+pub struct BatchedPcs<P> {
+    inner: P,
+}
+
+impl<P> BatchedPcs<P> {
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+}
+
+/// The inner PCS's proof for the merged rounds, along with the merged round each requested round
+/// was opened in.
+#[derive(Serialize, Deserialize)]
+pub struct BatchedProof<Proof> {
+    pub inner: Proof,
+    pub round_map: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub enum BatchedPcsError<E> {
+    Inner(E),
+    /// A matrix was claimed to have different values at the same point.
+    InconsistentOpenings,
+    /// The proof's `round_map` doesn't merge the rounds being verified into rounds over the same
+    /// commitment, in order of first appearance.
+    InvalidRoundMap,
+}
+
+impl<E: OpeningError> OpeningError for BatchedPcsError<E> {
+    fn query_index(&self) -> Option<usize> {
+        match self {
+            Self::Inner(e) => e.query_index(),
+            Self::InconsistentOpenings | Self::InvalidRoundMap => None,
+        }
+    }
+
+    fn round_index(&self) -> Option<usize> {
+        match self {
+            Self::Inner(e) => e.round_index(),
+            Self::InconsistentOpenings | Self::InvalidRoundMap => None,
+        }
+    }
+}
+
+/// Returns the index of `pt` in `distinct`, pushing it first if it isn't there yet.
+fn push_distinct<T: Copy + PartialEq>(distinct: &mut Vec<T>, pt: T) -> usize {
+    distinct.iter().position(|d| *d == pt).unwrap_or_else(|| {
+        distinct.push(pt);
+        distinct.len() - 1
+    })
+}
+
+impl<Challenge, Challenger, P> Pcs<Challenge, Challenger> for BatchedPcs<P>
+where
+    Challenge: ExtensionField<Val<P::Domain>>,
+    P: Pcs<Challenge, Challenger>,
+    P::Commitment: PartialEq,
+{
+    type Domain = P::Domain;
+    type Commitment = P::Commitment;
+    type ProverData = P::ProverData;
+    type Proof = BatchedProof<P::Proof>;
+    type Error = BatchedPcsError<P::Error>;
+
+    fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
+        self.inner.natural_domain_for_degree(degree)
+    }
+
+    fn max_log_degree(&self) -> Option<usize> {
+        self.inner.max_log_degree()
+    }
+
+    fn commit(
+        &self,
+        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        self.inner.commit(evaluations)
+    }
+
+    fn get_evaluations_on_domain(
+        &self,
+        prover_data: &Self::ProverData,
+        idx: usize,
+        domain: Self::Domain,
+    ) -> RowMajorMatrix<Val<Self::Domain>> {
+        self.inner
+            .get_evaluations_on_domain(prover_data, idx, domain)
+    }
+
+    fn open(
+        &self,
+        // For each round,
+        rounds: Vec<(
+            &Self::ProverData,
+            // for each matrix,
+            Vec<
+                // points to open
+                Vec<Challenge>,
+            >,
+        )>,
+        challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        let mut merged_rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)> = Vec::new();
+        let mut round_map = Vec::with_capacity(rounds.len());
+        // For each requested round, matrix and point, the index of the point among its matrix's
+        // distinct points in the merged round.
+        let mut indices = Vec::with_capacity(rounds.len());
+        for (data, points_for_round) in rounds {
+            let merged = merged_rounds
+                .iter()
+                .position(|(d, _)| core::ptr::eq(*d, data))
+                .unwrap_or_else(|| {
+                    merged_rounds.push((data, vec![Vec::new(); points_for_round.len()]));
+                    merged_rounds.len() - 1
+                });
+            let indices_for_round: Vec<Vec<usize>> = merged_rounds[merged]
+                .1
+                .iter_mut()
+                .zip(points_for_round)
+                .map(|(distinct, points_for_mat)| {
+                    points_for_mat
+                        .into_iter()
+                        .map(|pt| push_distinct(distinct, pt))
+                        .collect()
+                })
+                .collect();
+            round_map.push(merged);
+            indices.push(indices_for_round);
+        }
+
+        let (distinct_values, inner) = self.inner.open(merged_rounds, challenger);
+
+        let opened_values = round_map
+            .iter()
+            .zip(indices)
+            .map(|(&merged, indices_for_round)| {
+                distinct_values[merged]
+                    .iter()
+                    .zip(indices_for_round)
+                    .map(|(values_for_mat, indices_for_mat)| {
+                        indices_for_mat
+                            .into_iter()
+                            .map(|i| values_for_mat[i].clone())
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        (opened_values, BatchedProof { inner, round_map })
+    }
+
+    fn verify(
+        &self,
+        // For each round:
+        rounds: Vec<(
+            Self::Commitment,
+            // for each matrix:
+            Vec<(
+                // its domain,
+                Self::Domain,
+                // for each point:
+                Vec<(
+                    // the point,
+                    Challenge,
+                    // values at the point
+                    Vec<Challenge>,
+                )>,
+            )>,
+        )>,
+        proof: &Self::Proof,
+        challenger: &mut Challenger,
+    ) -> Result<(), Self::Error> {
+        if proof.round_map.len() != rounds.len() {
+            return Err(BatchedPcsError::InvalidRoundMap);
+        }
+
+        #[allow(clippy::type_complexity)]
+        let mut merged_rounds: Vec<(
+            Self::Commitment,
+            Vec<(Self::Domain, Vec<(Challenge, Vec<Challenge>)>)>,
+        )> = Vec::new();
+        for ((commit, mats), &merged) in rounds.into_iter().zip(&proof.round_map) {
+            if merged == merged_rounds.len() {
+                let empty_mats = mats
+                    .iter()
+                    .map(|(domain, _)| (*domain, Vec::new()))
+                    .collect();
+                merged_rounds.push((commit, empty_mats));
+            } else if merged > merged_rounds.len()
+                || merged_rounds[merged].0 != commit
+                || merged_rounds[merged].1.len() != mats.len()
+            {
+                return Err(BatchedPcsError::InvalidRoundMap);
+            }
+
+            for ((_, distinct), (_, points_and_values)) in
+                merged_rounds[merged].1.iter_mut().zip(mats)
+            {
+                for (pt, values) in points_and_values {
+                    match distinct.iter().find(|(d, _)| *d == pt) {
+                        Some((_, existing)) if *existing != values => {
+                            return Err(BatchedPcsError::InconsistentOpenings);
+                        }
+                        Some(_) => {}
+                        None => distinct.push((pt, values)),
+                    }
+                }
+            }
+        }
+
+        self.inner
+            .verify(merged_rounds, &proof.inner, challenger)
+            .map_err(BatchedPcsError::Inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::cell::RefCell;
+    use core::marker::PhantomData;
+
+    use p3_baby_bear::BabyBear;
+    use p3_dft::Radix2DitParallel;
+    use p3_field::AbstractField;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::testing::{NoChallenger, TrivialPcs};
+
+    type F = BabyBear;
+    type Inner = TrivialPcs<F, Radix2DitParallel>;
+
+    fn inner() -> Inner {
+        TrivialPcs {
+            dft: Radix2DitParallel,
+            log_n: 3,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn pcs() -> BatchedPcs<Inner> {
+        BatchedPcs::new(inner())
+    }
+
+    /// Delegates to an inner PCS, recording how many points of each matrix of each round the last
+    /// call to `open` asked for.
+    struct CountingPcs<P> {
+        inner: P,
+        opened: RefCell<Vec<Vec<usize>>>,
+    }
+
+    impl<P> CountingPcs<P> {
+        fn new(inner: P) -> Self {
+            Self {
+                inner,
+                opened: RefCell::default(),
+            }
+        }
+
+        fn opened(&self) -> Vec<Vec<usize>> {
+            self.opened.borrow().clone()
+        }
+    }
+
+    impl<Challenge, Challenger, P> Pcs<Challenge, Challenger> for CountingPcs<P>
+    where
+        Challenge: ExtensionField<Val<P::Domain>>,
+        P: Pcs<Challenge, Challenger>,
+    {
+        type Domain = P::Domain;
+        type Commitment = P::Commitment;
+        type ProverData = P::ProverData;
+        type Proof = P::Proof;
+        type Error = P::Error;
+
+        fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
+            self.inner.natural_domain_for_degree(degree)
+        }
+
+        fn commit(
+            &self,
+            evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
+        ) -> (Self::Commitment, Self::ProverData) {
+            self.inner.commit(evaluations)
+        }
+
+        fn get_evaluations_on_domain(
+            &self,
+            prover_data: &Self::ProverData,
+            idx: usize,
+            domain: Self::Domain,
+        ) -> RowMajorMatrix<Val<Self::Domain>> {
+            self.inner
+                .get_evaluations_on_domain(prover_data, idx, domain)
+        }
+
+        fn open(
+            &self,
+            rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)>,
+            challenger: &mut Challenger,
+        ) -> (OpenedValues<Challenge>, Self::Proof) {
+            *self.opened.borrow_mut() = rounds
+                .iter()
+                .map(|(_, points)| points.iter().map(Vec::len).collect())
+                .collect();
+            self.inner.open(rounds, challenger)
+        }
+
+        fn verify(
+            &self,
+            rounds: Vec<(
+                Self::Commitment,
+                Vec<(Self::Domain, Vec<(Challenge, Vec<Challenge>)>)>,
+            )>,
+            proof: &Self::Proof,
+            challenger: &mut Challenger,
+        ) -> Result<(), Self::Error> {
+            self.inner.verify(rounds, proof, challenger)
+        }
+    }
+
+    #[test]
+    fn test_push_distinct() {
+        let mut distinct = vec![];
+        let indices: Vec<_> = [5, 7, 5, 5, 9, 7]
+            .into_iter()
+            .map(|pt| push_distinct(&mut distinct, pt))
+            .collect();
+        assert_eq!(distinct, [5, 7, 9]);
+        assert_eq!(indices, [0, 1, 0, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_open_and_verify_repeated_points() {
+        let pcs = pcs();
+        let mut rng = thread_rng();
+        let domain =
+            <BatchedPcs<Inner> as Pcs<F, NoChallenger>>::natural_domain_for_degree(&pcs, 8);
+        let evals = RowMajorMatrix::new((0..16).map(|_| rng.gen()).collect(), 2);
+        let (commit, data) = Pcs::<F, NoChallenger>::commit(&pcs, vec![(domain, evals)]);
+
+        let (zeta, other): (F, F) = (rng.gen(), rng.gen());
+        let points = vec![zeta, other, zeta];
+        let (opened_values, proof) =
+            pcs.open(vec![(&data, vec![points.clone()])], &mut NoChallenger);
+        assert_eq!(opened_values[0][0].len(), 3);
+        assert_eq!(opened_values[0][0][0], opened_values[0][0][2]);
+
+        let claims: Vec<_> = points
+            .into_iter()
+            .zip(opened_values[0][0].clone())
+            .collect();
+        pcs.verify(
+            vec![(commit.clone(), vec![(domain, claims.clone())])],
+            &proof,
+            &mut NoChallenger,
+        )
+        .expect("verification failed");
+
+        // Contradictory claims about the same point are rejected.
+        let mut bad_claims = claims;
+        bad_claims[2].1[0] += F::one();
+        let result = pcs.verify(
+            vec![(commit, vec![(domain, bad_claims)])],
+            &proof,
+            &mut NoChallenger,
+        );
+        assert!(matches!(result, Err(BatchedPcsError::InconsistentOpenings)));
+    }
+
+    #[test]
+    fn test_merge_rounds_over_the_same_data() {
+        let pcs = BatchedPcs::new(CountingPcs::new(inner()));
+        let mut rng = thread_rng();
+        let domain =
+            <BatchedPcs<CountingPcs<Inner>> as Pcs<F, NoChallenger>>::natural_domain_for_degree(
+                &pcs, 8,
+            );
+        let trace = RowMajorMatrix::new((0..16).map(|_| rng.gen()).collect(), 2);
+        let quotient = RowMajorMatrix::new_col((0..8).map(|_| rng.gen()).collect());
+        let (trace_commit, trace_data) =
+            Pcs::<F, NoChallenger>::commit(&pcs, vec![(domain, trace)]);
+        let (quotient_commit, quotient_data) =
+            Pcs::<F, NoChallenger>::commit(&pcs, vec![(domain, quotient)]);
+
+        // The trace and the quotient are both opened at `zeta`, and the trace is opened again, at
+        // `zeta` and another point, e.g. by another part of the protocol.
+        let (zeta, zeta_next, other): (F, F, F) = (rng.gen(), rng.gen(), rng.gen());
+        let points = [vec![zeta, zeta_next], vec![zeta], vec![zeta, other]];
+        let rounds = vec![
+            (&trace_data, vec![points[0].clone()]),
+            (&quotient_data, vec![points[1].clone()]),
+            (&trace_data, vec![points[2].clone()]),
+        ];
+
+        let (naive_values, ()) = pcs.inner.open(rounds.clone(), &mut NoChallenger);
+        assert_eq!(pcs.inner.opened(), [vec![2], vec![1], vec![2]]);
+
+        // The inner PCS opens the trace once, at its three distinct points.
+        let (opened_values, proof) = pcs.open(rounds, &mut NoChallenger);
+        assert_eq!(pcs.inner.opened(), [vec![3], vec![1]]);
+        assert_eq!(proof.round_map, [0, 1, 0]);
+        assert_eq!(opened_values, naive_values);
+
+        let claims: Vec<_> = [trace_commit.clone(), quotient_commit, trace_commit]
+            .into_iter()
+            .zip(points)
+            .zip(opened_values)
+            .map(|((commit, points), values)| {
+                let values = values.into_iter().next().unwrap();
+                (
+                    commit,
+                    vec![(domain, points.into_iter().zip(values).collect())],
+                )
+            })
+            .collect();
+        pcs.verify(claims.clone(), &proof, &mut NoChallenger)
+            .expect("verification failed");
+
+        // Rounds over different commitments can't be merged.
+        let bad_proof = BatchedProof {
+            inner: (),
+            round_map: vec![0, 0, 0],
+        };
+        let result = pcs.verify(claims, &bad_proof, &mut NoChallenger);
+        assert!(matches!(result, Err(BatchedPcsError::InvalidRoundMap)));
+    }
+}
@@ -40,11 +40,11 @@ where
         prover_data: &Self::ProverData,
     ) -> (Vec<Vec<EF>>, Self::Proof) {
         let (opened_base_values, proof) = self.inner.open_batch(index, prover_data);
-        let opened_ext_values = opened_base_values
-            .into_iter()
-            .map(|row| row.chunks(EF::D).map(EF::from_base_slice).collect())
-            .collect();
-        (opened_ext_values, proof)
+        (to_ext_rows(opened_base_values), proof)
+    }
+
+    fn root(&self, prover_data: &Self::ProverData) -> Self::Commitment {
+        self.inner.root(prover_data)
     }
 
     fn get_matrices<'a>(&'a self, prover_data: &'a Self::ProverData) -> Vec<Self::Mat<'a>> {
@@ -66,27 +66,79 @@ where
         opened_values: &[Vec<EF>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
-        let opened_base_values: Vec<Vec<F>> = opened_values
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .flat_map(|el| el.as_base_slice())
-                    .copied()
-                    .collect()
-            })
-            .collect();
-        let base_dimensions = dimensions
+        self.inner.verify_batch(
+            commit,
+            &to_base_dimensions::<F, EF>(dimensions),
+            index,
+            &to_base_rows(opened_values),
+            proof,
+        )
+    }
+
+    fn open_multi_batch(
+        &self,
+        indices: &[usize],
+        prover_data: &Self::ProverData,
+    ) -> (Vec<Vec<Vec<EF>>>, Vec<Self::Proof>) {
+        let (opened_base_values, proofs) = self.inner.open_multi_batch(indices, prover_data);
+        let opened_ext_values = opened_base_values.into_iter().map(to_ext_rows).collect();
+        (opened_ext_values, proofs)
+    }
+
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<EF>>],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Self::Error> {
+        let opened_base_values = opened_values
             .iter()
-            .map(|dim| Dimensions {
-                width: dim.width * EF::D,
-                height: dim.height,
-            })
+            .map(|rows| to_base_rows(rows))
             .collect::<Vec<_>>();
-        self.inner
-            .verify_batch(commit, &base_dimensions, index, &opened_base_values, proof)
+        self.inner.verify_multi_batch(
+            commit,
+            &to_base_dimensions::<F, EF>(dimensions),
+            indices,
+            &opened_base_values,
+            proofs,
+        )
     }
 }
 
+/// Packs each row of base field elements into extension elements, `EF::D` at a time.
+fn to_ext_rows<F: Field, EF: ExtensionField<F>>(rows: Vec<Vec<F>>) -> Vec<Vec<EF>> {
+    rows.into_iter()
+        .map(|row| row.chunks(EF::D).map(EF::from_base_slice).collect())
+        .collect()
+}
+
+/// Flattens each row of extension elements into their base field coefficients.
+fn to_base_rows<F: Field, EF: ExtensionField<F>>(rows: &[Vec<EF>]) -> Vec<Vec<F>> {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .flat_map(|el| el.as_base_slice())
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+/// The dimensions of the base field matrices underlying extension matrices of `dimensions`.
+fn to_base_dimensions<F: Field, EF: ExtensionField<F>>(
+    dimensions: &[Dimensions],
+) -> Vec<Dimensions> {
+    dimensions
+        .iter()
+        .map(|dim| Dimensions {
+            width: dim.width * EF::D,
+            height: dim.height,
+        })
+        .collect()
+}
+
 impl<F, EF, InnerMmcs> DirectMmcs<EF> for ExtensionMmcs<F, EF, InnerMmcs>
 where
     F: Field,
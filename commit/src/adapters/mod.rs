@@ -1,5 +1,13 @@
 //! Adapters for converting between different types of commitment schemes.
+//!
+//! There is deliberately no `Pcs` counterpart of `ExtensionMmcs`, i.e. no `BaseExtensionPcs`
+//! wrapping a base field PCS to open at extension points. `Pcs<Challenge, _>` already commits to
+//! matrices over the base field `Val<Self::Domain>` and opens them at points of any extension
+//! `Challenge`, and every PCS here implements it for all extensions, so such an adapter could only
+//! delegate. See `Pcs`, and its test opening a base field commitment at an extension point.
 
+mod batched_pcs;
 mod extension_mmcs;
 
+pub use batched_pcs::*;
 pub use extension_mmcs::*;
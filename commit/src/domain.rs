@@ -30,7 +30,8 @@ pub trait PolynomialSpace: Copy {
     // for both prover/verifier determinism and LDE caching.
     fn create_disjoint_domain(&self, min_size: usize) -> Self;
 
-    // Split this domain into `num_chunks` even chunks.
+    // Split this domain into `num_chunks` even chunks, each a domain in its own right, which
+    // together partition this domain's points.
     fn split_domains(&self, num_chunks: usize) -> Vec<Self>;
     // Split the evals into chunks of evals, corresponding to each domain
     // from `split_domains`.
@@ -78,18 +79,35 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         Some(x * self.gen())
     }
 
+    /// The coset `g <w>` of the field's multiplicative generator `g`, whatever this domain's shift
+    /// is, since that's the coset a PCS like `TwoAdicFriPcs` takes its LDEs over.
+    ///
+    /// # Panics
+    /// Panics if the coset isn't disjoint from this domain, i.e. if this domain's shift is in it.
+    /// This can't happen for a shift in the two-adic subgroup.
     fn create_disjoint_domain(&self, min_size: usize) -> Self {
-        Self {
-            log_n: log2_ceil_usize(min_size),
-            shift: self.shift * Val::generator(),
-        }
+        let log_n = log2_ceil_usize(min_size);
+        let shift = Val::generator();
+        assert_ne!(
+            (self.shift * shift.inverse()).exp_power_of_2(log_n),
+            Val::one(),
+            "no disjoint domain of size 2^{} with the canonical shift",
+            log_n
+        );
+        Self { log_n, shift }
     }
     fn zp_at_point<Ext: ExtensionField<Val>>(&self, point: Ext) -> Ext {
         (point * self.shift.inverse()).exp_power_of_2(self.log_n) - Ext::one()
     }
 
+    /// The `i`th chunk is the coset `shift g^i <g^num_chunks>`, which holds the points
+    /// `i, i + num_chunks, i + 2 num_chunks, ...` of this domain, matching `split_evals`.
     fn split_domains(&self, num_chunks: usize) -> Vec<Self> {
         let log_chunks = log2_strict_usize(num_chunks);
+        assert!(
+            log_chunks <= self.log_n,
+            "can't split a domain into more chunks than points"
+        );
         (0..num_chunks)
             .map(|i| Self {
                 log_n: self.log_n - log_chunks,
@@ -121,11 +139,13 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
     }
 
     fn selectors_on_coset(&self, coset: Self) -> LagrangeSelectors<Vec<Val>> {
-        assert_eq!(self.shift, Val::one());
         assert!(coset.log_n >= self.log_n);
         let rate_bits = coset.log_n - self.log_n;
 
-        let s_pow_n = coset.shift.exp_power_of_2(self.log_n);
+        // As in `selectors_at_point`, the coset's points are unshifted by this domain's shift, so
+        // that this domain becomes the subgroup `<gen>`.
+        let unshifted_shift = coset.shift * self.shift.inverse();
+        let s_pow_n = unshifted_shift.exp_power_of_2(self.log_n);
         // evals of Z_H(X) = X^n - 1
         let evals = Val::two_adic_generator(rate_bits)
             .powers()
@@ -133,7 +153,7 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
             .map(|x| s_pow_n * x - Val::one())
             .collect_vec();
 
-        let xs = cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, 1 << coset.log_n)
+        let xs = cyclic_subgroup_coset_known_order(coset.gen(), unshifted_shift, 1 << coset.log_n)
             .collect_vec();
 
         let single_point_selector = |i: u64| {
@@ -161,3 +181,90 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField32};
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn points(domain: &TwoAdicMultiplicativeCoset<F>) -> Vec<F> {
+        cyclic_subgroup_coset_known_order(domain.gen(), domain.shift, domain.size()).collect()
+    }
+
+    #[test]
+    fn test_split_domains() {
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 5,
+            shift: F::generator(),
+        };
+        let all_points = points(&domain);
+
+        for log_chunks in 0..=3 {
+            let chunks = domain.split_domains(1 << log_chunks);
+            assert_eq!(chunks.len(), 1 << log_chunks);
+
+            let mut union = BTreeSet::new();
+            for chunk in &chunks {
+                assert_eq!(chunk.size(), domain.size() >> log_chunks);
+                for x in points(chunk) {
+                    // The chunks are disjoint.
+                    assert!(union.insert(x.as_canonical_u32()));
+                }
+            }
+            let expected: BTreeSet<_> = all_points.iter().map(|x| x.as_canonical_u32()).collect();
+            assert_eq!(union, expected);
+        }
+    }
+
+    #[test]
+    fn test_selectors_on_coset_of_shifted_domain() {
+        // The coset of <g^2> which isn't <g^2> itself.
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 3,
+            shift: F::two_adic_generator(4),
+        };
+        let coset = domain.create_disjoint_domain(1 << 5);
+        assert_eq!(coset.shift, F::generator());
+
+        let sels = domain.selectors_on_coset(coset);
+        for (i, x) in points(&coset).into_iter().enumerate() {
+            let expected = domain.selectors_at_point(x);
+            assert_eq!(sels.is_first_row[i], expected.is_first_row);
+            assert_eq!(sels.is_last_row[i], expected.is_last_row);
+            assert_eq!(sels.is_transition[i], expected.is_transition);
+            assert_eq!(sels.inv_zeroifier[i], expected.inv_zeroifier);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no disjoint domain")]
+    fn test_create_disjoint_domain_overlapping() {
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 3,
+            shift: F::generator(),
+        };
+        domain.create_disjoint_domain(1 << 5);
+    }
+
+    #[test]
+    fn test_split_domains_matches_split_evals() {
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 4,
+            shift: F::generator(),
+        };
+        let evals = RowMajorMatrix::new_col(points(&domain));
+
+        // Evaluating the identity over each chunk gives that chunk's share of the evaluations.
+        let chunks = domain.split_domains(4);
+        let chunk_evals = domain.split_evals(4, evals);
+        for (chunk, chunk_evals) in chunks.iter().zip(chunk_evals) {
+            assert_eq!(chunk_evals.values, points(chunk));
+        }
+    }
+}
@@ -4,6 +4,11 @@
 
 extern crate alloc;
 
+// `testing::InstrumentedMmcs` shares its log behind a `Mutex`, so it can be handed to PCSs which
+// need their MMCSs to be `Sync`.
+#[cfg(any(test, feature = "test-utils"))]
+extern crate std;
+
 mod adapters;
 mod domain;
 mod mmcs;
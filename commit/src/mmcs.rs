@@ -21,7 +21,7 @@ use serde::Serialize;
 /// streaming manner.
 pub trait Mmcs<T>: Clone {
     type ProverData;
-    type Commitment: Clone + Serialize + DeserializeOwned;
+    type Commitment: Clone + PartialEq + Serialize + DeserializeOwned;
     type Proof: Serialize + DeserializeOwned;
     type Error: Debug;
     type Mat<'a>: MatrixRows<T> + Sync
@@ -38,6 +38,9 @@ pub trait Mmcs<T>: Clone {
         prover_data: &Self::ProverData,
     ) -> (Vec<Vec<T>>, Self::Proof);
 
+    /// The commitment to the matrices in `prover_data`, i.e. the one `commit` returned with it.
+    fn root(&self, prover_data: &Self::ProverData) -> Self::Commitment;
+
     /// Get the matrices that were committed to.
     fn get_matrices<'a>(&'a self, prover_data: &'a Self::ProverData) -> Vec<Self::Mat<'a>>;
 
@@ -48,6 +51,24 @@ pub trait Mmcs<T>: Clone {
             .collect()
     }
 
+    fn get_matrix_widths(&self, prover_data: &Self::ProverData) -> Vec<usize> {
+        self.get_matrices(prover_data)
+            .iter()
+            .map(|matrix| matrix.width())
+            .collect()
+    }
+
+    /// The dimensions of each committed matrix, in the order they were committed.
+    ///
+    /// A verifier only has the commitment, so it must get these from elsewhere, e.g. the AIR, to
+    /// pass to `verify_batch`; this is for checking that they match what was committed.
+    fn get_matrix_dimensions(&self, prover_data: &Self::ProverData) -> Vec<Dimensions> {
+        self.get_matrices(prover_data)
+            .iter()
+            .map(|matrix| matrix.dimensions())
+            .collect()
+    }
+
     /// Get the largest height of any committed matrix.
     fn get_max_height(&self, prover_data: &Self::ProverData) -> usize {
         self.get_matrix_heights(prover_data)
@@ -69,6 +90,50 @@ pub trait Mmcs<T>: Clone {
         opened_values: &[Vec<T>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error>;
+
+    /// Opens the rows at each of `indices`, like calling `open_batch` once per index. Returns the
+    /// openings, one per index in the order requested, along with proofs for them.
+    ///
+    /// The default implementation calls `open_batch` for each index, giving one proof per index.
+    /// Implementations may share work between indices and return fewer proofs, e.g. a Merkle tree
+    /// needs to send a sibling shared by several authentication paths only once. Indices may
+    /// repeat and need not be sorted.
+    #[allow(clippy::type_complexity)]
+    fn open_multi_batch(
+        &self,
+        indices: &[usize],
+        prover_data: &Self::ProverData,
+    ) -> (Vec<Vec<Vec<T>>>, Vec<Self::Proof>) {
+        indices
+            .iter()
+            .map(|&index| self.open_batch(index, prover_data))
+            .unzip()
+    }
+
+    /// Verifies openings produced by `open_multi_batch`. `indices` must be the same indices, in
+    /// the same order, that were passed to `open_multi_batch`.
+    ///
+    /// # Panics
+    /// The default implementation panics unless there is one opening and one proof per index.
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<T>>],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Self::Error> {
+        assert_eq!(
+            opened_values.len(),
+            indices.len(),
+            "expected an opening per index"
+        );
+        assert_eq!(proofs.len(), indices.len(), "expected a proof per index");
+        for ((&index, opened_values), proof) in indices.iter().zip(opened_values).zip(proofs) {
+            self.verify_batch(commit, dimensions, index, opened_values, proof)?;
+        }
+        Ok(())
+    }
 }
 
 /// An MMCS over explicit inputs which are supplied upfront.
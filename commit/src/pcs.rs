@@ -13,6 +13,10 @@ use crate::PolynomialSpace;
 pub type Val<D> = <D as PolynomialSpace>::Val;
 
 /// A (not necessarily hiding) polynomial commitment scheme, for committing to (batches of) polynomials
+///
+/// Polynomials are committed over the base field `Val<Self::Domain>`, and opened at points of the
+/// extension field `Challenge`, so a base field trace never needs to be lifted to the extension to
+/// be committed to. One PCS can implement this trait for any number of extensions.
 // TODO: Should we have a super-trait for weakly-binding PCSs, like FRI outside unique decoding radius?
 pub trait Pcs<Challenge, Challenger>
 where
@@ -29,17 +33,39 @@ where
     /// The opening argument.
     type Proof: Serialize + DeserializeOwned;
 
-    type Error: Debug;
+    type Error: OpeningError;
 
     /// This should return a coset domain (s.t. Domain::next_point returns Some)
     fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain;
 
+    /// The largest `log2` of a degree this PCS supports, if it has such a bound. Verifiers can use
+    /// this to reject proofs claiming a larger degree before deriving any domains from it.
+    fn max_log_degree(&self) -> Option<usize> {
+        None
+    }
+
     #[allow(clippy::type_complexity)]
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
     ) -> (Self::Commitment, Self::ProverData);
 
+    /// Commits to several batches of matrices under a single commitment, as if their matrices had
+    /// been passed to `commit` in order. So when opening, matrix `i` of batch `j` is the matrix at
+    /// index `i` plus the number of matrices in batches `0..j`.
+    ///
+    /// This saves a commitment, and a round of Merkle proofs per query, when the batches are
+    /// available at the same time. Matrices of different heights can share a commitment, so any
+    /// batches can be combined. Note that a batch which depends on challenges sampled after
+    /// another was committed, like the quotient chunks in `uni-stark`, can't share its commitment.
+    #[allow(clippy::type_complexity)]
+    fn commit_batches(
+        &self,
+        batches: Vec<Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        self.commit(batches.into_iter().flatten().collect())
+    }
+
     fn get_evaluations_on_domain(
         &self,
         prover_data: &Self::ProverData,
@@ -85,7 +111,76 @@ where
     ) -> Result<(), Self::Error>;
 }
 
+/// An error from `Pcs::verify`, which can report where the opening argument failed.
+pub trait OpeningError: Debug {
+    /// The index of the query which failed, if the failure was specific to one query.
+    fn query_index(&self) -> Option<usize> {
+        None
+    }
+
+    /// The index, among the rounds passed to `verify`, of the commitment whose opening failed, if
+    /// the failure was specific to one commitment.
+    fn round_index(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl OpeningError for () {}
+
 pub type OpenedValues<F> = Vec<OpenedValuesForRound<F>>;
 pub type OpenedValuesForRound<F> = Vec<OpenedValuesForMatrix<F>>;
 pub type OpenedValuesForMatrix<F> = Vec<OpenedValuesForPoint<F>>;
 pub type OpenedValuesForPoint<F> = Vec<F>;
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::marker::PhantomData;
+
+    use p3_baby_bear::BabyBear;
+    use p3_dft::Radix2DitParallel;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::{AbstractExtensionField, AbstractField, TwoAdicField};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::testing::{NoChallenger, TrivialPcs};
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 4>;
+    type MyPcs = TrivialPcs<F, Radix2DitParallel>;
+
+    #[test]
+    fn test_open_base_commitment_at_extension_point() {
+        let pcs = MyPcs {
+            dft: Radix2DitParallel,
+            log_n: 3,
+            _phantom: PhantomData,
+        };
+        let domain = <MyPcs as Pcs<EF, NoChallenger>>::natural_domain_for_degree(&pcs, 8);
+
+        // The evaluations of `x^2 + 3` on the domain, which is the subgroup of order 8.
+        let evals = F::two_adic_generator(3)
+            .powers()
+            .take(8)
+            .map(|x| x.square() + F::from_canonical_u32(3))
+            .collect();
+        let evals = RowMajorMatrix::new_col(evals);
+        let (commit, data) = Pcs::<EF, NoChallenger>::commit(&pcs, vec![(domain, evals)]);
+
+        let zeta: EF = thread_rng().gen();
+        let (opened_values, proof) = pcs.open(vec![(&data, vec![vec![zeta]])], &mut NoChallenger);
+        let value = opened_values[0][0][0][0];
+        assert_eq!(
+            value,
+            zeta.square() + EF::from_base(F::from_canonical_u32(3))
+        );
+
+        pcs.verify(
+            vec![(commit, vec![(domain, vec![(zeta, vec![value])])])],
+            &proof,
+            &mut NoChallenger,
+        )
+        .expect("verification failed");
+    }
+}
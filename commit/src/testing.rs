@@ -1,16 +1,18 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
 use p3_challenger::CanSample;
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::{Matrix, MatrixRowSlices, MatrixRows};
+use p3_matrix::{Dimensions, Matrix, MatrixRowSlices, MatrixRows};
+use p3_symmetric::{CryptographicHasher, Hash};
 use p3_util::log2_strict_usize;
 use serde::{Deserialize, Serialize};
 
-use crate::{OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use crate::{DirectMmcs, Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
 
 /// A trivial PCS: its commitment is simply the coefficients of each poly.
 pub struct TrivialPcs<Val: TwoAdicField, Dft: TwoAdicSubgroupDft<Val>> {
@@ -20,6 +22,18 @@ pub struct TrivialPcs<Val: TwoAdicField, Dft: TwoAdicSubgroupDft<Val>> {
     pub _phantom: PhantomData<Val>,
 }
 
+/// A challenger with nothing to sample, for testing PCSs such as `TrivialPcs` which never sample.
+///
+/// # Panics
+/// Panics if anything samples from it.
+pub struct NoChallenger;
+
+impl<T> CanSample<T> for NoChallenger {
+    fn sample(&mut self) -> T {
+        unreachable!("NoChallenger has nothing to sample")
+    }
+}
+
 pub fn eval_coeffs_at_pt<F: Field, EF: ExtensionField<F>>(
     coeffs: &RowMajorMatrix<F>,
     x: EF,
@@ -171,3 +185,221 @@ where
         Ok(())
     }
 }
+
+/// A mock PCS for fast tests of AIRs and of `prove`/`verify` plumbing.
+///
+/// **This provides no soundness whatsoever, and must only be used in tests.** A commitment is
+/// just a hash of the committed evaluations, openings are computed honestly from the stored
+/// polynomials, and `verify` accepts any claimed openings without checking them against the
+/// commitment. Constraint checks made by the caller on the opened values still catch a trace which
+/// doesn't satisfy its AIR, which is what makes this useful for testing AIRs.
+pub struct MockPcs<Val, Dft, H, const DIGEST_ELEMS: usize> {
+    dft: Dft,
+    hash: H,
+    _phantom: PhantomData<Val>,
+}
+
+impl<Val, Dft, H, const DIGEST_ELEMS: usize> MockPcs<Val, Dft, H, DIGEST_ELEMS> {
+    pub fn new(dft: Dft, hash: H) -> Self {
+        Self {
+            dft,
+            hash,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Val, Dft, H, Challenge, Challenger, const DIGEST_ELEMS: usize> Pcs<Challenge, Challenger>
+    for MockPcs<Val, Dft, H, DIGEST_ELEMS>
+where
+    Val: TwoAdicField,
+    Challenge: ExtensionField<Val>,
+    Dft: TwoAdicSubgroupDft<Val>,
+    H: CryptographicHasher<Val, [Val; DIGEST_ELEMS]>,
+    [Val; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    type Domain = TwoAdicMultiplicativeCoset<Val>;
+    type Commitment = Hash<Val, Val, DIGEST_ELEMS>;
+    /// The coefficients of each committed polynomial.
+    type ProverData = Vec<RowMajorMatrix<Val>>;
+    type Proof = ();
+    type Error = ();
+
+    fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
+        TwoAdicMultiplicativeCoset {
+            log_n: log2_strict_usize(degree),
+            shift: Val::one(),
+        }
+    }
+
+    fn commit(
+        &self,
+        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let commit = self.hash.hash_iter(
+            evaluations
+                .iter()
+                .flat_map(|(_, evals)| evals.values.iter().copied()),
+        );
+        let coeffs = evaluations
+            .into_iter()
+            .map(|(domain, evals)| {
+                assert_eq!(domain.size(), evals.height());
+                let mut coeffs = self.dft.idft_batch(evals);
+                coeffs
+                    .rows_mut()
+                    .zip(domain.shift.inverse().powers())
+                    .for_each(|(row, weight)| {
+                        row.iter_mut().for_each(|coeff| {
+                            *coeff *= weight;
+                        })
+                    });
+                coeffs
+            })
+            .collect();
+        (commit.into(), coeffs)
+    }
+
+    fn get_evaluations_on_domain(
+        &self,
+        prover_data: &Self::ProverData,
+        idx: usize,
+        domain: Self::Domain,
+    ) -> RowMajorMatrix<Val> {
+        let mut coeffs = prover_data[idx].clone();
+        assert!(domain.size() >= coeffs.height());
+        coeffs
+            .values
+            .resize(domain.size() * coeffs.width(), Val::zero());
+        self.dft
+            .coset_dft_batch(coeffs, domain.shift)
+            .to_row_major_matrix()
+    }
+
+    fn open(
+        &self,
+        // For each round,
+        rounds: Vec<(
+            &Self::ProverData,
+            // for each matrix,
+            Vec<
+                // points to open
+                Vec<Challenge>,
+            >,
+        )>,
+        _challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        let opened_values = rounds
+            .into_iter()
+            .map(|(coeffs_for_round, points_for_round)| {
+                coeffs_for_round
+                    .iter()
+                    .zip(points_for_round)
+                    .map(|(coeffs_for_mat, points_for_mat)| {
+                        points_for_mat
+                            .into_iter()
+                            .map(|pt| eval_coeffs_at_pt(coeffs_for_mat, pt))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        (opened_values, ())
+    }
+
+    fn verify(
+        &self,
+        // For each round:
+        _rounds: Vec<(
+            Self::Commitment,
+            // for each matrix:
+            Vec<(
+                // its domain,
+                Self::Domain,
+                // for each point:
+                Vec<(
+                    Challenge,
+                    // values at this point
+                    Vec<Challenge>,
+                )>,
+            )>,
+        )>,
+        _proof: &Self::Proof,
+        _challenger: &mut Challenger,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// An MMCS which delegates to an inner MMCS, recording the index of every `open_batch` and
+/// `verify_batch` call. Multi-batch openings take the trait's default path through these, so they
+/// log each of their indices.
+///
+/// Clones share a log, so one can keep a clone around to inspect after handing the MMCS to a PCS.
+/// The log is behind a `Mutex`, so this is `Send + Sync` whenever the inner MMCS is.
+#[derive(Clone)]
+pub struct InstrumentedMmcs<M> {
+    inner: M,
+    log: Arc<Mutex<Vec<usize>>>,
+}
+
+impl<M> InstrumentedMmcs<M> {
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            log: Arc::default(),
+        }
+    }
+
+    /// The indices opened or verified so far, in call order.
+    pub fn access_log(&self) -> Vec<usize> {
+        self.log.lock().unwrap().clone()
+    }
+}
+
+impl<T, M: Mmcs<T>> Mmcs<T> for InstrumentedMmcs<M> {
+    type ProverData = M::ProverData;
+    type Commitment = M::Commitment;
+    type Proof = M::Proof;
+    type Error = M::Error;
+    type Mat<'a>
+        = M::Mat<'a>
+    where
+        Self: 'a;
+
+    fn open_batch(
+        &self,
+        index: usize,
+        prover_data: &Self::ProverData,
+    ) -> (Vec<Vec<T>>, Self::Proof) {
+        self.log.lock().unwrap().push(index);
+        self.inner.open_batch(index, prover_data)
+    }
+
+    fn root(&self, prover_data: &Self::ProverData) -> Self::Commitment {
+        self.inner.root(prover_data)
+    }
+
+    fn get_matrices<'a>(&'a self, prover_data: &'a Self::ProverData) -> Vec<Self::Mat<'a>> {
+        self.inner.get_matrices(prover_data)
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<T>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        self.log.lock().unwrap().push(index);
+        self.inner
+            .verify_batch(commit, dimensions, index, opened_values, proof)
+    }
+}
+
+impl<T, M: DirectMmcs<T>> DirectMmcs<T> for InstrumentedMmcs<M> {
+    fn commit(&self, inputs: Vec<RowMajorMatrix<T>>) -> (Self::Commitment, Self::ProverData) {
+        self.inner.commit(inputs)
+    }
+}
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 pub struct FriConfig<M> {
     pub log_blowup: usize,
     pub num_queries: usize,
@@ -9,4 +11,34 @@ impl<M> FriConfig<M> {
     pub fn blowup(&self) -> usize {
         1 << self.log_blowup
     }
+
+    /// This config's parameters, without its MMCS.
+    pub fn params(&self) -> FriParams {
+        FriParams {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.proof_of_work_bits,
+        }
+    }
+}
+
+/// The fields of a `FriConfig` other than its MMCS, which unlike the MMCS can be serialized, e.g.
+/// to ship the parameters a proof was generated with alongside it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriParams {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+impl FriParams {
+    /// The `FriConfig` with these parameters and `mmcs`.
+    pub fn with_mmcs<M>(self, mmcs: M) -> FriConfig<M> {
+        FriConfig {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.proof_of_work_bits,
+            mmcs,
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
 
 use p3_commit::Mmcs;
 use p3_field::Field;
@@ -37,3 +38,81 @@ pub struct CommitPhaseProofStep<F: Field, M: Mmcs<F>> {
 
     pub(crate) opening_proof: M::Proof,
 }
+
+// The impls below are written by hand, since a derive would require `M` itself to be `Clone` or
+// `Debug`, rather than its commitments and proofs.
+
+impl<F: Field, M: Mmcs<F>, Witness: Clone> Clone for FriProof<F, M, Witness>
+where
+    M::Proof: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            commit_phase_commits: self.commit_phase_commits.clone(),
+            query_proofs: self.query_proofs.clone(),
+            final_poly: self.final_poly,
+            pow_witness: self.pow_witness.clone(),
+        }
+    }
+}
+
+impl<F: Field, M: Mmcs<F>, Witness: Debug> Debug for FriProof<F, M, Witness>
+where
+    M::Commitment: Debug,
+    M::Proof: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FriProof")
+            .field("commit_phase_commits", &self.commit_phase_commits)
+            .field("query_proofs", &self.query_proofs)
+            .field("final_poly", &self.final_poly)
+            .field("pow_witness", &self.pow_witness)
+            .finish()
+    }
+}
+
+impl<F: Field, M: Mmcs<F>> Clone for QueryProof<F, M>
+where
+    M::Proof: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            commit_phase_openings: self.commit_phase_openings.clone(),
+        }
+    }
+}
+
+impl<F: Field, M: Mmcs<F>> Debug for QueryProof<F, M>
+where
+    M::Proof: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryProof")
+            .field("commit_phase_openings", &self.commit_phase_openings)
+            .finish()
+    }
+}
+
+impl<F: Field, M: Mmcs<F>> Clone for CommitPhaseProofStep<F, M>
+where
+    M::Proof: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sibling_value: self.sibling_value,
+            opening_proof: self.opening_proof.clone(),
+        }
+    }
+}
+
+impl<F: Field, M: Mmcs<F>> Debug for CommitPhaseProofStep<F, M>
+where
+    M::Proof: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommitPhaseProofStep")
+            .field("sibling_value", &self.sibling_value)
+            .field("opening_proof", &self.opening_proof)
+            .finish()
+    }
+}
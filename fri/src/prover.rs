@@ -4,7 +4,7 @@ use alloc::vec::Vec;
 use itertools::Itertools;
 use p3_challenger::{CanObserve, CanSample, GrindingChallenger};
 use p3_commit::{DirectMmcs, Mmcs};
-use p3_field::{Field, TwoAdicField};
+use p3_field::{AbstractField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use tracing::{info_span, instrument};
 
@@ -26,7 +26,13 @@ where
 
     let commit_phase_result = commit_phase(config, input, log_max_height, challenger);
 
-    let pow_witness = challenger.grind(config.proof_of_work_bits);
+    // Without any bits of work, the witness isn't observed, so there's nothing to grind for and
+    // the transcript is left as it is. The verifier skips the check in the same way.
+    let pow_witness = if config.proof_of_work_bits == 0 {
+        <Challenger::Witness as AbstractField>::zero()
+    } else {
+        challenger.grind(config.proof_of_work_bits)
+    };
 
     let query_indices: Vec<usize> = (0..config.num_queries)
         .map(|_| challenger.sample_bits(log_max_height))
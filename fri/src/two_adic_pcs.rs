@@ -1,11 +1,12 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use core::fmt::Debug;
-use core::marker::PhantomData;
+use core::fmt::{self, Debug, Formatter};
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, CanSample, GrindingChallenger};
-use p3_commit::{DirectMmcs, Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    DirectMmcs, Mmcs, OpenedValues, OpeningError, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
     batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, AbstractField, ExtensionField,
@@ -22,35 +23,125 @@ use serde::{Deserialize, Serialize};
 use tracing::{info_span, instrument};
 
 use crate::verifier::{self, FriError};
-use crate::{prover, FriConfig, FriProof};
+use crate::{prover, FriConfig, FriParams, FriProof};
 
 pub struct TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
     // degree bound
     log_n: usize,
+    /// The shift of the natural domains, which are cosets of two-adic subgroups.
+    shift: Val,
     dft: Dft,
     mmcs: InputMmcs,
     fri: FriConfig<FriMmcs>,
-    _phantom: PhantomData<Val>,
 }
 
-impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
+impl<Val: TwoAdicField, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
     pub fn new(log_n: usize, dft: Dft, mmcs: InputMmcs, fri: FriConfig<FriMmcs>) -> Self {
+        Self::with_shift(log_n, Val::one(), dft, mmcs, fri)
+    }
+
+    /// A PCS whose natural domains are the cosets `shift <g>` of the two-adic subgroups, rather
+    /// than the subgroups themselves. For instance, traces proven with shifts `1` and
+    /// `Val::two_adic_generator(log_n + 1)` are over the two halves of the same subgroup.
+    ///
+    /// # Panics
+    /// Panics if `shift` isn't in the two-adic subgroup, which keeps the natural domains disjoint
+    /// from the cosets the LDEs are taken over.
+    pub fn with_shift(
+        log_n: usize,
+        shift: Val,
+        dft: Dft,
+        mmcs: InputMmcs,
+        fri: FriConfig<FriMmcs>,
+    ) -> Self {
+        assert_eq!(
+            shift.exp_power_of_2(Val::TWO_ADICITY),
+            Val::one(),
+            "the shift must be in the two-adic subgroup"
+        );
         Self {
             log_n,
+            shift,
             dft,
             mmcs,
             fri,
-            _phantom: PhantomData,
         }
     }
+
+    /// The parameters a verifier needs to agree on with the prover, which can be serialized.
+    pub fn verifier_params(&self) -> VerifierParams<Val> {
+        VerifierParams {
+            log_n: self.log_n,
+            shift: self.shift,
+            fri: self.fri.params(),
+        }
+    }
+
+    /// The PCS with the given parameters, e.g. as deserialized from another machine's
+    /// `verifier_params`, and the given DFT and MMCSs.
+    ///
+    /// # Panics
+    /// Panics if `params.shift` isn't in the two-adic subgroup, as with `with_shift`.
+    pub fn from_verifier_params(
+        params: VerifierParams<Val>,
+        dft: Dft,
+        mmcs: InputMmcs,
+        fri_mmcs: FriMmcs,
+    ) -> Self {
+        Self::with_shift(
+            params.log_n,
+            params.shift,
+            dft,
+            mmcs,
+            params.fri.with_mmcs(fri_mmcs),
+        )
+    }
+}
+
+/// The parameters of a `TwoAdicFriPcs`, i.e. everything about it but the DFT and the MMCSs, whose
+/// hashers aren't data. A proof can only be verified with the parameters it was generated with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierParams<Val> {
+    /// The log2 of the largest trace degree the PCS supports.
+    pub log_n: usize,
+    /// The shift of the natural domains.
+    pub shift: Val,
+    pub fri: FriParams,
 }
 
 #[derive(Debug)]
 pub enum VerificationError<InputMmcsError, FriMmcsError> {
-    InputMmcsError(InputMmcsError),
+    /// The proof doesn't have one opening per query, round and matrix, or its FRI proof doesn't
+    /// have one round per halving of the largest domain.
+    InvalidProofShape,
+    /// The opening of the `round`th commitment failed to verify, in query number `query`.
+    InputMmcsError {
+        query: usize,
+        round: usize,
+        error: InputMmcsError,
+    },
     FriError(FriError<FriMmcsError>),
 }
 
+impl<InputMmcsError: Debug, FriMmcsError: Debug> OpeningError
+    for VerificationError<InputMmcsError, FriMmcsError>
+{
+    fn query_index(&self) -> Option<usize> {
+        match self {
+            Self::InputMmcsError { query, .. } => Some(*query),
+            Self::FriError(e) => e.query_index(),
+            Self::InvalidProofShape => None,
+        }
+    }
+
+    fn round_index(&self) -> Option<usize> {
+        match self {
+            Self::InputMmcsError { round, .. } => Some(*round),
+            Self::FriError(_) | Self::InvalidProofShape => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct TwoAdicFriPcsProof<
@@ -71,6 +162,68 @@ pub struct BatchOpening<Val: Field, InputMmcs: Mmcs<Val>> {
     pub(crate) opening_proof: <InputMmcs as Mmcs<Val>>::Proof,
 }
 
+// As with `FriProof`, these bound the MMCS proofs rather than the MMCS types themselves.
+impl<Val, Challenge, InputMmcs, FriMmcs> Clone
+    for TwoAdicFriPcsProof<Val, Challenge, InputMmcs, FriMmcs>
+where
+    Val: Field,
+    Challenge: Field,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+    InputMmcs::Proof: Clone,
+    FriMmcs::Proof: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            fri_proof: self.fri_proof.clone(),
+            query_openings: self.query_openings.clone(),
+        }
+    }
+}
+
+impl<Val, Challenge, InputMmcs, FriMmcs> Debug
+    for TwoAdicFriPcsProof<Val, Challenge, InputMmcs, FriMmcs>
+where
+    Val: Field,
+    Challenge: Field,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+    InputMmcs::Proof: Debug,
+    FriMmcs::Commitment: Debug,
+    FriMmcs::Proof: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TwoAdicFriPcsProof")
+            .field("fri_proof", &self.fri_proof)
+            .field("query_openings", &self.query_openings)
+            .finish()
+    }
+}
+
+impl<Val: Field, InputMmcs: Mmcs<Val>> Clone for BatchOpening<Val, InputMmcs>
+where
+    InputMmcs::Proof: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            opened_values: self.opened_values.clone(),
+            opening_proof: self.opening_proof.clone(),
+        }
+    }
+}
+
+impl<Val: Field, InputMmcs: Mmcs<Val>> Debug for BatchOpening<Val, InputMmcs>
+where
+    InputMmcs::Proof: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchOpening")
+            .field("opened_values", &self.opened_values)
+            .field("opening_proof", &self.opening_proof)
+            .finish()
+    }
+}
+
 impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger> Pcs<Challenge, Challenger>
     for TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
 where
@@ -93,10 +246,14 @@ where
         assert!(log_n <= self.log_n);
         TwoAdicMultiplicativeCoset {
             log_n,
-            shift: Val::one(),
+            shift: self.shift,
         }
     }
 
+    fn max_log_degree(&self) -> Option<usize> {
+        Some(self.log_n)
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -313,6 +470,26 @@ where
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
+        // The proof's shape comes from the prover, and everything below is indexed by it, so check
+        // it against the claimed openings first.
+        let log_global_max_height = rounds
+            .iter()
+            .flat_map(|(_, mats)| mats)
+            .map(|(domain, _)| log2_strict_usize(domain.size()))
+            .max()
+            .unwrap_or(0);
+        let valid_shape = proof.fri_proof.commit_phase_commits.len() == log_global_max_height
+            && proof.query_openings.len() == self.fri.num_queries
+            && proof.query_openings.iter().all(|query_opening| {
+                query_opening.len() == rounds.len()
+                    && izip!(query_opening, &rounds).all(|(batch_opening, (_, mats))| {
+                        batch_opening.opened_values.len() == mats.len()
+                    })
+            });
+        if !valid_shape {
+            return Err(VerificationError::InvalidProofShape);
+        }
+
         // Batch combination challenge
         let alpha: Challenge = challenger.sample();
 
@@ -322,14 +499,18 @@ where
 
         let log_max_height = proof.fri_proof.commit_phase_commits.len() + self.fri.log_blowup;
 
+        let span = info_span!("verify input openings").entered();
         let reduced_openings: Vec<[Challenge; 32]> = proof
             .query_openings
             .iter()
             .zip(&fri_challenges.query_indices)
-            .map(|(query_opening, &index)| {
+            .enumerate()
+            .map(|(query, (query_opening, &index))| {
                 let mut ro = [Challenge::zero(); 32];
                 let mut alpha_pow = [Challenge::one(); 32];
-                for (batch_opening, (batch_commit, mats)) in izip!(query_opening, &rounds) {
+                for (round, (batch_opening, (batch_commit, mats))) in
+                    izip!(query_opening, &rounds).enumerate()
+                {
                     let batch_dims: Vec<Dimensions> = mats
                         .iter()
                         .map(|(domain, _)| Dimensions {
@@ -338,13 +519,19 @@ where
                             height: domain.size(),
                         })
                         .collect_vec();
-                    self.mmcs.verify_batch(
-                        batch_commit,
-                        &batch_dims,
-                        index,
-                        &batch_opening.opened_values,
-                        &batch_opening.opening_proof,
-                    )?;
+                    self.mmcs
+                        .verify_batch(
+                            batch_commit,
+                            &batch_dims,
+                            index,
+                            &batch_opening.opened_values,
+                            &batch_opening.opening_proof,
+                        )
+                        .map_err(|error| VerificationError::InputMmcsError {
+                            query,
+                            round,
+                            error,
+                        })?;
                     for (mat_opening, (mat_domain, mat_points_and_values)) in
                         izip!(&batch_opening.opened_values, mats)
                     {
@@ -367,8 +554,8 @@ where
                 }
                 Ok(ro)
             })
-            .collect::<Result<Vec<_>, InputMmcs::Error>>()
-            .map_err(VerificationError::InputMmcsError)?;
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(span);
 
         verifier::verify_challenges(
             &self.fri,
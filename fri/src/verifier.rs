@@ -7,17 +7,37 @@ use p3_commit::Mmcs;
 use p3_field::{Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_util::reverse_bits_len;
+use tracing::instrument;
 
 use crate::{FriConfig, FriProof, QueryProof};
 
 #[derive(Debug)]
 pub enum FriError<CommitMmcsErr> {
     InvalidProofShape,
-    CommitPhaseMmcsError(CommitMmcsErr),
-    FinalPolyMismatch,
+    /// The commit phase opening of query number `query` failed to verify.
+    CommitPhaseMmcsError {
+        query: usize,
+        error: CommitMmcsErr,
+    },
+    /// Query number `query` didn't fold to the final polynomial.
+    FinalPolyMismatch {
+        query: usize,
+    },
     InvalidPowWitness,
 }
 
+impl<CommitMmcsErr> FriError<CommitMmcsErr> {
+    /// The index of the query which failed, if the failure was specific to one query.
+    pub fn query_index(&self) -> Option<usize> {
+        match self {
+            Self::CommitPhaseMmcsError { query, .. } | Self::FinalPolyMismatch { query } => {
+                Some(*query)
+            }
+            Self::InvalidProofShape | Self::InvalidPowWitness => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FriChallenges<F> {
     pub query_indices: Vec<usize>,
@@ -43,12 +63,23 @@ where
         })
         .collect();
 
-    if proof.query_proofs.len() != config.num_queries {
+    let num_rounds = proof.commit_phase_commits.len();
+    if proof.query_proofs.len() != config.num_queries
+        || proof
+            .query_proofs
+            .iter()
+            .any(|query_proof| query_proof.commit_phase_openings.len() != num_rounds)
+    {
         return Err(FriError::InvalidProofShape);
     }
 
-    // Check PoW.
-    if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
+    // Check PoW. With no bits of work the prover doesn't grind, and leaves the witness zero.
+    let valid_pow = if config.proof_of_work_bits == 0 {
+        proof.pow_witness.is_zero()
+    } else {
+        challenger.check_witness(config.proof_of_work_bits, proof.pow_witness)
+    };
+    if !valid_pow {
         return Err(FriError::InvalidPowWitness);
     }
 
@@ -64,6 +95,7 @@ where
     })
 }
 
+#[instrument(name = "verify FRI queries", skip_all)]
 pub fn verify_challenges<F, M, Witness>(
     config: &FriConfig<M>,
     proof: &FriProof<F, M, Witness>,
@@ -75,11 +107,13 @@ where
     M: Mmcs<F>,
 {
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
-    for (&index, query_proof, ro) in izip!(
+    for (query, (&index, query_proof, ro)) in izip!(
         &challenges.query_indices,
         &proof.query_proofs,
         reduced_openings
-    ) {
+    )
+    .enumerate()
+    {
         let folded_eval = verify_query(
             config,
             &proof.commit_phase_commits,
@@ -88,10 +122,11 @@ where
             &challenges.betas,
             ro,
             log_max_height,
-        )?;
+        )
+        .map_err(|error| FriError::CommitPhaseMmcsError { query, error })?;
 
         if folded_eval != proof.final_poly {
-            return Err(FriError::FinalPolyMismatch);
+            return Err(FriError::FinalPolyMismatch { query });
         }
     }
 
@@ -106,7 +141,7 @@ fn verify_query<F, M>(
     betas: &[F],
     reduced_openings: &[F; 32],
     log_max_height: usize,
-) -> Result<F, FriError<M::Error>>
+) -> Result<F, M::Error>
 where
     F: TwoAdicField,
     M: Mmcs<F>,
@@ -133,16 +168,13 @@ where
             width: 2,
             height: (1 << log_folded_height),
         }];
-        config
-            .mmcs
-            .verify_batch(
-                commit,
-                dims,
-                index_pair,
-                &[evals.clone()],
-                &step.opening_proof,
-            )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+        config.mmcs.verify_batch(
+            commit,
+            dims,
+            index_pair,
+            &[evals.clone()],
+            &step.opening_proof,
+        )?;
 
         let mut xs = [x; 2];
         xs[index_sibling % 2] *= F::two_adic_generator(1);
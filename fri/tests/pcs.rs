@@ -1,5 +1,6 @@
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
 use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger};
+use p3_commit::testing::InstrumentedMmcs;
 use p3_commit::{ExtensionMmcs, Pcs};
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
@@ -119,3 +120,164 @@ fn test_fri_pcs_many_different_rev() {
         make_test_fri_pcs(&degrees);
     }
 }
+
+#[test]
+fn test_fri_pcs_commit_batches() {
+    let mut rng = thread_rng();
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    let hash = MyHash::new(perm.clone());
+
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    let compress = MyCompress::new(perm.clone());
+
+    type ValMmcs = FieldMerkleTreeMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        MyHash,
+        MyCompress,
+        8,
+    >;
+    let val_mmcs = ValMmcs::new(hash, compress);
+
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+    type Dft = Radix2DitParallel;
+    let dft = Dft {};
+
+    type Challenger = DuplexChallenger<Val, Perm, 16>;
+
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 10,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+    let pcs: MyPcs = MyPcs::new(5, dft, val_mmcs, fri_config);
+
+    let mut domain_and_poly = |log_degree: usize| {
+        (
+            <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << log_degree),
+            RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_degree, 4),
+        )
+    };
+    let batches = vec![
+        vec![domain_and_poly(5), domain_and_poly(4)],
+        vec![domain_and_poly(3)],
+    ];
+    let zeta: Challenge = Challenger::new(perm.clone()).sample_ext_element();
+
+    // The reference openings, from committing to each batch separately.
+    let reference = batches
+        .iter()
+        .map(|batch| {
+            let (_, data) = <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, batch.clone());
+            let points = batch.iter().map(|_| vec![zeta]).collect();
+            let (opening, _) = pcs.open(vec![(&data, points)], &mut Challenger::new(perm.clone()));
+            opening.into_iter().next().unwrap()
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    let (commit, data) =
+        <MyPcs as Pcs<Challenge, Challenger>>::commit_batches(&pcs, batches.clone());
+    let domains = batches
+        .iter()
+        .flatten()
+        .map(|(d, _)| *d)
+        .collect::<Vec<_>>();
+    let points = domains.iter().map(|_| vec![zeta]).collect();
+    let mut challenger = Challenger::new(perm.clone());
+    challenger.observe(commit);
+    let (opening, proof) = pcs.open(vec![(&data, points)], &mut challenger);
+    assert_eq!(opening[0], reference);
+
+    let os = domains
+        .iter()
+        .zip(&opening[0])
+        .map(|(domain, mat_openings)| (*domain, vec![(zeta, mat_openings[0].clone())]))
+        .collect();
+    let mut challenger = Challenger::new(perm);
+    challenger.observe(commit);
+    pcs.verify(vec![(commit, os)], &proof, &mut challenger)
+        .unwrap()
+}
+
+#[test]
+fn test_fri_pcs_instrumented_input_mmcs() {
+    let mut rng = thread_rng();
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    let hash = MyHash::new(perm.clone());
+
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    let compress = MyCompress::new(perm.clone());
+
+    type ValMmcs = FieldMerkleTreeMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        MyHash,
+        MyCompress,
+        8,
+    >;
+    let val_mmcs = ValMmcs::new(hash, compress);
+
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+    // Only the input MMCS is instrumented, so the log holds the input openings and not FRI's own.
+    type InputMmcs = InstrumentedMmcs<ValMmcs>;
+    let input_mmcs = InputMmcs::new(val_mmcs);
+
+    type Dft = Radix2DitParallel;
+    let dft = Dft {};
+
+    type Challenger = DuplexChallenger<Val, Perm, 16>;
+
+    let num_queries = 10;
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    type MyPcs = TwoAdicFriPcs<Val, Dft, InputMmcs, ChallengeMmcs>;
+    let pcs: MyPcs = MyPcs::new(4, dft, input_mmcs.clone(), fri_config);
+
+    let domain = <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << 4);
+    let poly = RowMajorMatrix::<Val>::rand(&mut rng, 1 << 4, 3);
+    let (commit, data) = <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, vec![(domain, poly)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    challenger.observe(commit);
+    let zeta = challenger.sample_ext_element::<Challenge>();
+    let (opening, proof) = pcs.open(vec![(&data, vec![vec![zeta]])], &mut challenger);
+
+    // The prover opens the input commitment once per query.
+    let opened = input_mmcs.access_log();
+    assert_eq!(opened.len(), num_queries);
+
+    let mut challenger = Challenger::new(perm);
+    challenger.observe(commit);
+    let _ = challenger.sample_ext_element::<Challenge>();
+    let os = vec![(domain, vec![(zeta, opening[0][0][0].clone())])];
+    pcs.verify(vec![(commit, os)], &proof, &mut challenger)
+        .unwrap();
+
+    // The verifier checks the same indices, in the same order.
+    let log = input_mmcs.access_log();
+    assert_eq!(log[..num_queries], opened[..]);
+    assert_eq!(log[num_queries..], opened[..]);
+}
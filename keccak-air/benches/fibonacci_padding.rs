@@ -0,0 +1,69 @@
+//! Compares the cost of the LDE and commitment of a `FibonacciAir` trace, which is 3 columns wide,
+//! with that of a `PaddedFibonacciAir` trace of the same height, which is 4 columns wide.
+//!
+//! The padded trace has a third more values, so it only pays off if the power-of-two width makes
+//! each value cheaper to transform and hash.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_commit::DirectMmcs;
+use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_field::{AbstractField, Field};
+use p3_keccak_air::{PaddedFibonacciAir, SeededFibonacciAir};
+use p3_matrix::bitrev::BitReversableMatrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Dft = Radix2DitParallel;
+
+const LOG_NUM_ROWS: usize = 16;
+const LOG_BLOWUP: usize = 1;
+
+/// The low-degree extension of `trace`, bit-reversed as `TwoAdicFriPcs` commits to it.
+fn lde(dft: &Dft, trace: RowMajorMatrix<Val>) -> RowMajorMatrix<Val> {
+    dft.coset_lde_batch(trace, LOG_BLOWUP, Val::generator())
+        .bit_reverse_rows()
+        .to_row_major_matrix()
+}
+
+fn bench_fibonacci_padding(criterion: &mut Criterion) {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+    let dft = Dft {};
+
+    let air = SeededFibonacciAir::<Val>::default();
+    let num_rows = 1 << LOG_NUM_ROWS;
+    let traces = [
+        air.generate_trace(num_rows),
+        PaddedFibonacciAir::new(air).generate_trace(num_rows),
+    ];
+
+    let mut group = criterion.benchmark_group("fibonacci_padding");
+    group.sample_size(10);
+
+    for trace in traces {
+        let param = format!("rows=2^{},width={}", LOG_NUM_ROWS, trace.width());
+
+        group.bench_with_input(BenchmarkId::new("lde", &param), &trace, |b, trace| {
+            b.iter(|| lde(&dft, trace.clone()))
+        });
+
+        let lde = lde(&dft, trace);
+        group.bench_with_input(BenchmarkId::new("commit", &param), &lde, |b, lde| {
+            b.iter(|| mmcs.commit_matrix(lde.clone()))
+        });
+    }
+}
+
+criterion_group!(benches, bench_fibonacci_padding);
+criterion_main!(benches);
@@ -0,0 +1,91 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p3_baby_bear::BabyBear;
+use p3_field::AbstractField;
+use p3_keccak_air::{generate_fibonacci_trace, NUM_FIBONACCI_COLS};
+use p3_matrix::dense::RowMajorMatrix;
+
+type F = BabyBear;
+
+const NUM_ROWS: usize = 1 << 18;
+
+/// The system allocator, instrumented to track the bytes currently allocated, their peak, and the
+/// number of allocations made.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static NUM_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+            NUM_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Builds the trace as a `Vec` of rows, then flattens it, as the examples used to.
+fn generate_via_rows(num_rows: usize) -> RowMajorMatrix<F> {
+    let mut rows: Vec<Vec<F>> = Vec::with_capacity(num_rows);
+    let (mut a, mut b) = (F::one(), F::one());
+    for _ in 0..num_rows {
+        rows.push(vec![a, b, a + b]);
+        (a, b) = (b, a + b);
+    }
+    RowMajorMatrix::new(rows.concat(), NUM_FIBONACCI_COLS)
+}
+
+/// Generates a trace and prints the peak number of bytes allocated meanwhile, over what was
+/// allocated before, and the number of allocations made.
+fn report_memory(name: &str, generate: impl FnOnce() -> RowMajorMatrix<F>) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let num_allocations_before = NUM_ALLOCATIONS.load(Ordering::Relaxed);
+
+    let trace = black_box(generate());
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+    let num_allocations = NUM_ALLOCATIONS.load(Ordering::Relaxed) - num_allocations_before;
+    drop(trace);
+
+    println!(
+        "{}: peak of {} bytes over {} allocations, for a {}-byte trace",
+        name,
+        peak,
+        num_allocations,
+        NUM_ROWS * NUM_FIBONACCI_COLS * core::mem::size_of::<F>()
+    );
+}
+
+fn bench_fibonacci_trace(c: &mut Criterion) {
+    report_memory("fibonacci_trace_builder::<BabyBear, 2^18>", || {
+        generate_fibonacci_trace::<F>(NUM_ROWS)
+    });
+    report_memory("fibonacci_trace_via_rows::<BabyBear, 2^18>", || {
+        generate_via_rows(NUM_ROWS)
+    });
+
+    c.bench_function("fibonacci_trace_builder::<BabyBear, 2^18>", |b| {
+        b.iter(|| generate_fibonacci_trace::<F>(black_box(NUM_ROWS)))
+    });
+    c.bench_function("fibonacci_trace_via_rows::<BabyBear, 2^18>", |b| {
+        b.iter(|| generate_via_rows(black_box(NUM_ROWS)))
+    });
+}
+
+criterion_group!(benches, bench_fibonacci_trace);
+criterion_main!(benches);
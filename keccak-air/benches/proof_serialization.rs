@@ -0,0 +1,78 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak_air::FibonacciAir;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, Proof, StarkConfig};
+use p3_util::log2_ceil_usize;
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+const NUM_ROWS: usize = 1 << 10;
+
+fn fibonacci_proof() -> Proof<MyConfig> {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft {};
+
+    let air = FibonacciAir {};
+    let trace = air.generate_trace::<Val>(NUM_ROWS);
+    let public_values = vec![air.expected_output::<Val>(NUM_ROWS)];
+
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 100,
+        proof_of_work_bits: 16,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(log2_ceil_usize(trace.height()), dft, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &public_values)
+}
+
+fn bench_proof_serialization(c: &mut Criterion) {
+    let proof = fibonacci_proof();
+    let bytes = proof.to_bytes();
+    let json = serde_json::to_vec(&proof).unwrap();
+
+    c.bench_function("proof_to_bytes::<BabyBear, 2^10>", |b| {
+        b.iter(|| black_box(&proof).to_bytes())
+    });
+    c.bench_function("proof_from_bytes::<BabyBear, 2^10>", |b| {
+        b.iter(|| Proof::<MyConfig>::from_bytes(black_box(&bytes)).unwrap())
+    });
+    c.bench_function("proof_to_json::<BabyBear, 2^10>", |b| {
+        b.iter(|| serde_json::to_vec(black_box(&proof)).unwrap())
+    });
+    c.bench_function("proof_from_json::<BabyBear, 2^10>", |b| {
+        b.iter(|| serde_json::from_slice::<Proof<MyConfig>>(black_box(&json)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_proof_serialization);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+//! The inputs shared by the examples which prove Keccak permutations.
+
+use std::env;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const NUM_HASHES: usize = 680;
+
+/// Samples the inputs to hash. They're random on each run unless the `KECCAK_INPUT_SEED`
+/// environment variable is set to a `u64`, which seeds the RNG so that runs can be reproduced.
+pub fn generate_inputs() -> Vec<[u64; 25]> {
+    let mut rng = match env::var("KECCAK_INPUT_SEED") {
+        Ok(seed) => StdRng::seed_from_u64(seed.parse().expect("KECCAK_INPUT_SEED should be a u64")),
+        Err(_) => StdRng::from_entropy(),
+    };
+    (0..NUM_HASHES).map(|_| rng.gen()).collect()
+}
@@ -11,16 +11,17 @@ use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
 use p3_uni_stark::{prove, verify, StarkConfig, VerificationError};
 use p3_util::log2_ceil_usize;
-use rand::random;
 use tracing_forest::util::LevelFilter;
 use tracing_forest::ForestLayer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Registry};
 
-const NUM_HASHES: usize = 680;
+mod common;
 
-fn main() -> Result<(), VerificationError> {
+use common::generate_inputs;
+
+fn main() -> Result<(), VerificationError<BabyBear>> {
     let env_filter = EnvFilter::builder()
         .with_default_directive(LevelFilter::INFO.into())
         .from_env_lossy();
@@ -52,7 +53,7 @@ fn main() -> Result<(), VerificationError> {
 
     type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
 
-    let inputs = (0..NUM_HASHES).map(|_| random()).collect::<Vec<_>>();
+    let inputs = generate_inputs();
     let trace = generate_trace_rows::<Val>(inputs);
 
     let fri_config = FriConfig {
@@ -71,6 +72,14 @@ fn main() -> Result<(), VerificationError> {
 
     let proof = prove(&config, &KeccakAir {}, &mut challenger, trace, &vec![]);
 
+    // In the forest output, verification breaks down into these spans:
+    //
+    //     verify
+    //     ┝━ derive challenges
+    //     ┝━ verify opening argument
+    //     │  ┝━ verify input openings
+    //     │  ┕━ verify FRI queries
+    //     ┕━ check constraints at zeta
     let mut challenger = Challenger::from_hasher(vec![], byte_hash);
     verify(&config, &KeccakAir {}, &mut challenger, &proof, &vec![])
 }
@@ -10,16 +10,17 @@ use p3_mersenne_31::Mersenne31;
 use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
 use p3_uni_stark::{prove, verify, StarkConfig, VerificationError};
 use p3_util::log2_strict_usize;
-use rand::random;
 use tracing_forest::util::LevelFilter;
 use tracing_forest::ForestLayer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Registry};
 
-const NUM_HASHES: usize = 680;
+mod common;
 
-fn main() -> Result<(), VerificationError> {
+use common::generate_inputs;
+
+fn main() -> Result<(), VerificationError<Mersenne31>> {
     let env_filter = EnvFilter::builder()
         .with_default_directive(LevelFilter::INFO.into())
         .from_env_lossy();
@@ -66,7 +67,7 @@ fn main() -> Result<(), VerificationError> {
     type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
     let config = MyConfig::new(pcs);
 
-    let inputs = (0..NUM_HASHES).map(|_| random()).collect::<Vec<_>>();
+    let inputs = generate_inputs();
     let trace = generate_trace_rows::<Val>(inputs);
 
     dbg!(trace.height(), log2_strict_usize(trace.height()));
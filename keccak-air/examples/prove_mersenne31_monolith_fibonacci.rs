@@ -0,0 +1,67 @@
+use p3_challenger::DuplexChallenger;
+use p3_circle::{Cfft, CirclePcs};
+use p3_keccak_air::FibonacciAir;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_mersenne_31::Mersenne31;
+use p3_monolith::{
+    MonolithCompress, MonolithHash, MonolithMdsMatrixMersenne31, MonolithMersenne31,
+};
+use p3_uni_stark::{prove, verify, StarkConfig, VerificationError};
+use tracing_forest::util::LevelFilter;
+use tracing_forest::ForestLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+const NUM_ROWS: usize = 1 << 16;
+
+fn main() -> Result<(), VerificationError<Mersenne31>> {
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    Registry::default()
+        .with(env_filter)
+        .with(ForestLayer::default())
+        .init();
+
+    type Val = Mersenne31;
+    type Challenge = Val;
+
+    type Mds = MonolithMdsMatrixMersenne31<6>;
+    type Perm = MonolithMersenne31<Mds, 16, 5>;
+    let perm = Perm::new(MonolithMdsMatrixMersenne31);
+
+    type MyHash = MonolithHash<Mds, 16, 5, 8, 8>;
+    let hash = MyHash::new(perm.clone());
+
+    type MyCompress = MonolithCompress<Mds, 16, 5, 8>;
+    let compress = MyCompress::new(perm.clone());
+
+    // Monolith is only implemented on scalars, so the tree is hashed without packing.
+    type ValMmcs = FieldMerkleTreeMmcs<Val, Val, MyHash, MyCompress, 8>;
+    let val_mmcs = ValMmcs::new(hash, compress);
+
+    type Challenger = DuplexChallenger<Val, Perm, 16>;
+
+    type Pcs = CirclePcs<Val, ValMmcs>;
+    let pcs = Pcs {
+        log_blowup: 1,
+        cfft: Cfft::default(),
+        mmcs: val_mmcs,
+    };
+
+    type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+    let config = MyConfig::new(pcs);
+
+    let air = FibonacciAir {};
+    let trace = air.generate_trace::<Val>(NUM_ROWS);
+    let public_values = vec![air.expected_output::<Val>(NUM_ROWS)];
+
+    let mut challenger = Challenger::new(perm.clone());
+
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
@@ -1,7 +1,11 @@
+use alloc::vec::Vec;
 use core::borrow::{Borrow, BorrowMut};
 use core::mem::{size_of, transmute};
+use core::{ptr, slice};
 
 use p3_util::indices_arr;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::constants::R;
 use crate::{NUM_ROUNDS, RATE_LIMBS, U64_LIMBS};
@@ -13,6 +17,10 @@ use crate::{NUM_ROUNDS, RATE_LIMBS, U64_LIMBS};
 /// Thus, for example, `a_prime` is stored in `y, x, z` order. This departs from the more common
 /// convention of `x, y, z` order, but it has the benefit that input lists map to AIR columns in a
 /// nicer way.
+///
+/// A row is serialized as a flat sequence of `NUM_KECCAK_COLS` values, in the same order as the
+/// AIR's columns, i.e. the order in which fields are declared here. Each 64-bit lane is split into
+/// `U64_LIMBS` 16-bit limbs, ordered according to `LIMB_ORDER`; see `u64_to_limbs`.
 #[repr(C)]
 pub struct KeccakCols<T> {
     /// The `i`th value is set to 1 if we are in the `i`th round, otherwise 0.
@@ -62,6 +70,40 @@ pub struct KeccakCols<T> {
     pub a_prime_prime_prime_0_0_limbs: [T; U64_LIMBS],
 }
 
+impl<T> KeccakCols<T> {
+    pub(crate) fn as_slice(&self) -> &[T] {
+        // SAFETY: `KeccakCols<T>` is `repr(C)` and consists only of (nested arrays of) `T`, so it
+        // has the same layout as `[T; NUM_KECCAK_COLS]`.
+        unsafe { slice::from_raw_parts(self as *const Self as *const T, NUM_KECCAK_COLS) }
+    }
+}
+
+impl<T: Serialize> Serialize for KeccakCols<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for KeccakCols<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut values = Vec::<T>::deserialize(deserializer)?;
+        if values.len() != NUM_KECCAK_COLS {
+            return Err(D::Error::invalid_length(
+                values.len(),
+                &"a sequence of NUM_KECCAK_COLS values",
+            ));
+        }
+        // SAFETY: `values` holds exactly `NUM_KECCAK_COLS` values of type `T`, which has the same
+        // layout as `KeccakCols<T>`. Setting the length to zero moves ownership of the values into
+        // `cols`, so they aren't dropped twice.
+        unsafe {
+            let cols = ptr::read(values.as_ptr() as *const Self);
+            values.set_len(0);
+            Ok(cols)
+        }
+    }
+}
+
 impl<T: Copy> KeccakCols<T> {
     pub fn b(&self, x: usize, y: usize, z: usize) -> T {
         debug_assert!(x < 5);
@@ -148,3 +190,33 @@ impl<T> BorrowMut<KeccakCols<T>> for [T] {
         &mut shorts[0]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::MatrixRowSlices;
+    use rand::random;
+
+    use super::*;
+    use crate::generate_trace_rows;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let input: [u64; 25] = random();
+        let trace = generate_trace_rows::<F>(vec![input]);
+        let row: &KeccakCols<F> = trace.row_slice(3).borrow();
+
+        let json = serde_json::to_string(row).unwrap();
+        let deserialized: KeccakCols<F> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.as_slice(), row.as_slice());
+    }
+
+    #[test]
+    fn test_deserialize_wrong_len() {
+        assert!(serde_json::from_str::<KeccakCols<F>>("[1, 2, 3]").is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::BITS_PER_LIMB;
+use core::array;
+
+use p3_field::AbstractField;
+
+use crate::logic::limb_shift;
+use crate::{NUM_ROUNDS, U64_LIMBS};
 
 pub(crate) const R: [[u8; 5]; 5] = [
     [0, 36, 3, 41, 18],
@@ -159,9 +164,49 @@ const RC_BITS: [[u8; 64]; 24] = [
 ];
 
 pub(crate) const fn rc_value_limb(round: usize, limb: usize) -> u16 {
-    (RC[round] >> (limb * BITS_PER_LIMB)) as u16
+    (RC[round] >> limb_shift(limb)) as u16
 }
 
 pub(crate) const fn rc_value_bit(round: usize, bit_index: usize) -> u8 {
     RC_BITS[round][bit_index]
 }
+
+/// The round constants `RC`, each split into `U64_LIMBS` 16-bit limbs ordered according to
+/// `LIMB_ORDER`, as they appear in the trace.
+pub fn round_constants<F: AbstractField>() -> impl Iterator<Item = [F; U64_LIMBS]> {
+    (0..NUM_ROUNDS)
+        .map(|round| array::from_fn(|limb| F::from_canonical_u16(rc_value_limb(round, limb))))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::PrimeField;
+    use p3_goldilocks::Goldilocks;
+    use p3_mersenne_31::Mersenne31;
+
+    use super::*;
+    use crate::BITS_PER_LIMB;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_round_constants() {
+        let rcs: Vec<[F; U64_LIMBS]> = round_constants().collect();
+        assert_eq!(rcs.len(), NUM_ROUNDS);
+        assert_eq!(rcs[0][0], F::one());
+
+        // RC[2] = 0x800000000000808A
+        assert_eq!(rcs[2], [0x808A, 0, 0, 0x8000].map(F::from_canonical_u16));
+    }
+
+    #[test]
+    fn test_limbs_fit_in_fields() {
+        // `KeccakAir` assumes every 16-bit limb is a distinct field element.
+        assert!(BabyBear::bits() > BITS_PER_LIMB);
+        assert!(Goldilocks::bits() > BITS_PER_LIMB);
+        assert!(Mersenne31::bits() > BITS_PER_LIMB);
+    }
+}
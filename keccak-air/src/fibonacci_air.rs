@@ -0,0 +1,653 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, PrimeField64};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRowSlices};
+
+/// An AIR for the Fibonacci sequence `1, 1, 2, 3, 5, ...`.
+///
+/// Each row holds three consecutive terms `a, b, c` with `c = a + b`, and each transition shifts
+/// the window along by one term. For other seeds, see `SeededFibonacciAir`.
+///
+/// The single public value is the claimed output, i.e. the `c` column of the last row. A trace of
+/// `n` rows simply runs the recurrence for `n` steps, so `n` must be a power of two. To prove a
+/// sequence of any other length, whose trace is padded past its last term, see
+/// `VariableLengthFibonacciAir`. See `expected_output`.
+pub struct FibonacciAir {}
+
+impl FibonacciAir {
+    /// Generates a trace with `num_rows` rows. See `generate_fibonacci_trace`.
+    pub fn generate_trace<F: AbstractField>(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        generate_fibonacci_trace(num_rows)
+    }
+
+    /// The value of `c` in the last row of a `num_rows`-row trace, which should be passed as the
+    /// public value when proving and verifying.
+    pub fn expected_output<F: AbstractField>(&self, num_rows: usize) -> F {
+        SeededFibonacciAir::default().expected_output(num_rows)
+    }
+}
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        SeededFibonacciAir::<AB::F>::default().eval(builder);
+    }
+}
+
+/// An AIR for the Fibonacci-like sequence `a0, b0, a0 + b0, ...`, e.g. the Lucas numbers with
+/// `a0 = 2, b0 = 1`.
+///
+/// The layout and public value are those of `FibonacciAir`, which has the same constraints as
+/// `SeededFibonacciAir::default()`.
+pub struct SeededFibonacciAir<F> {
+    pub a0: F,
+    pub b0: F,
+}
+
+impl<F> SeededFibonacciAir<F> {
+    pub fn new(a0: F, b0: F) -> Self {
+        Self { a0, b0 }
+    }
+}
+
+impl<F: AbstractField> SeededFibonacciAir<F> {
+    /// Generates a trace with `num_rows` rows, starting from this AIR's seeds.
+    pub fn generate_trace(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        generate_fibonacci_trace_from_seeds(self.a0.clone(), self.b0.clone(), num_rows)
+    }
+
+    /// The value of `c` in the last row of a `num_rows`-row trace, which should be passed as the
+    /// public value when proving and verifying.
+    pub fn expected_output(&self, num_rows: usize) -> F {
+        // The last row holds the terms `num_rows - 1` to `num_rows + 1`.
+        fibonacci_nth(num_rows + 1, self.a0.clone(), self.b0.clone())
+    }
+}
+
+/// The `n`th term of the sequence `a0, b0, a0 + b0, ...`, counting from zero, computed directly
+/// rather than through a trace. So `n = 0` and `n = 1` give the seeds themselves.
+pub fn fibonacci_nth<F: AbstractField>(n: usize, a0: F, b0: F) -> F {
+    let mut a = a0;
+    let mut b = b0;
+    for _ in 0..n {
+        let c = a + b.clone();
+        a = b;
+        b = c;
+    }
+    a
+}
+
+impl<F: AbstractField> Default for SeededFibonacciAir<F> {
+    fn default() -> Self {
+        Self::new(F::one(), F::one())
+    }
+}
+
+impl<F: Sync> BaseAir<F> for SeededFibonacciAir<F> {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SeededFibonacciAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &FibonacciCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &FibonacciCols<AB::Var> = main.row_slice(1).borrow();
+        self.eval_rows(builder, local, next);
+    }
+}
+
+impl<F: Copy> SeededFibonacciAir<F> {
+    /// Applies this AIR's constraints to a pair of adjacent rows, wherever they sit in the trace.
+    pub(crate) fn eval_rows<AB: AirBuilderWithPublicValues<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &FibonacciCols<AB::Var>,
+        next: &FibonacciCols<AB::Var>,
+    ) {
+        let output = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(self.a0, local.a);
+        builder.when_first_row().assert_eq(self.b0, local.b);
+
+        // c = a + b
+        builder.assert_eq(local.a + local.b, local.c);
+
+        // a' <- b
+        builder.when_transition().assert_eq(local.b, next.a);
+
+        // b' <- c
+        builder.when_transition().assert_eq(local.c, next.b);
+
+        builder.when_last_row().assert_eq(local.c, output);
+    }
+}
+
+/// `SeededFibonacciAir` with each row padded to `NUM_FIBONACCI_COLS_PADDED = 4` columns, a power of two,
+/// by a column constrained to zero.
+///
+/// Rows of three columns straddle the lanes of packed fields, so transposing and hashing the trace
+/// is slower than for a power-of-two width. The padding costs a third more trace, but can win that
+/// back in throughput; see the `fibonacci_padding` bench. The constraints and public value are
+/// otherwise those of `SeededFibonacciAir`.
+pub struct PaddedFibonacciAir<F> {
+    pub air: SeededFibonacciAir<F>,
+}
+
+impl<F> PaddedFibonacciAir<F> {
+    pub fn new(air: SeededFibonacciAir<F>) -> Self {
+        Self { air }
+    }
+}
+
+impl<F: AbstractField> PaddedFibonacciAir<F> {
+    /// Generates a trace with `num_rows` rows, holding `SeededFibonacciAir::generate_trace`'s
+    /// rows followed by a zero.
+    pub fn generate_trace(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        let trace = self.air.generate_trace(num_rows);
+        let mut values = Vec::with_capacity(num_rows * NUM_FIBONACCI_COLS_PADDED);
+        for row in trace.rows() {
+            values.extend_from_slice(row);
+            values.push(F::zero());
+        }
+        RowMajorMatrix::new(values, NUM_FIBONACCI_COLS_PADDED)
+    }
+
+    /// See `SeededFibonacciAir::expected_output`.
+    pub fn expected_output(&self, num_rows: usize) -> F {
+        self.air.expected_output(num_rows)
+    }
+}
+
+impl<F: Sync> BaseAir<F> for PaddedFibonacciAir<F> {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS_PADDED
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for PaddedFibonacciAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &FibonacciColsPadded<AB::Var> = main.row_slice(0).borrow();
+        let next: &FibonacciColsPadded<AB::Var> = main.row_slice(1).borrow();
+
+        builder.assert_zero(local.pad);
+        self.air.eval_rows(builder, &local.cols, &next.cols);
+    }
+}
+
+/// Generates a Fibonacci trace with `num_rows` rows, consistent with the constraints of
+/// `FibonacciAir`.
+///
+/// Row `i` holds the terms `F(i + 1), F(i + 2), F(i + 3)`, where `F(1) = F(2) = 1`.
+pub fn generate_fibonacci_trace<F: AbstractField>(num_rows: usize) -> RowMajorMatrix<F> {
+    generate_fibonacci_trace_from_seeds(F::one(), F::one(), num_rows)
+}
+
+/// Generates a trace of the Fibonacci-like sequence starting with `a0, b0`, with `num_rows` rows.
+pub fn generate_fibonacci_trace_from_seeds<F: AbstractField>(
+    a0: F,
+    b0: F,
+    num_rows: usize,
+) -> RowMajorMatrix<F> {
+    let mut builder = FibonacciTraceBuilder::new(num_rows);
+    let (mut a, mut b) = (a0, b0);
+    for _ in 0..num_rows {
+        let c = a.clone() + b.clone();
+        builder.push(a, b.clone());
+        (a, b) = (b, c);
+    }
+    builder.build()
+}
+
+/// The values of `trace` as canonical `u64`s, one `Vec` per row, e.g. for cross-checking a trace
+/// against a Fibonacci computation done outside the field.
+pub fn trace_to_u64s<F: PrimeField64>(trace: &RowMajorMatrix<F>) -> Vec<Vec<u64>> {
+    trace
+        .rows()
+        .map(|row| row.iter().map(|x| x.as_canonical_u64()).collect())
+        .collect()
+}
+
+/// Builds a Fibonacci trace one row at a time, writing each row directly into a matrix allocated
+/// upfront.
+///
+/// The builder doesn't check that consecutive rows follow the recurrence; that's left to the AIR.
+pub struct FibonacciTraceBuilder<F> {
+    trace: RowMajorMatrix<F>,
+    num_pushed: usize,
+}
+
+impl<F: AbstractField> FibonacciTraceBuilder<F> {
+    /// Creates a builder for a trace with `num_rows` rows, which must be a power of two.
+    pub fn new(num_rows: usize) -> Self {
+        assert!(
+            num_rows.is_power_of_two(),
+            "number of rows must be a power of two, got {}",
+            num_rows
+        );
+        let trace = RowMajorMatrix::new(
+            vec![F::zero(); num_rows * NUM_FIBONACCI_COLS],
+            NUM_FIBONACCI_COLS,
+        );
+        Self {
+            trace,
+            num_pushed: 0,
+        }
+    }
+
+    /// Appends the row `a, b, a + b`.
+    ///
+    /// # Panics
+    /// Panics if the trace is already full.
+    pub fn push(&mut self, a: F, b: F) {
+        assert!(
+            self.num_pushed < self.trace.height(),
+            "trace is already full"
+        );
+        let row: &mut FibonacciCols<F> = self.trace.row_mut(self.num_pushed).borrow_mut();
+        row.c = a.clone() + b.clone();
+        row.a = a;
+        row.b = b;
+        self.num_pushed += 1;
+    }
+
+    /// The number of rows pushed so far.
+    pub fn len(&self) -> usize {
+        self.num_pushed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_pushed == 0
+    }
+
+    /// Returns the finished trace.
+    ///
+    /// # Panics
+    /// Panics if fewer rows were pushed than the trace has.
+    pub fn build(self) -> RowMajorMatrix<F> {
+        assert_eq!(
+            self.num_pushed,
+            self.trace.height(),
+            "trace has {} rows, but only {} were pushed",
+            self.trace.height(),
+            self.num_pushed
+        );
+        self.trace
+    }
+}
+
+pub const NUM_FIBONACCI_COLS: usize = size_of::<FibonacciCols<u8>>();
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FibonacciCols<T> {
+    pub a: T,
+    pub b: T,
+    /// Always equal to `a + b`.
+    pub c: T,
+}
+
+impl<T> FibonacciCols<T> {
+    /// Views a row of `NUM_FIBONACCI_COLS` elements as a `FibonacciCols`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_COLS`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_COLS,
+            slice.len()
+        );
+        // SAFETY: `FibonacciCols<T>` is `repr(C)` and every field has type `T`, so it has the same
+        // size and alignment as `[T; NUM_FIBONACCI_COLS]`, with no padding. We checked the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+
+    /// Like `from_slice`, but for a mutable row.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_COLS`, in every build profile.
+    pub fn from_slice_mut(slice: &mut [T]) -> &mut Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_COLS,
+            slice.len()
+        );
+        // SAFETY: See `from_slice`.
+        unsafe { &mut *(slice.as_mut_ptr() as *mut Self) }
+    }
+}
+
+impl<T: Copy> FibonacciCols<T> {
+    /// The columns `[a, b, c]`, in the order they appear in a trace row.
+    pub fn as_array(&self) -> [T; NUM_FIBONACCI_COLS] {
+        // SAFETY: See `from_slice`.
+        unsafe { *(self as *const Self as *const [T; NUM_FIBONACCI_COLS]) }
+    }
+
+    /// The inverse of `as_array`.
+    pub fn from_array(array: [T; NUM_FIBONACCI_COLS]) -> Self {
+        *Self::from_slice(&array)
+    }
+}
+
+impl<T> From<(T, T, T)> for FibonacciCols<T> {
+    fn from((a, b, c): (T, T, T)) -> Self {
+        Self { a, b, c }
+    }
+}
+
+impl<T> From<FibonacciCols<T>> for (T, T, T) {
+    fn from(cols: FibonacciCols<T>) -> Self {
+        (cols.a, cols.b, cols.c)
+    }
+}
+
+pub const NUM_FIBONACCI_COLS_PADDED: usize = size_of::<FibonacciColsPadded<u8>>();
+
+/// The row layout of `PaddedFibonacciAir`.
+#[repr(C)]
+pub struct FibonacciColsPadded<T> {
+    pub cols: FibonacciCols<T>,
+    /// Always zero.
+    pub pad: T,
+}
+
+impl<T> FibonacciColsPadded<T> {
+    /// Views a row of `NUM_FIBONACCI_COLS_PADDED` elements as a `FibonacciColsPadded`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_COLS_PADDED`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_COLS_PADDED,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_COLS_PADDED,
+            slice.len()
+        );
+        // SAFETY: `FibonacciColsPadded<T>` is `repr(C)` and its fields are a `FibonacciCols<T>`,
+        // which is itself made of `T`s, and a `T`, so it has the same size and alignment as
+        // `[T; NUM_FIBONACCI_COLS_PADDED]`, with no padding. We checked the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+}
+
+impl<T> Borrow<FibonacciColsPadded<T>> for [T] {
+    fn borrow(&self) -> &FibonacciColsPadded<T> {
+        FibonacciColsPadded::from_slice(self)
+    }
+}
+
+impl<T> Borrow<FibonacciCols<T>> for [T] {
+    fn borrow(&self) -> &FibonacciCols<T> {
+        FibonacciCols::from_slice(self)
+    }
+}
+
+impl<T> BorrowMut<FibonacciCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut FibonacciCols<T> {
+        FibonacciCols::from_slice_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_air::TwoRowMatrixView;
+    use p3_baby_bear::BabyBear;
+    use p3_field::{Field, PackedField, PackedValue};
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    /// Evaluates constraints over packed values, one row per lane, recording the value of each.
+    struct LaneBuilder<'a, P: PackedField> {
+        main: TwoRowMatrixView<'a, P>,
+        public_values: &'a [P::Scalar],
+        is_first_row: P,
+        is_last_row: P,
+        is_transition: P,
+        constraints: Vec<P>,
+    }
+
+    impl<'a, P: PackedField> AirBuilder for LaneBuilder<'a, P> {
+        type F = P::Scalar;
+        type Expr = P;
+        type Var = P;
+        type M = TwoRowMatrixView<'a, P>;
+
+        fn main(&self) -> Self::M {
+            self.main
+        }
+
+        fn is_first_row(&self) -> Self::Expr {
+            self.is_first_row
+        }
+
+        fn is_last_row(&self) -> Self::Expr {
+            self.is_last_row
+        }
+
+        fn is_transition_window(&self, size: usize) -> Self::Expr {
+            assert_eq!(size, 2, "only a window size of 2 is supported");
+            self.is_transition
+        }
+
+        fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+            self.constraints.push(x.into());
+        }
+    }
+
+    impl<P: PackedField> AirBuilderWithPublicValues for LaneBuilder<'_, P> {
+        fn public_values(&self) -> &[Self::F] {
+            self.public_values
+        }
+    }
+
+    /// The constraint values of rows `start..start + P::WIDTH`, with row `start + i` in lane `i`.
+    fn eval_lanes<P: PackedField<Scalar = BabyBear>>(
+        air: &FibonacciAir,
+        trace: &RowMajorMatrix<BabyBear>,
+        public_values: &[BabyBear],
+        start: usize,
+    ) -> Vec<P> {
+        let height = trace.height();
+        let row = |offset: usize, lane: usize| trace.row_slice((start + lane + offset) % height);
+        let local = (0..NUM_FIBONACCI_COLS)
+            .map(|col| P::from_fn(|lane| row(0, lane)[col]))
+            .collect::<Vec<_>>();
+        let next = (0..NUM_FIBONACCI_COLS)
+            .map(|col| P::from_fn(|lane| row(1, lane)[col]))
+            .collect::<Vec<_>>();
+        let selector = |is_set: &dyn Fn(usize) -> bool| {
+            P::from_fn(|lane| BabyBear::from_bool(is_set(start + lane)))
+        };
+
+        let mut builder = LaneBuilder {
+            main: TwoRowMatrixView::new(&local, &next),
+            public_values,
+            is_first_row: selector(&|r| r == 0),
+            is_last_row: selector(&|r| r == height - 1),
+            is_transition: selector(&|r| r != height - 1),
+            constraints: Vec::new(),
+        };
+        air.eval(&mut builder);
+        builder.constraints
+    }
+
+    #[test]
+    fn test_generate_fibonacci_trace() {
+        let trace = generate_fibonacci_trace::<F>(64);
+        assert_eq!(trace.width, NUM_FIBONACCI_COLS);
+
+        let first: &FibonacciCols<F> = trace.row_slice(0).borrow();
+        assert_eq!(first.a, F::one());
+        assert_eq!(first.b, F::one());
+        assert_eq!(first.c, F::two());
+
+        // The last row holds F(64), F(65), F(66).
+        let last: &FibonacciCols<F> = trace.row_slice(63).borrow();
+        assert_eq!(last.c, F::from_canonical_u64(27_777_890_035_288));
+    }
+
+    #[test]
+    fn test_generate_trace_with_seeds() {
+        // Lucas numbers: 2, 1, 3, 4, 7, 11, 18, 29, 47, 76, ...
+        let air = SeededFibonacciAir::new(F::two(), F::one());
+        let trace = air.generate_trace(8);
+
+        let last: &FibonacciCols<F> = trace.row_slice(7).borrow();
+        assert_eq!(last.c, F::from_canonical_u64(76));
+    }
+
+    #[test]
+    fn test_expected_output() {
+        let air = FibonacciAir {};
+        let trace = air.generate_trace::<F>(64);
+
+        let last: &FibonacciCols<F> = trace.row_slice(63).borrow();
+        assert_eq!(air.expected_output::<F>(64), last.c);
+    }
+
+    #[test]
+    fn test_fibonacci_nth() {
+        let expected = [0, 1, 1, 2, 3, 5, 8, 13, 21, 34];
+        for (n, expected) in expected.into_iter().enumerate() {
+            assert_eq!(
+                fibonacci_nth(n, F::zero(), F::one()),
+                F::from_canonical_u64(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let array = [1, 2, 3].map(F::from_canonical_u8);
+        let cols = FibonacciCols::from_array(array);
+        assert_eq!((cols.a, cols.b, cols.c), (array[0], array[1], array[2]));
+        assert_eq!(cols.as_array(), array);
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let tuple = (F::one(), F::two(), F::from_canonical_u8(3));
+        let cols = FibonacciCols::from(tuple);
+        assert_eq!(cols.as_array(), [tuple.0, tuple.1, tuple.2]);
+        let (a, b, c) = cols.into();
+        assert_eq!((a, b, c), tuple);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a row of 3 elements, got 2")]
+    fn test_from_slice_wrong_len() {
+        let row = [F::one(), F::one()];
+        FibonacciCols::from_slice(&row);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a row of 4 elements, got 3")]
+    fn test_padded_from_slice_wrong_len() {
+        let row = [F::one(), F::one(), F::two()];
+        FibonacciColsPadded::from_slice(&row);
+    }
+
+    #[test]
+    fn test_trace_builder() {
+        let mut builder = FibonacciTraceBuilder::new(4);
+        for (a, b) in [(3, 5), (5, 8), (8, 13), (13, 21)] {
+            builder.push(F::from_canonical_u8(a), F::from_canonical_u8(b));
+        }
+        assert_eq!(builder.len(), 4);
+        let trace = builder.build();
+
+        let expected = SeededFibonacciAir::new(F::from_canonical_u8(3), F::from_canonical_u8(5));
+        assert_eq!(trace.values, expected.generate_trace(4).values);
+    }
+
+    #[test]
+    #[should_panic(expected = "trace has 4 rows, but only 1 were pushed")]
+    fn test_trace_builder_incomplete() {
+        let mut builder = FibonacciTraceBuilder::new(4);
+        builder.push(F::one(), F::one());
+        builder.build();
+    }
+
+    #[test]
+    fn test_trace_to_u64s() {
+        let trace = generate_fibonacci_trace::<F>(4);
+        assert_eq!(
+            trace_to_u64s(&trace),
+            vec![vec![1, 1, 2], vec![1, 2, 3], vec![2, 3, 5], vec![3, 5, 8]]
+        );
+    }
+
+    #[test]
+    fn test_padded_trace() {
+        let air = PaddedFibonacciAir::new(SeededFibonacciAir::<F>::default());
+        let trace = air.generate_trace(8);
+        assert_eq!(trace.width, NUM_FIBONACCI_COLS_PADDED);
+        assert_eq!(NUM_FIBONACCI_COLS_PADDED, 4);
+
+        let unpadded = air.air.generate_trace(8);
+        for (row, unpadded_row) in trace.rows().zip(unpadded.rows()) {
+            let padded: &FibonacciColsPadded<F> = row.borrow();
+            assert_eq!(padded.cols.as_array()[..], unpadded_row[..]);
+            assert_eq!(padded.pad, F::zero());
+        }
+        assert_eq!(air.expected_output(8), air.air.expected_output(8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_fibonacci_trace_non_power_of_two() {
+        generate_fibonacci_trace::<F>(48);
+    }
+
+    #[test]
+    fn test_packed_eval_matches_scalar() {
+        type P = <BabyBear as Field>::Packing;
+        const NUM_ROWS: usize = 16;
+
+        let air = FibonacciAir {};
+        let mut trace = air.generate_trace::<BabyBear>(NUM_ROWS);
+        // Break a row, and claim the wrong output, so that some constraints are nonzero.
+        trace.row_mut(5)[2] += BabyBear::one();
+        let public_values = [air.expected_output::<BabyBear>(NUM_ROWS) + BabyBear::one()];
+
+        let scalar = (0..NUM_ROWS)
+            .map(|r| eval_lanes::<BabyBear>(&air, &trace, &public_values, r))
+            .collect::<Vec<_>>();
+        assert!(scalar.iter().flatten().any(|c| !c.is_zero()));
+
+        for start in (0..NUM_ROWS).step_by(P::WIDTH) {
+            let packed = eval_lanes::<P>(&air, &trace, &public_values, start);
+            for lane in 0..P::WIDTH {
+                let lane_values = packed
+                    .iter()
+                    .map(|c| c.as_slice()[lane])
+                    .collect::<Vec<_>>();
+                assert_eq!(lane_values, scalar[start + lane], "row {}", start + lane);
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use alloc::vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+
+/// The number of bits in each range check, which bounds the modulus.
+pub const RANGE_CHECK_BITS: usize = 16;
+
+/// An AIR for the Fibonacci sequence reduced modulo `m`, i.e. with `c = (a + b) mod m`.
+///
+/// Field arithmetic only wraps at the field's own modulus, so the reduction is proven explicitly:
+/// each row holds the quotient `q` of `a + b` by `m`, and the constraints are `a + b = q m + c`
+/// with `c < m`. The bound on `c` is a range check showing both `c` and `m - 1 - c` fit in
+/// `RANGE_CHECK_BITS` bits. Since `a` and `b` are themselves earlier remainders, or seeds below
+/// `m`, `q` is always 0 or 1.
+///
+/// As with `FibonacciAir`, the single public value is the `c` column of the last row. The seeds
+/// are `a0 = b0 = 1`.
+///
+/// Assumes `m <= 2^RANGE_CHECK_BITS` and that the field size is at least `RANGE_CHECK_BITS + 2`
+/// bits, so that the range checks can't wrap around.
+pub struct FibonacciModAir {
+    pub m: u32,
+}
+
+impl FibonacciModAir {
+    pub fn new(m: u32) -> Self {
+        assert!(
+            (2..=1 << RANGE_CHECK_BITS).contains(&m),
+            "modulus must be in 2..=2^{}, got {}",
+            RANGE_CHECK_BITS,
+            m
+        );
+        Self { m }
+    }
+
+    /// Generates a trace with `num_rows` rows, which must be a power of two.
+    pub fn generate_trace<F: AbstractField>(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        assert!(
+            num_rows.is_power_of_two(),
+            "number of rows must be a power of two, got {}",
+            num_rows
+        );
+        let mut trace = RowMajorMatrix::new(
+            vec![F::zero(); num_rows * NUM_FIBONACCI_MOD_COLS],
+            NUM_FIBONACCI_MOD_COLS,
+        );
+
+        let (mut a, mut b) = (1, 1);
+        for row in trace.values.chunks_exact_mut(NUM_FIBONACCI_MOD_COLS) {
+            let cols: &mut FibonacciModCols<F> = row.borrow_mut();
+            let (q, c) = ((a + b) / self.m, (a + b) % self.m);
+            cols.a = F::from_canonical_u32(a);
+            cols.b = F::from_canonical_u32(b);
+            cols.q = F::from_canonical_u32(q);
+            cols.c = F::from_canonical_u32(c);
+            cols.c_bits = bits(c);
+            cols.c_slack_bits = bits(self.m - 1 - c);
+            (a, b) = (b, c);
+        }
+
+        trace
+    }
+
+    /// The value of `c` in the last row of a `num_rows`-row trace, which should be passed as the
+    /// public value when proving and verifying.
+    pub fn expected_output<F: AbstractField>(&self, num_rows: usize) -> F {
+        let (mut a, mut b) = (1, 1);
+        for _ in 0..num_rows {
+            (a, b) = (b, (a + b) % self.m);
+        }
+        F::from_canonical_u32(b)
+    }
+}
+
+/// The little-endian bits of `x`, which must be less than `2^RANGE_CHECK_BITS`.
+fn bits<F: AbstractField>(x: u32) -> [F; RANGE_CHECK_BITS] {
+    debug_assert!(x < 1 << RANGE_CHECK_BITS);
+    core::array::from_fn(|i| F::from_bool((x >> i) & 1 == 1))
+}
+
+/// Constrains `value` to be the number whose little-endian bits are `bits`, which shows it's less
+/// than `2^RANGE_CHECK_BITS`.
+fn eval_range_check<AB: AirBuilder>(
+    builder: &mut AB,
+    value: AB::Expr,
+    bits: &[AB::Var; RANGE_CHECK_BITS],
+) {
+    for &bit in bits {
+        builder.assert_bool(bit);
+    }
+    let sum = bits
+        .iter()
+        .rev()
+        .fold(AB::Expr::zero(), |acc, &bit| acc.double() + bit);
+    builder.assert_eq(value, sum);
+}
+
+impl<F: Sync> BaseAir<F> for FibonacciModAir {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_MOD_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciModAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let output = pis[0];
+        let m = AB::F::from_canonical_u32(self.m);
+
+        let local: &FibonacciModCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &FibonacciModCols<AB::Var> = main.row_slice(1).borrow();
+
+        builder.when_first_row().assert_one(local.a);
+        builder.when_first_row().assert_one(local.b);
+
+        // a + b = q m + c, with q in {0, 1} and 0 <= c < m.
+        builder.assert_bool(local.q);
+        builder.assert_eq(local.a + local.b, local.q * m + local.c);
+        eval_range_check(builder, local.c.into(), &local.c_bits);
+        eval_range_check(
+            builder,
+            AB::Expr::from(m) - AB::F::one() - local.c,
+            &local.c_slack_bits,
+        );
+
+        // a' <- b
+        builder.when_transition().assert_eq(local.b, next.a);
+
+        // b' <- c
+        builder.when_transition().assert_eq(local.c, next.b);
+
+        builder.when_last_row().assert_eq(local.c, output);
+    }
+}
+
+pub const NUM_FIBONACCI_MOD_COLS: usize = size_of::<FibonacciModCols<u8>>();
+
+#[repr(C)]
+pub struct FibonacciModCols<T> {
+    pub a: T,
+    pub b: T,
+    /// The quotient `(a + b) / m`, rounded down.
+    pub q: T,
+    /// The remainder `(a + b) mod m`.
+    pub c: T,
+    /// The little-endian bits of `c`.
+    pub c_bits: [T; RANGE_CHECK_BITS],
+    /// The little-endian bits of `m - 1 - c`, which show that `c < m`.
+    pub c_slack_bits: [T; RANGE_CHECK_BITS],
+}
+
+impl<T> FibonacciModCols<T> {
+    /// Views a row of `NUM_FIBONACCI_MOD_COLS` elements as a `FibonacciModCols`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_MOD_COLS`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_MOD_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_MOD_COLS,
+            slice.len()
+        );
+        // SAFETY: `FibonacciModCols<T>` is `repr(C)` and every field is a `T` or an array of `T`s,
+        // so it has the same size and alignment as `[T; NUM_FIBONACCI_MOD_COLS]`, with no padding.
+        // We checked the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+
+    /// Like `from_slice`, but for a mutable row.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_MOD_COLS`, in every build profile.
+    pub fn from_slice_mut(slice: &mut [T]) -> &mut Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_MOD_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_MOD_COLS,
+            slice.len()
+        );
+        // SAFETY: See `from_slice`.
+        unsafe { &mut *(slice.as_mut_ptr() as *mut Self) }
+    }
+}
+
+impl<T> Borrow<FibonacciModCols<T>> for [T] {
+    fn borrow(&self) -> &FibonacciModCols<T> {
+        FibonacciModCols::from_slice(self)
+    }
+}
+
+impl<T> BorrowMut<FibonacciModCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut FibonacciModCols<T> {
+        FibonacciModCols::from_slice_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_generate_trace_mod_7() {
+        let air = FibonacciModAir::new(7);
+        let trace = air.generate_trace::<F>(32);
+
+        // The Fibonacci sequence mod 7 has period 16, starting from its third term.
+        let cs = (0..32)
+            .map(|r| {
+                let row: &FibonacciModCols<F> = trace.row_slice(r).borrow();
+                row.c
+            })
+            .collect::<Vec<_>>();
+        let expected = [2, 3, 5, 1, 6, 0, 6, 6, 5, 4, 2, 6, 1, 0, 1, 1].map(F::from_canonical_u32);
+        assert_eq!(cs[..16], expected);
+        assert_eq!(cs[16..], expected);
+        assert_eq!(air.expected_output::<F>(32), F::one());
+
+        // 6 + 6 = 12 wraps, so row 8 has quotient 1 and remainder 5.
+        let row: &FibonacciModCols<F> = trace.row_slice(8).borrow();
+        assert_eq!((row.q, row.c), (F::one(), F::from_canonical_u32(5)));
+        assert_eq!(row.c_slack_bits, bits(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a row of 36 elements, got 4")]
+    fn test_from_slice_wrong_len() {
+        let row = [F::zero(); 4];
+        FibonacciModCols::from_slice(&row);
+    }
+}
@@ -0,0 +1,166 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRowSlices};
+
+use crate::{fibonacci_nth, FibonacciCols, SeededFibonacciAir};
+
+/// `SeededFibonacciAir` with an extra column holding a running sum of the sequence.
+///
+/// Each row's `sum` is the sum of the terms before its `c`, so it starts at `a + b` on the first row
+/// and each transition adds the row's `c`. Summing the first `n` terms of the Fibonacci sequence
+/// gives `F(n + 2) - 1`, which makes the sum a cross-check on the sequence itself.
+///
+/// The public values are `SeededFibonacciAir`'s output, followed by the `sum` column of the last row. See
+/// `public_values`.
+pub struct FibonacciSumAir<F> {
+    pub air: SeededFibonacciAir<F>,
+}
+
+impl<F> FibonacciSumAir<F> {
+    pub fn new(air: SeededFibonacciAir<F>) -> Self {
+        Self { air }
+    }
+}
+
+impl<F: AbstractField> FibonacciSumAir<F> {
+    /// Generates a trace with `num_rows` rows, holding `SeededFibonacciAir::generate_trace`'s
+    /// rows followed by their running sum.
+    pub fn generate_trace(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        let trace = self.air.generate_trace(num_rows);
+        let mut values = Vec::with_capacity(num_rows * NUM_FIBONACCI_SUM_COLS);
+        let mut sum = self.air.a0.clone() + self.air.b0.clone();
+        for row in trace.rows() {
+            let cols: &FibonacciCols<F> = row.borrow();
+            values.extend_from_slice(row);
+            values.push(sum.clone());
+            sum += cols.c.clone();
+        }
+        RowMajorMatrix::new(values, NUM_FIBONACCI_SUM_COLS)
+    }
+
+    /// The value of `sum` in the last row of a `num_rows`-row trace.
+    pub fn expected_sum(&self, num_rows: usize) -> F {
+        // The last row sums the first `num_rows + 1` terms. The first `n` terms of the sequence
+        // sum to the term `n + 1` less `b0`, as each term is the difference of the two after it.
+        fibonacci_nth(num_rows + 2, self.air.a0.clone(), self.air.b0.clone()) - self.air.b0.clone()
+    }
+
+    /// The public values for proving a `num_rows`-row trace, i.e. the output and the sum.
+    pub fn public_values(&self, num_rows: usize) -> Vec<F> {
+        vec![
+            self.air.expected_output(num_rows),
+            self.expected_sum(num_rows),
+        ]
+    }
+}
+
+impl<F: Sync> BaseAir<F> for FibonacciSumAir<F> {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_SUM_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciSumAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &FibonacciSumCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &FibonacciSumCols<AB::Var> = main.row_slice(1).borrow();
+        let sum = builder.public_values()[1];
+
+        self.air.eval_rows(builder, &local.cols, &next.cols);
+
+        // The first row's `a` and `b` are the seeds, which no earlier terms precede.
+        builder
+            .when_first_row()
+            .assert_eq(local.sum, local.cols.a + local.cols.b);
+
+        // sum' <- sum + c
+        builder
+            .when_transition()
+            .assert_eq(next.sum, local.sum + local.cols.c);
+
+        builder.when_last_row().assert_eq(local.sum, sum);
+    }
+}
+
+pub const NUM_FIBONACCI_SUM_COLS: usize = size_of::<FibonacciSumCols<u8>>();
+
+/// The row layout of `FibonacciSumAir`.
+#[repr(C)]
+pub struct FibonacciSumCols<T> {
+    pub cols: FibonacciCols<T>,
+    /// The sum of the sequence's terms before `cols.c`.
+    pub sum: T,
+}
+
+impl<T> FibonacciSumCols<T> {
+    /// Views a row of `NUM_FIBONACCI_SUM_COLS` elements as a `FibonacciSumCols`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_FIBONACCI_SUM_COLS`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_FIBONACCI_SUM_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_FIBONACCI_SUM_COLS,
+            slice.len()
+        );
+        // SAFETY: `FibonacciSumCols<T>` is `repr(C)` and its fields are a `FibonacciCols<T>`, which
+        // is itself made of `T`s, and a `T`, so it has the same size and alignment as
+        // `[T; NUM_FIBONACCI_SUM_COLS]`, with no padding. We checked the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+}
+
+impl<T> Borrow<FibonacciSumCols<T>> for [T] {
+    fn borrow(&self) -> &FibonacciSumCols<T> {
+        FibonacciSumCols::from_slice(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_sum_matches_closed_form() {
+        let air = FibonacciSumAir::new(SeededFibonacciAir::<F>::default());
+        let trace = air.generate_trace(8);
+        assert_eq!(trace.width, NUM_FIBONACCI_SUM_COLS);
+
+        // 1 + 1 + 2 + 3 + 5 + 8 + 13 + 21 + 34 = F(11) - 1.
+        let last: &FibonacciSumCols<F> = trace.row_slice(7).borrow();
+        assert_eq!(last.sum, F::from_canonical_u64(88));
+        assert_eq!(last.sum, fibonacci_nth(10, F::one(), F::one()) - F::one());
+        assert_eq!(air.public_values(8), [last.cols.c, last.sum]);
+    }
+
+    #[test]
+    fn test_sum_with_seeds() {
+        // Lucas numbers: 2 + 1 + 3 + 4 + 7 = 17.
+        let air = FibonacciSumAir::new(SeededFibonacciAir::new(F::two(), F::one()));
+        let trace = air.generate_trace(4);
+
+        let last: &FibonacciSumCols<F> = trace.row_slice(3).borrow();
+        assert_eq!(last.sum, F::from_canonical_u64(17));
+        assert_eq!(air.expected_sum(4), last.sum);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a row of 4 elements, got 3")]
+    fn test_from_slice_wrong_len() {
+        let row = [F::one(), F::one(), F::two()];
+        FibonacciSumCols::from_slice(&row);
+    }
+}
@@ -1,45 +1,140 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use core::iter;
 
 use p3_field::PrimeField64;
 use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use p3_maybe_rayon::should_parallelize;
 use tracing::instrument;
 
 use crate::columns::{KeccakCols, NUM_KECCAK_COLS};
 use crate::constants::rc_value_limb;
-use crate::logic::{andn, xor};
-use crate::{BITS_PER_LIMB, NUM_ROUNDS, U64_LIMBS};
+use crate::logic::{andn, limb_bits, limb_of_bit, limbs_to_u64, u64_to_limbs, xor};
+use crate::{NUM_ROUNDS, U64_LIMBS};
 
-#[instrument(name = "generate Keccak trace", skip_all)]
+/// Generates a trace for the given Keccak-f inputs, padded with permutations of the zero state up
+/// to a power-of-two height.
+///
+/// Each permutation is independent of the others, so with the `parallel` feature they're
+/// generated in parallel. Without it, generation needs only `core` and `alloc`, so it works in
+/// `no_std` environments.
 pub fn generate_trace_rows<F: PrimeField64>(inputs: Vec<[u64; 25]>) -> RowMajorMatrix<F> {
-    let num_rows = (inputs.len() * NUM_ROUNDS).next_power_of_two();
+    let num_rows = min_trace_height(inputs.len());
+    generate_trace_rows_padded(inputs, num_rows)
+}
+
+/// The height of the smallest trace of `num_inputs` permutations, i.e. the smallest power of two
+/// at or above their `24 * num_inputs` rows.
+pub const fn min_trace_height(num_inputs: usize) -> usize {
+    (num_inputs * NUM_ROUNDS).next_power_of_two()
+}
+
+/// Like `generate_trace_rows`, but the trace has exactly `num_rows` rows, as chosen by the caller.
+///
+/// `min_trace_height(inputs.len())` is the smallest height possible, with only as much padding as
+/// a power-of-two height requires; a larger one suits e.g. traces which must share a domain.
+/// Padding rows are never all-zero: they hold permutations of the zero state, which satisfy every
+/// constraint of `KeccakAir` like any real permutation. If the height isn't a multiple of 24, the
+/// final padding permutation is cut short; this is fine, as no transition constraints apply to the
+/// last row.
+///
+/// # Panics
+/// Panics if `num_rows` isn't a power of two, or is less than `24 * inputs.len()`.
+#[instrument(name = "generate Keccak trace", skip_all)]
+pub fn generate_trace_rows_padded<F: PrimeField64>(
+    mut inputs: Vec<[u64; 25]>,
+    num_rows: usize,
+) -> RowMajorMatrix<F> {
+    assert!(
+        num_rows.is_power_of_two(),
+        "the trace height {} isn't a power of two",
+        num_rows
+    );
+    assert!(
+        num_rows >= inputs.len() * NUM_ROUNDS,
+        "a trace of {} rows can't hold {} permutations",
+        num_rows,
+        inputs.len()
+    );
+    let mut trace =
+        RowMajorMatrix::new(vec![F::zero(); num_rows * NUM_KECCAK_COLS], NUM_KECCAK_COLS);
+    let rows = keccak_rows_mut(&mut trace);
+
+    let num_perms = num_rows.div_ceil(NUM_ROUNDS);
+    inputs.resize(num_perms, [0; 25]);
+    if should_parallelize(num_rows) {
+        rows.par_chunks_mut(NUM_ROUNDS)
+            .zip(inputs)
+            .for_each(|(rows, input)| generate_trace_rows_for_perm(rows, input));
+    } else {
+        rows.chunks_mut(NUM_ROUNDS)
+            .zip(inputs)
+            .for_each(|(rows, input)| generate_trace_rows_for_perm(rows, input));
+    }
+
+    trace
+}
+
+/// Generates a trace for a chain of `num_perms` Keccak-f permutations, the first starting from
+/// `state` and each of the others from the previous one's output, padded as in
+/// `generate_trace_rows`.
+///
+/// Each permutation's input depends on the one before, so unlike in `generate_trace_rows` they're
+/// generated one at a time. Note that `KeccakAir` constrains each permutation on its own and
+/// doesn't check the links between them; a caller relying on them must enforce them, e.g. with a
+/// lookup.
+#[instrument(name = "generate chained Keccak trace", skip_all)]
+pub fn generate_trace_rows_from_states<F: PrimeField64>(
+    mut state: [u64; 25],
+    num_perms: usize,
+) -> RowMajorMatrix<F> {
+    let num_rows = min_trace_height(num_perms);
     let mut trace =
         RowMajorMatrix::new(vec![F::zero(); num_rows * NUM_KECCAK_COLS], NUM_KECCAK_COLS);
+    let mut perms = keccak_rows_mut(&mut trace).chunks_mut(NUM_ROUNDS);
+    for rows in perms.by_ref().take(num_perms) {
+        generate_trace_rows_for_perm(rows, state);
+        state = perm_output(&rows[NUM_ROUNDS - 1]);
+    }
+    for rows in perms {
+        generate_trace_rows_for_perm(rows, [0; 25]);
+    }
+    trace
+}
+
+pub(crate) fn keccak_rows_mut<F: PrimeField64>(
+    trace: &mut RowMajorMatrix<F>,
+) -> &mut [KeccakCols<F>] {
+    let num_rows = trace.values.len() / NUM_KECCAK_COLS;
     let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<KeccakCols<F>>() };
     assert!(prefix.is_empty(), "Alignment should match");
     assert!(suffix.is_empty(), "Alignment should match");
     assert_eq!(rows.len(), num_rows);
+    rows
+}
 
-    let padded_inputs = inputs.into_iter().chain(iter::repeat([0; 25]));
-    for (row, input) in rows.chunks_mut(NUM_ROUNDS).zip(padded_inputs) {
-        generate_trace_rows_for_perm(row, input);
-    }
-
-    trace
+/// Reads a permutation's output state, in y-major order, from the row of its final round.
+pub(crate) fn perm_output<F: PrimeField64>(row: &KeccakCols<F>) -> [u64; 25] {
+    core::array::from_fn(|i| {
+        let (y, x) = (i / 5, i % 5);
+        let limbs = core::array::from_fn(|limb| {
+            row.a_prime_prime_prime(x, y, limb).as_canonical_u64() as u16
+        });
+        limbs_to_u64(limbs)
+    })
 }
 
 /// `rows` will normally consist of 24 rows, with an exception for the final row.
-fn generate_trace_rows_for_perm<F: PrimeField64>(rows: &mut [KeccakCols<F>], input: [u64; 25]) {
+pub(crate) fn generate_trace_rows_for_perm<F: PrimeField64>(
+    rows: &mut [KeccakCols<F>],
+    input: [u64; 25],
+) {
     // Populate the preimage for each row.
     for row in rows.iter_mut() {
         for y in 0..5 {
             for x in 0..5 {
-                let input_xy = input[y * 5 + x];
-                for limb in 0..U64_LIMBS {
-                    row.preimage[y][x][limb] =
-                        F::from_canonical_u64((input_xy >> (16 * limb)) & 0xFFFF);
-                }
+                let input_xy = u64_to_limbs(input[y * 5 + x]);
+                row.preimage[y][x] = input_xy.map(F::from_canonical_u16);
             }
         }
     }
@@ -47,10 +142,8 @@ fn generate_trace_rows_for_perm<F: PrimeField64>(rows: &mut [KeccakCols<F>], inp
     // Populate the round input for the first round.
     for y in 0..5 {
         for x in 0..5 {
-            let input_xy = input[y * 5 + x];
-            for limb in 0..U64_LIMBS {
-                rows[0].a[y][x][limb] = F::from_canonical_u64((input_xy >> (16 * limb)) & 0xFFFF);
-            }
+            let input_xy = u64_to_limbs(input[y * 5 + x]);
+            rows[0].a[y][x] = input_xy.map(F::from_canonical_u16);
         }
     }
 
@@ -76,8 +169,7 @@ fn generate_trace_row_for_round<F: PrimeField64>(row: &mut KeccakCols<F>, round:
     // Populate C[x] = xor(A[x, 0], A[x, 1], A[x, 2], A[x, 3], A[x, 4]).
     for x in 0..5 {
         for z in 0..64 {
-            let limb = z / BITS_PER_LIMB;
-            let bit_in_limb = z % BITS_PER_LIMB;
+            let (limb, bit_in_limb) = limb_of_bit(z);
             let a = (0..5).map(|y| {
                 let a_limb = row.a[y][x][limb].as_canonical_u64() as u16;
                 ((a_limb >> bit_in_limb) & 1) != 0
@@ -104,8 +196,7 @@ fn generate_trace_row_for_round<F: PrimeField64>(row: &mut KeccakCols<F>, round:
     for x in 0..5 {
         for y in 0..5 {
             for z in 0..64 {
-                let limb = z / BITS_PER_LIMB;
-                let bit_in_limb = z % BITS_PER_LIMB;
+                let (limb, bit_in_limb) = limb_of_bit(z);
                 let a_limb = row.a[y][x][limb].as_canonical_u64() as u16;
                 let a_bit = F::from_bool(((a_limb >> bit_in_limb) & 1) != 0);
                 row.a_prime[y][x][z] = xor([a_bit, row.c[x][z], row.c_prime[x][z]]);
@@ -118,25 +209,19 @@ fn generate_trace_row_for_round<F: PrimeField64>(row: &mut KeccakCols<F>, round:
     for y in 0..5 {
         for x in 0..5 {
             for limb in 0..U64_LIMBS {
-                row.a_prime_prime[y][x][limb] = (limb * BITS_PER_LIMB..(limb + 1) * BITS_PER_LIMB)
-                    .rev()
-                    .fold(F::zero(), |acc, z| {
-                        let bit = xor([
-                            row.b(x, y, z),
-                            andn(row.b((x + 1) % 5, y, z), row.b((x + 2) % 5, y, z)),
-                        ]);
-                        acc.double() + bit
-                    });
+                row.a_prime_prime[y][x][limb] = limb_bits(limb).rev().fold(F::zero(), |acc, z| {
+                    let bit = xor([
+                        row.b(x, y, z),
+                        andn(row.b((x + 1) % 5, y, z), row.b((x + 2) % 5, y, z)),
+                    ]);
+                    acc.double() + bit
+                });
             }
         }
     }
 
     // For the XOR, we split A''[0, 0] to bits.
-    let mut val = 0;
-    for limb in 0..U64_LIMBS {
-        let val_limb = row.a_prime_prime[0][0][limb].as_canonical_u64();
-        val |= val_limb << (limb * BITS_PER_LIMB);
-    }
+    let val = limbs_to_u64(row.a_prime_prime[0][0].map(|x| x.as_canonical_u64() as u16));
     let val_bits: Vec<bool> = (0..64)
         .scan(val, |acc, _| {
             let bit = (*acc & 1) != 0;
@@ -155,3 +240,122 @@ fn generate_trace_row_for_round<F: PrimeField64>(row: &mut KeccakCols<F>, round:
             F::from_canonical_u16(row.a_prime_prime[0][0][limb].as_canonical_u64() as u16 ^ rc_lo);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::borrow::Borrow;
+
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::Matrix;
+    use rand::random;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_perms_are_independent() {
+        // Whether or not permutations are generated in parallel, each 24-row block should match
+        // the trace of its input generated on its own.
+        let inputs: Vec<[u64; 25]> = (0..5).map(|_| random()).collect();
+        let trace = generate_trace_rows::<F>(inputs.clone());
+        assert_eq!(trace.height(), 128);
+
+        let block_len = NUM_ROUNDS * NUM_KECCAK_COLS;
+        for (i, input) in inputs.into_iter().enumerate() {
+            let expected = generate_trace_rows::<F>(vec![input]);
+            assert_eq!(
+                &trace.values[i * block_len..(i + 1) * block_len],
+                &expected.values[..block_len]
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_trace_rows_padded() {
+        let inputs: Vec<[u64; 25]> = (0..3).map(|_| random()).collect();
+        let trace = generate_trace_rows::<F>(inputs.clone());
+        let padded = generate_trace_rows_padded::<F>(inputs, 256);
+        assert_eq!(trace.height(), 128);
+        assert_eq!(padded.height(), 256);
+        assert_eq!(&padded.values[..trace.values.len()], &trace.values[..]);
+    }
+
+    #[test]
+    fn test_min_trace_height_adds_no_extra_padding() {
+        // 11 permutations take 264 rows, so the smallest trace has 512, and 248 padding rows: the
+        // rest of the power of two, and no more.
+        let inputs: Vec<[u64; 25]> = (0..11).map(|_| random()).collect();
+        assert_eq!(min_trace_height(inputs.len()), 512);
+        let trace = generate_trace_rows_padded::<F>(inputs.clone(), min_trace_height(inputs.len()));
+        assert_eq!(trace.height(), 512);
+        assert_eq!(trace, generate_trace_rows::<F>(inputs));
+
+        // The padding permutations of the zero state start right after the last real one.
+        let real_len = 11 * NUM_ROUNDS * NUM_KECCAK_COLS;
+        let padding = generate_trace_rows::<F>(vec![[0; 25]; 11]);
+        assert_eq!(&trace.values[real_len..], &padding.values[real_len..]);
+        let first_padding_row: &KeccakCols<F> =
+            trace.values[real_len..][..NUM_KECCAK_COLS].borrow();
+        assert_eq!(first_padding_row.step_flags[0], F::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "a trace of 256 rows can't hold 11 permutations")]
+    fn test_generate_trace_rows_padded_too_short() {
+        generate_trace_rows_padded::<F>(vec![[0; 25]; 11], 256);
+    }
+
+    #[test]
+    #[should_panic(expected = "the trace height 300 isn't a power of two")]
+    fn test_generate_trace_rows_padded_not_power_of_two() {
+        generate_trace_rows_padded::<F>(vec![[0; 25]; 11], 300);
+    }
+
+    #[test]
+    fn test_generate_trace_rows_from_states() {
+        let input: [u64; 25] = random();
+        let first = generate_trace_rows::<F>(vec![input]);
+        let block_len = NUM_ROUNDS * NUM_KECCAK_COLS;
+        let last_row: &KeccakCols<F> =
+            first.values[block_len - NUM_KECCAK_COLS..block_len].borrow();
+        let output = perm_output(last_row);
+
+        let trace = generate_trace_rows_from_states::<F>(input, 3);
+        assert_eq!(trace.height(), 128);
+        assert_eq!(&trace.values[..block_len], &first.values[..block_len]);
+
+        // The second permutation's input is the first one's output.
+        let second_row: &KeccakCols<F> = trace.values[block_len..][..NUM_KECCAK_COLS].borrow();
+        for y in 0..5 {
+            for x in 0..5 {
+                let limbs = second_row.preimage[y][x].map(|limb| limb.as_canonical_u64() as u16);
+                assert_eq!(limbs_to_u64(limbs), output[y * 5 + x]);
+            }
+        }
+
+        // So the whole trace, padding included, is that of the chain's inputs taken on their own.
+        let second = generate_trace_rows::<F>(vec![output]);
+        assert_eq!(
+            &trace.values[block_len..2 * block_len],
+            &second.values[..block_len]
+        );
+        let last_row: &KeccakCols<F> =
+            second.values[block_len - NUM_KECCAK_COLS..block_len].borrow();
+        let unchained = generate_trace_rows::<F>(vec![input, output, perm_output(last_row)]);
+        assert_eq!(trace, unchained);
+    }
+
+    #[test]
+    fn test_preimage_limbs_recompose_to_input() {
+        let input: [u64; 25] = random();
+        let trace = generate_trace_rows::<F>(vec![input]);
+        let row: &KeccakCols<F> = trace.values[..NUM_KECCAK_COLS].borrow();
+        for y in 0..5 {
+            for x in 0..5 {
+                let limbs = row.preimage[y][x].map(|limb| limb.as_canonical_u64() as u16);
+                assert_eq!(limbs_to_u64(limbs), input[y * 5 + x]);
+            }
+        }
+    }
+}
@@ -7,14 +7,31 @@ extern crate alloc;
 mod air;
 mod columns;
 mod constants;
+mod fibonacci_air;
+mod fibonacci_mod_air;
+mod fibonacci_sum_air;
 mod generation;
+mod linear_recurrence_air;
 mod logic;
+mod multi_fibonacci_air;
+mod reverse_fibonacci_air;
 mod round_flags;
+mod sha3;
+mod variable_length_fibonacci_air;
 
 pub use air::*;
 pub use columns::*;
 pub use constants::*;
+pub use fibonacci_air::*;
+pub use fibonacci_mod_air::*;
+pub use fibonacci_sum_air::*;
 pub use generation::*;
+pub use linear_recurrence_air::*;
+pub use logic::*;
+pub use multi_fibonacci_air::*;
+pub use reverse_fibonacci_air::*;
+pub use sha3::*;
+pub use variable_length_fibonacci_air::*;
 
 pub const NUM_ROUNDS: usize = 24;
 const BITS_PER_LIMB: usize = 16;
@@ -0,0 +1,159 @@
+use alloc::vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+
+/// An AIR for an order-`N` linear recurrence
+/// `x[n + N] = coefficients[0] * x[n] + ... + coefficients[N - 1] * x[n + N - 1]`.
+///
+/// Each row holds a sliding window of `N` consecutive terms along with the term that follows
+/// them. For example, `N = 2` with coefficients `[1, 1]` gives Fibonacci, `N = 3` with
+/// coefficients `[1, 1, 1]` gives tribonacci, and `N = 2` with coefficients `[1, 2]` gives Pell.
+pub struct LinearRecurrenceAir<F, const N: usize> {
+    pub coefficients: [F; N],
+    /// The first `N` terms of the sequence.
+    pub seeds: [F; N],
+}
+
+impl<F, const N: usize> LinearRecurrenceAir<F, N> {
+    pub const NUM_COLS: usize = size_of::<LinearRecurrenceCols<u8, N>>();
+
+    pub fn new(coefficients: [F; N], seeds: [F; N]) -> Self {
+        assert!(N >= 1, "recurrence must have order at least 1");
+        Self {
+            coefficients,
+            seeds,
+        }
+    }
+}
+
+impl<F: Field, const N: usize> LinearRecurrenceAir<F, N> {
+    /// Generates a trace with `num_rows` rows, consistent with the constraints of this AIR.
+    pub fn generate_trace(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        assert!(
+            num_rows.is_power_of_two(),
+            "number of rows must be a power of two, got {}",
+            num_rows
+        );
+
+        let mut trace =
+            RowMajorMatrix::new(vec![F::zero(); num_rows * Self::NUM_COLS], Self::NUM_COLS);
+
+        let (prefix, rows, suffix) =
+            unsafe { trace.values.align_to_mut::<LinearRecurrenceCols<F, N>>() };
+        assert!(prefix.is_empty(), "Alignment should match");
+        assert!(suffix.is_empty(), "Alignment should match");
+        assert_eq!(rows.len(), num_rows);
+
+        rows[0].window = self.seeds;
+        rows[0].next = self.next_term(&rows[0].window);
+
+        for i in 1..num_rows {
+            let mut window = [F::zero(); N];
+            window[..N - 1].copy_from_slice(&rows[i - 1].window[1..]);
+            window[N - 1] = rows[i - 1].next;
+            rows[i].next = self.next_term(&window);
+            rows[i].window = window;
+        }
+
+        trace
+    }
+
+    fn next_term(&self, window: &[F; N]) -> F {
+        window
+            .iter()
+            .zip(self.coefficients)
+            .map(|(&x, coeff)| x * coeff)
+            .sum()
+    }
+}
+
+impl<F: Sync, const N: usize> BaseAir<F> for LinearRecurrenceAir<F, N> {
+    fn width(&self) -> usize {
+        Self::NUM_COLS
+    }
+}
+
+impl<AB: AirBuilder, const N: usize> Air<AB> for LinearRecurrenceAir<AB::F, N> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &LinearRecurrenceCols<AB::Var, N> = main.row_slice(0).borrow();
+        let next: &LinearRecurrenceCols<AB::Var, N> = main.row_slice(1).borrow();
+
+        for (&seed, &x) in self.seeds.iter().zip(&local.window) {
+            builder.when_first_row().assert_eq(seed, x);
+        }
+
+        // next = sum_i coefficients[i] * window[i]
+        let computed_next = local
+            .window
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(&x, &coeff)| x * coeff)
+            .fold(AB::Expr::zero(), |acc, term| acc + term);
+        builder.assert_eq(computed_next, local.next);
+
+        // Slide the window along by one term.
+        for (&x, &next_x) in local.window[1..].iter().zip(&next.window) {
+            builder.when_transition().assert_eq(x, next_x);
+        }
+        builder
+            .when_transition()
+            .assert_eq(local.next, next.window[N - 1]);
+    }
+}
+
+#[repr(C)]
+pub struct LinearRecurrenceCols<T, const N: usize> {
+    /// `N` consecutive terms of the sequence.
+    pub window: [T; N],
+    /// The term following `window`.
+    pub next: T,
+}
+
+impl<T, const N: usize> Borrow<LinearRecurrenceCols<T, N>> for [T] {
+    fn borrow(&self) -> &LinearRecurrenceCols<T, N> {
+        debug_assert_eq!(self.len(), N + 1);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<LinearRecurrenceCols<T, N>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T, const N: usize> BorrowMut<LinearRecurrenceCols<T, N>> for [T] {
+    fn borrow_mut(&mut self) -> &mut LinearRecurrenceCols<T, N> {
+        debug_assert_eq!(self.len(), N + 1);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<LinearRecurrenceCols<T, N>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_generate_tribonacci_trace() {
+        // Tribonacci: 0, 0, 1, 1, 2, 4, 7, 13, 24, ...
+        let air = LinearRecurrenceAir::new([F::one(); 3], [F::zero(), F::zero(), F::one()]);
+        let trace = air.generate_trace(8);
+        assert_eq!(trace.width, LinearRecurrenceAir::<F, 3>::NUM_COLS);
+
+        let row: &LinearRecurrenceCols<F, 3> = trace.row_slice(4).borrow();
+        assert_eq!(row.window, [2, 4, 7].map(F::from_canonical_u8));
+        assert_eq!(row.next, F::from_canonical_u8(13));
+    }
+}
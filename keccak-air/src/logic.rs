@@ -1,5 +1,64 @@
+use core::array;
+use core::ops::Range;
+
 use p3_field::{AbstractField, PrimeField64};
 
+use crate::{BITS_PER_LIMB, U64_LIMBS};
+
+/// The order in which the `U64_LIMBS` 16-bit limbs of a 64-bit lane are stored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LimbOrder {
+    /// The least significant limb comes first.
+    LittleEndian,
+    /// The most significant limb comes first.
+    BigEndian,
+}
+
+/// The limb order of every lane in the trace, i.e. in the preimage, the round state, the
+/// postimage and the round constants. Both `generate_trace_rows` and `KeccakAir` derive their
+/// limb layout from this, via `u64_to_limbs` and `limbs_to_u64`, so they always agree.
+///
+/// Callers reading lanes out of the trace, or writing them into its input limbs, should convert
+/// with the same two functions rather than assuming an order. Within a limb, bits are always
+/// little-endian.
+pub const LIMB_ORDER: LimbOrder = LimbOrder::LittleEndian;
+
+/// The index within its lane of the lowest bit stored in limb `limb`.
+pub(crate) const fn limb_shift(limb: usize) -> usize {
+    debug_assert!(limb < U64_LIMBS);
+    match LIMB_ORDER {
+        LimbOrder::LittleEndian => limb * BITS_PER_LIMB,
+        LimbOrder::BigEndian => (U64_LIMBS - 1 - limb) * BITS_PER_LIMB,
+    }
+}
+
+/// The indices within its lane of the bits stored in limb `limb`, least significant first.
+pub(crate) const fn limb_bits(limb: usize) -> Range<usize> {
+    limb_shift(limb)..limb_shift(limb) + BITS_PER_LIMB
+}
+
+/// The limb holding bit `z` of a lane, and the index of that bit within the limb.
+pub(crate) const fn limb_of_bit(z: usize) -> (usize, usize) {
+    debug_assert!(z < 64);
+    let limb = match LIMB_ORDER {
+        LimbOrder::LittleEndian => z / BITS_PER_LIMB,
+        LimbOrder::BigEndian => U64_LIMBS - 1 - z / BITS_PER_LIMB,
+    };
+    (limb, z % BITS_PER_LIMB)
+}
+
+/// Splits a lane into limbs, ordered according to `LIMB_ORDER`.
+pub fn u64_to_limbs(lane: u64) -> [u16; U64_LIMBS] {
+    array::from_fn(|limb| (lane >> limb_shift(limb)) as u16)
+}
+
+/// Recomposes a lane from limbs ordered according to `LIMB_ORDER`. This inverts `u64_to_limbs`.
+pub fn limbs_to_u64(limbs: [u16; U64_LIMBS]) -> u64 {
+    limbs.into_iter().enumerate().fold(0, |acc, (limb, value)| {
+        acc | ((value as u64) << limb_shift(limb))
+    })
+}
+
 pub(crate) fn xor<F: PrimeField64, const N: usize>(xs: [F; N]) -> F {
     xs.into_iter().fold(F::zero(), |acc, x| {
         debug_assert!(x.is_zero() || x.is_one());
@@ -28,3 +87,35 @@ pub(crate) fn andn<F: PrimeField64>(x: F, y: F) -> F {
 pub(crate) fn andn_gen<AF: AbstractField>(x: AF, y: AF) -> AF {
     (AF::one() - x) * y
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::random;
+
+    use super::*;
+
+    #[test]
+    fn test_limbs_round_trip() {
+        for _ in 0..100 {
+            let lane: u64 = random();
+            assert_eq!(limbs_to_u64(u64_to_limbs(lane)), lane);
+        }
+    }
+
+    #[test]
+    fn test_limb_order() {
+        let limbs = u64_to_limbs(0x0123_4567_89ab_cdef);
+        let expected = match LIMB_ORDER {
+            LimbOrder::LittleEndian => [0xcdef, 0x89ab, 0x4567, 0x0123],
+            LimbOrder::BigEndian => [0x0123, 0x4567, 0x89ab, 0xcdef],
+        };
+        assert_eq!(limbs, expected);
+
+        // Each bit is found in the limb which `limb_of_bit` names.
+        for z in 0..64 {
+            let (limb, bit_in_limb) = limb_of_bit(z);
+            assert!(limb_bits(limb).contains(&z));
+            assert_eq!(u64_to_limbs(1 << z)[limb], 1 << bit_in_limb);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+
+use crate::{FibonacciCols, SeededFibonacciAir};
+
+/// An AIR for `num_instances` independent Fibonacci-like sequences, each running for
+/// `rows_per_instance` rows, stacked one after another in a single trace.
+///
+/// Within an instance, each row follows `SeededFibonacciAir`'s recurrence. The last row of each instance
+/// is marked by an `is_last_step` selector, which is derived from a step counter so the prover
+/// can't place it freely. A transition out of such a row doesn't follow the recurrence, but instead
+/// re-seeds `a` and `b` for the next instance. A one-hot set of instance columns tracks which
+/// instance each row belongs to, so that its seeds and output can be bound to public values.
+///
+/// The public values are `a0, b0, output` for each instance in turn, where `output` is the `c`
+/// column of the instance's last row, as with `SeededFibonacciAir::expected_output`.
+pub struct MultiFibonacciAir {
+    pub num_instances: usize,
+    pub rows_per_instance: usize,
+}
+
+impl MultiFibonacciAir {
+    pub fn new(num_instances: usize, rows_per_instance: usize) -> Self {
+        assert!(num_instances >= 1, "need at least one instance");
+        assert!(
+            (num_instances * rows_per_instance).is_power_of_two(),
+            "total number of rows must be a power of two, got {}",
+            num_instances * rows_per_instance
+        );
+        Self {
+            num_instances,
+            rows_per_instance,
+        }
+    }
+
+    /// Generates a trace running instance `i` from the seeds `seeds[i]`.
+    pub fn generate_trace<F: Field>(&self, seeds: &[(F, F)]) -> RowMajorMatrix<F> {
+        assert_eq!(
+            seeds.len(),
+            self.num_instances,
+            "expected one pair of seeds per instance"
+        );
+        let width = <Self as BaseAir<F>>::width(self);
+        let last_step = F::from_canonical_usize(self.rows_per_instance - 1);
+        let mut trace = RowMajorMatrix::new(
+            vec![F::zero(); self.num_instances * self.rows_per_instance * width],
+            width,
+        );
+
+        let mut rows = trace.values.chunks_exact_mut(width);
+        for (instance, &(a0, b0)) in seeds.iter().enumerate() {
+            let (mut a, mut b) = (a0, b0);
+            for step in 0..self.rows_per_instance {
+                let row = rows.next().unwrap();
+                let (cols, instance_flags) = row.split_at_mut(NUM_MULTI_FIBONACCI_COLS);
+                let cols: &mut MultiFibonacciCols<F> = cols.borrow_mut();
+
+                let step = F::from_canonical_usize(step);
+                cols.fib = FibonacciCols { a, b, c: a + b };
+                cols.step = step;
+                if step == last_step {
+                    cols.is_last_step = F::one();
+                } else {
+                    cols.step_minus_last_inv = (step - last_step).inverse();
+                }
+                instance_flags[instance] = F::one();
+
+                (a, b) = (b, a + b);
+            }
+        }
+
+        trace
+    }
+
+    /// The public values for proving the instances started from `seeds`.
+    pub fn public_values<F: AbstractField>(&self, seeds: &[(F, F)]) -> Vec<F> {
+        seeds
+            .iter()
+            .flat_map(|(a0, b0)| {
+                let output = SeededFibonacciAir::new(a0.clone(), b0.clone())
+                    .expected_output(self.rows_per_instance);
+                [a0.clone(), b0.clone(), output]
+            })
+            .collect()
+    }
+}
+
+impl<F: Sync> BaseAir<F> for MultiFibonacciAir {
+    fn width(&self) -> usize {
+        NUM_MULTI_FIBONACCI_COLS + self.num_instances
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for MultiFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let seeds_a = pis.iter().step_by(3).copied().collect::<Vec<_>>();
+        let seeds_b = pis.iter().skip(1).step_by(3).copied().collect::<Vec<_>>();
+        let outputs = pis.iter().skip(2).step_by(3).copied().collect::<Vec<_>>();
+
+        let (local, local_flags) = main.row_slice(0).split_at(NUM_MULTI_FIBONACCI_COLS);
+        let (next, next_flags) = main.row_slice(1).split_at(NUM_MULTI_FIBONACCI_COLS);
+        let local: &MultiFibonacciCols<AB::Var> = local.borrow();
+        let next: &MultiFibonacciCols<AB::Var> = next.borrow();
+
+        // The sum of `flags[i] * values[i]`, i.e. the value for the flagged instance.
+        let select = |flags: &[AB::Var], values: &[AB::F]| {
+            flags
+                .iter()
+                .zip(values)
+                .fold(AB::Expr::zero(), |acc, (&flag, &value)| acc + flag * value)
+        };
+
+        // Exactly one instance flag is set.
+        for &flag in local_flags {
+            builder.assert_bool(flag);
+        }
+        let flag_sum = local_flags
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &flag| acc + flag);
+        builder.assert_one(flag_sum);
+
+        // is_last_step = 1 if step = rows_per_instance - 1, and 0 otherwise.
+        let step_minus_last =
+            local.step.into() - AB::Expr::from_canonical_usize(self.rows_per_instance - 1);
+        builder.assert_eq(
+            local.step_minus_last_inv * step_minus_last.clone(),
+            AB::Expr::one() - local.is_last_step,
+        );
+        builder.assert_zero(local.is_last_step * step_minus_last);
+
+        let not_last_step = AB::Expr::one() - local.is_last_step;
+
+        // The first row starts the first instance.
+        builder.when_first_row().assert_zero(local.step);
+        builder.when_first_row().assert_one(local_flags[0]);
+        builder.when_first_row().assert_eq(local.fib.a, seeds_a[0]);
+        builder.when_first_row().assert_eq(local.fib.b, seeds_b[0]);
+
+        // c = a + b
+        builder.assert_eq(local.fib.a + local.fib.b, local.fib.c);
+
+        // Within an instance, step the counter and the recurrence, and keep the instance flags.
+        let mut in_instance = builder.when_transition();
+        let mut in_instance = in_instance.when(not_last_step);
+        in_instance.assert_eq(local.step + AB::Expr::one(), next.step);
+        in_instance.assert_eq(local.fib.b, next.fib.a);
+        in_instance.assert_eq(local.fib.c, next.fib.b);
+        for (&flag, &next_flag) in local_flags.iter().zip(next_flags) {
+            in_instance.assert_eq(flag, next_flag);
+        }
+
+        // Between instances, reset the counter, move on to the next instance, and re-seed.
+        let mut between_instances = builder.when_transition();
+        let mut between_instances = between_instances.when(local.is_last_step);
+        between_instances.assert_zero(next.step);
+        between_instances.assert_zero(next_flags[0]);
+        for (&flag, &next_flag) in local_flags.iter().zip(&next_flags[1..]) {
+            between_instances.assert_eq(flag, next_flag);
+        }
+        between_instances.assert_eq(next.fib.a, select(next_flags, &seeds_a));
+        between_instances.assert_eq(next.fib.b, select(next_flags, &seeds_b));
+
+        // The last row of each instance holds its output.
+        builder
+            .when(local.is_last_step)
+            .assert_eq(local.fib.c, select(local_flags, &outputs));
+
+        // The trace ends with the last instance's last row.
+        builder.when_last_row().assert_one(local.is_last_step);
+        builder
+            .when_last_row()
+            .assert_one(local_flags[self.num_instances - 1]);
+    }
+}
+
+pub const NUM_MULTI_FIBONACCI_COLS: usize = size_of::<MultiFibonacciCols<u8>>();
+
+/// The fixed columns of a `MultiFibonacciAir` row. They're followed by `num_instances` columns
+/// flagging which instance the row belongs to.
+#[repr(C)]
+pub struct MultiFibonacciCols<T> {
+    pub fib: FibonacciCols<T>,
+    /// The row's index within its instance.
+    pub step: T,
+    /// The inverse of `step - (rows_per_instance - 1)`, or zero on an instance's last row.
+    pub step_minus_last_inv: T,
+    /// Whether this is the last row of its instance.
+    pub is_last_step: T,
+}
+
+impl<T> MultiFibonacciCols<T> {
+    /// Views a row of `NUM_MULTI_FIBONACCI_COLS` elements as a `MultiFibonacciCols`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_MULTI_FIBONACCI_COLS`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_MULTI_FIBONACCI_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_MULTI_FIBONACCI_COLS,
+            slice.len()
+        );
+        // SAFETY: `MultiFibonacciCols<T>` is `repr(C)` and its fields are a `FibonacciCols<T>`,
+        // which is itself made of `T`s, and `T`s, so it has the same size and alignment as
+        // `[T; NUM_MULTI_FIBONACCI_COLS]`, with no padding. We checked the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+
+    /// Like `from_slice`, but for a mutable row.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_MULTI_FIBONACCI_COLS`, in every build profile.
+    pub fn from_slice_mut(slice: &mut [T]) -> &mut Self {
+        assert_eq!(
+            slice.len(),
+            NUM_MULTI_FIBONACCI_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_MULTI_FIBONACCI_COLS,
+            slice.len()
+        );
+        // SAFETY: See `from_slice`.
+        unsafe { &mut *(slice.as_mut_ptr() as *mut Self) }
+    }
+}
+
+impl<T> Borrow<MultiFibonacciCols<T>> for [T] {
+    fn borrow(&self) -> &MultiFibonacciCols<T> {
+        MultiFibonacciCols::from_slice(self)
+    }
+}
+
+impl<T> BorrowMut<MultiFibonacciCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut MultiFibonacciCols<T> {
+        MultiFibonacciCols::from_slice_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_generate_trace() {
+        let air = MultiFibonacciAir::new(2, 4);
+        let seeds = [(F::one(), F::one()), (F::two(), F::one())];
+        let trace = air.generate_trace(&seeds);
+        assert_eq!(trace.width, NUM_MULTI_FIBONACCI_COLS + 2);
+
+        // The second instance restarts from its own seeds, on row 4.
+        let (row, flags) = trace.row_slice(4).split_at(NUM_MULTI_FIBONACCI_COLS);
+        let row: &MultiFibonacciCols<F> = row.borrow();
+        assert_eq!((row.fib.a, row.fib.b), seeds[1]);
+        assert_eq!(row.step, F::zero());
+        assert_eq!(flags, [F::zero(), F::one()]);
+
+        let pis = air.public_values(&seeds);
+        for (instance, last_row) in [(0, 3), (1, 7)] {
+            let row: &MultiFibonacciCols<F> =
+                trace.row_slice(last_row)[..NUM_MULTI_FIBONACCI_COLS].borrow();
+            assert_eq!(row.is_last_step, F::one());
+            assert_eq!(row.fib.c, pis[3 * instance + 2]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a row of 6 elements, got 8")]
+    fn test_from_slice_wrong_len() {
+        // A whole row, including the instance flags.
+        let row = [F::zero(); NUM_MULTI_FIBONACCI_COLS + 2];
+        MultiFibonacciCols::from_slice(&row);
+    }
+}
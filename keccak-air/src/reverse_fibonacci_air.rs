@@ -0,0 +1,108 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+
+use crate::{FibonacciCols, FibonacciTraceBuilder, NUM_FIBONACCI_COLS};
+
+/// An AIR for a Fibonacci-like sequence run backwards, from its last two terms `b0, c0` towards
+/// its seeds, using `a = c - b`.
+///
+/// Rows have `FibonacciAir`'s layout, with `c = a + b`, but each transition steps back by one
+/// term, so the trace of `FibonacciAir` holds these rows in reverse. This proves the path from a
+/// known end state back to the start of the sequence.
+///
+/// The single public value is the claimed earliest term, i.e. the `a` column of the last row. See
+/// `expected_output`.
+pub struct ReverseFibonacciAir<F> {
+    pub b0: F,
+    pub c0: F,
+}
+
+impl<F> ReverseFibonacciAir<F> {
+    pub fn new(b0: F, c0: F) -> Self {
+        Self { b0, c0 }
+    }
+}
+
+impl<F: AbstractField> ReverseFibonacciAir<F> {
+    /// Generates a trace with `num_rows` rows, whose first row ends with `b0, c0`.
+    pub fn generate_trace(&self, num_rows: usize) -> RowMajorMatrix<F> {
+        let mut builder = FibonacciTraceBuilder::new(num_rows);
+        let (mut b, mut c) = (self.b0.clone(), self.c0.clone());
+        for _ in 0..num_rows {
+            let a = c - b.clone();
+            builder.push(a.clone(), b.clone());
+            (b, c) = (a, b);
+        }
+        builder.build()
+    }
+
+    /// The value of `a` in the last row of a `num_rows`-row trace, which should be passed as the
+    /// public value when proving and verifying.
+    pub fn expected_output(&self, num_rows: usize) -> F {
+        // Each row's `a` is the next row's `b`, so after `num_rows` steps back `b` holds the last
+        // row's `a`.
+        let (mut b, mut c) = (self.b0.clone(), self.c0.clone());
+        for _ in 0..num_rows {
+            (b, c) = (c - b.clone(), b);
+        }
+        b
+    }
+}
+
+impl<F: Sync> BaseAir<F> for ReverseFibonacciAir<F> {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ReverseFibonacciAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &FibonacciCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &FibonacciCols<AB::Var> = main.row_slice(1).borrow();
+        let output = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(self.b0, local.b);
+        builder.when_first_row().assert_eq(self.c0, local.c);
+
+        // a = c - b
+        builder.assert_eq(local.a, local.c - local.b);
+
+        // c' <- b
+        builder.when_transition().assert_eq(local.b, next.c);
+
+        // b' <- a
+        builder.when_transition().assert_eq(local.a, next.b);
+
+        builder.when_last_row().assert_eq(local.a, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+    use crate::FibonacciAir;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_reverses_forward_trace() {
+        // The forward trace's last row is 21, 34, 55.
+        let forward = FibonacciAir {}.generate_trace::<F>(8);
+        let air = ReverseFibonacciAir::new(F::from_canonical_u8(34), F::from_canonical_u8(55));
+        let trace = air.generate_trace(8);
+
+        let mut reversed = forward.rows().collect::<Vec<_>>();
+        reversed.reverse();
+        assert_eq!(trace.rows().collect::<Vec<_>>(), reversed);
+        assert_eq!(air.expected_output(8), F::one());
+    }
+}
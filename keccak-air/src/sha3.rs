@@ -0,0 +1,340 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, PrimeField64};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use tracing::instrument;
+
+use crate::air::eval_keccak;
+use crate::columns::{KeccakCols, NUM_KECCAK_COLS};
+use crate::generation::{generate_trace_rows_for_perm, keccak_rows_mut, perm_output};
+use crate::logic::{limb_bits, u64_to_limbs, xor_gen};
+use crate::{NUM_ROUNDS, RATE_BITS, U64_LIMBS};
+
+/// The rate of the SHA3-256 sponge, in bytes.
+pub const SHA3_256_RATE_BYTES: usize = RATE_BITS / 8;
+
+/// The number of lanes of the state which a block is absorbed into.
+const RATE_LANES: usize = RATE_BITS / 64;
+
+/// The number of lanes the digest is squeezed from.
+const DIGEST_LANES: usize = 4;
+
+/// The number of public values of `Sha3Air`, i.e. the digest's limbs.
+pub const NUM_SHA3_256_PUBLIC_VALUES: usize = DIGEST_LANES * U64_LIMBS;
+
+/// Applies the SHA3 padding rule to `message`: the domain separation bits `01` followed by
+/// `pad10*1`, so that the result is a nonzero multiple of `rate_bytes` long. In bytes, this appends
+/// `0x06`, then zeros, and finally sets the top bit of the last byte; a single padding byte is
+/// `0x86`.
+pub fn sha3_pad(message: &[u8], rate_bytes: usize) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(0x06);
+    padded.resize(padded.len().next_multiple_of(rate_bytes), 0);
+    *padded.last_mut().unwrap() |= 0x80;
+    padded
+}
+
+/// An AIR proving that a padded message, given as a witness, hashes to a SHA3-256 digest given as
+/// the public values. See `sha3_256_public_values` for their layout.
+///
+/// Each permutation of the sponge occupies 24 rows, as in `KeccakAir`, whose constraints apply to
+/// each of them. On top of those, the AIR checks that
+/// - the first permutation's input is its block absorbed into the zero state,
+/// - every later permutation's input is its block XORed into the rate lanes of the previous
+///   permutation's output, with the capacity lanes carried over, and
+/// - exactly one permutation is squeezed, and the first `DIGEST_LANES` lanes of its output are the
+///   digest.
+///
+/// The blocks are private, so this is a proof of knowledge of a preimage. The padding isn't checked
+/// beyond the final bit of the squeezed permutation's block, as its position depends on the
+/// message length. Permutations after the squeezed one, which pad the trace to a power-of-two
+/// height, carry on the chain absorbing zero blocks.
+pub struct Sha3Air {}
+
+impl<F> BaseAir<F> for Sha3Air {
+    fn width(&self) -> usize {
+        NUM_SHA3_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Sha3Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &Sha3Cols<AB::Var> = main.row_slice(0).borrow();
+        let next: &Sha3Cols<AB::Var> = main.row_slice(1).borrow();
+        let digest = builder.public_values()[..NUM_SHA3_256_PUBLIC_VALUES].to_vec();
+
+        eval_keccak(builder, &local.keccak, &next.keccak);
+
+        let final_step = local.keccak.step_flags[NUM_ROUNDS - 1];
+        let not_final_step = AB::Expr::one() - final_step;
+
+        for i in 0..RATE_BITS {
+            builder.assert_bool(local.block_bits[i]);
+            builder.assert_bool(local.preimage_bits[i]);
+
+            // Each permutation absorbs a single block.
+            builder
+                .when_transition()
+                .when(not_final_step.clone())
+                .assert_eq(local.block_bits[i], next.block_bits[i]);
+        }
+
+        // The preimage's rate lanes are made up of its bits, and the first round's input is the
+        // preimage.
+        for i in 0..25 {
+            let (y, x) = (i / 5, i % 5);
+            for limb in 0..U64_LIMBS {
+                let preimage = local.keccak.preimage[y][x][limb];
+                if i < RATE_LANES {
+                    builder.assert_eq(
+                        rate_limb::<AB>(i, limb, |z| local.preimage_bits[z].into()),
+                        preimage,
+                    );
+                }
+                builder
+                    .when(local.keccak.step_flags[0])
+                    .assert_eq(local.keccak.a[y][x][limb], preimage);
+            }
+        }
+
+        // The first permutation absorbs its block into the zero state.
+        for i in 0..RATE_BITS {
+            builder
+                .when_first_row()
+                .assert_eq(local.preimage_bits[i], local.block_bits[i]);
+        }
+        for i in RATE_LANES..25 {
+            let (y, x) = (i / 5, i % 5);
+            for limb in 0..U64_LIMBS {
+                builder
+                    .when_first_row()
+                    .assert_zero(local.keccak.preimage[y][x][limb]);
+            }
+        }
+
+        // Each later permutation absorbs its block into the previous permutation's output. XORing
+        // the block back out of the next preimage must give that output.
+        for i in 0..25 {
+            let (y, x) = (i / 5, i % 5);
+            for limb in 0..U64_LIMBS {
+                let output = local.keccak.a_prime_prime_prime(x, y, limb);
+                let input = if i < RATE_LANES {
+                    rate_limb::<AB>(i, limb, |z| {
+                        xor_gen::<AB::Expr>(next.preimage_bits[z].into(), next.block_bits[z].into())
+                    })
+                } else {
+                    next.keccak.preimage[y][x][limb].into()
+                };
+                builder
+                    .when_transition()
+                    .when(final_step)
+                    .assert_eq(output, input);
+            }
+        }
+
+        // Exactly one row is squeezed, and it's the final step of a permutation.
+        builder.assert_bool(local.squeeze);
+        builder.when(not_final_step).assert_zero(local.squeeze);
+        builder
+            .when_first_row()
+            .assert_eq(local.squeezed, local.squeeze);
+        builder
+            .when_transition()
+            .assert_eq(next.squeezed, local.squeezed + next.squeeze);
+        builder.when_last_row().assert_one(local.squeezed);
+
+        // The digest is the start of the squeezed output, and the squeezed block ends with the
+        // final bit of `pad10*1`.
+        for lane in 0..DIGEST_LANES {
+            for limb in 0..U64_LIMBS {
+                builder.when(local.squeeze).assert_eq(
+                    local.keccak.a_prime_prime_prime(lane % 5, lane / 5, limb),
+                    digest[lane * U64_LIMBS + limb],
+                );
+            }
+        }
+        builder
+            .when(local.squeeze)
+            .assert_one(local.block_bits[RATE_BITS - 1]);
+    }
+}
+
+/// Recomposes limb `limb` of rate lane `lane` from the bits given by `bit`, which is indexed as
+/// `Sha3Cols::block_bits` is.
+fn rate_limb<AB: AirBuilder>(
+    lane: usize,
+    limb: usize,
+    bit: impl Fn(usize) -> AB::Expr,
+) -> AB::Expr {
+    limb_bits(limb)
+        .rev()
+        .fold(AB::Expr::zero(), |acc, z| acc.double() + bit(64 * lane + z))
+}
+
+pub const NUM_SHA3_COLS: usize = size_of::<Sha3Cols<u8>>();
+
+/// The row layout of `Sha3Air`.
+#[repr(C)]
+pub struct Sha3Cols<T> {
+    pub keccak: KeccakCols<T>,
+
+    /// The bits of the block absorbed ahead of this permutation, with bit `z` of lane `i` at index
+    /// `64 * i + z`. Held on every row of the permutation.
+    pub block_bits: [T; RATE_BITS],
+
+    /// The bits of the rate lanes of this permutation's preimage, laid out like `block_bits`.
+    pub preimage_bits: [T; RATE_BITS],
+
+    /// 1 on the final row of the permutation whose output is squeezed, otherwise 0.
+    pub squeeze: T,
+
+    /// The number of rows up to and including this one with `squeeze = 1`.
+    pub squeezed: T,
+}
+
+impl<T> Borrow<Sha3Cols<T>> for [T] {
+    fn borrow(&self) -> &Sha3Cols<T> {
+        debug_assert_eq!(self.len(), NUM_SHA3_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Sha3Cols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+/// Generates a trace of `Sha3Air` computing the SHA3-256 hash of `message`, and returns it along
+/// with the digest.
+///
+/// The message is padded with `sha3_pad` and split into `SHA3_256_RATE_BYTES`-byte blocks. The
+/// `i`th permutation's input is the sponge state after XORing in the `i`th block, and its output is
+/// the state into which the next block is absorbed. The digest is squeezed from the first 32 bytes
+/// of the output of the permutation absorbing the last block. The trace is padded to a power-of-two
+/// height with further permutations, which absorb zero blocks.
+#[instrument(name = "generate SHA3-256 trace", skip_all)]
+pub fn generate_sha3_256_trace<F: PrimeField64>(message: &[u8]) -> (RowMajorMatrix<F>, [u8; 32]) {
+    let padded = sha3_pad(message, SHA3_256_RATE_BYTES);
+    let num_blocks = padded.len() / SHA3_256_RATE_BYTES;
+
+    let num_rows = (num_blocks * NUM_ROUNDS).next_power_of_two();
+    let mut keccak_trace =
+        RowMajorMatrix::new(vec![F::zero(); num_rows * NUM_KECCAK_COLS], NUM_KECCAK_COLS);
+    let rows = keccak_rows_mut(&mut keccak_trace);
+
+    let mut blocks = padded.chunks_exact(SHA3_256_RATE_BYTES).map(block_lanes);
+    let mut state = [0u64; 25];
+    let mut digest = [0u8; 32];
+    let mut values = Vec::with_capacity(num_rows * NUM_SHA3_COLS);
+    for (perm, perm_rows) in rows.chunks_mut(NUM_ROUNDS).enumerate() {
+        let block = blocks.next().unwrap_or([0; RATE_LANES]);
+        for (lane, block_lane) in state.iter_mut().zip(block) {
+            *lane ^= block_lane;
+        }
+        generate_trace_rows_for_perm(perm_rows, state);
+
+        let preimage = state;
+        let is_squeezed = perm == num_blocks - 1;
+        if perm_rows.len() == NUM_ROUNDS {
+            state = perm_output(&perm_rows[NUM_ROUNDS - 1]);
+        }
+        if is_squeezed {
+            for (bytes, lane) in digest.chunks_exact_mut(8).zip(state) {
+                bytes.copy_from_slice(&lane.to_le_bytes());
+            }
+        }
+
+        for (round, row) in perm_rows.iter().enumerate() {
+            let squeeze = is_squeezed && round == NUM_ROUNDS - 1;
+            let squeezed = perm >= num_blocks || squeeze;
+            values.extend_from_slice(row.as_slice());
+            values.extend(lane_bits::<F>(&block));
+            values.extend(lane_bits::<F>(&preimage[..RATE_LANES]));
+            values.push(F::from_bool(squeeze));
+            values.push(F::from_bool(squeezed));
+        }
+    }
+
+    (RowMajorMatrix::new(values, NUM_SHA3_COLS), digest)
+}
+
+/// The public values of `Sha3Air` for `digest`: its first `DIGEST_LANES` lanes, each split into limbs
+/// as in the trace, i.e. by `u64_to_limbs`.
+pub fn sha3_256_public_values<F: AbstractField>(digest: &[u8; 32]) -> Vec<F> {
+    digest
+        .chunks_exact(8)
+        .flat_map(|bytes| u64_to_limbs(u64::from_le_bytes(bytes.try_into().unwrap())))
+        .map(F::from_canonical_u16)
+        .collect()
+}
+
+/// Splits a block into the lanes of the state it's absorbed into.
+fn block_lanes(block: &[u8]) -> [u64; RATE_LANES] {
+    core::array::from_fn(|i| u64::from_le_bytes(block[8 * i..8 * i + 8].try_into().unwrap()))
+}
+
+/// The bits of `lanes`, least significant first within each lane.
+fn lane_bits<F: AbstractField>(lanes: &[u64]) -> impl Iterator<Item = F> + '_ {
+    lanes
+        .iter()
+        .flat_map(|&lane| (0..64).map(move |z| F::from_bool((lane >> z) & 1 != 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::Matrix;
+    use rand::{thread_rng, Rng};
+    use tiny_keccak::{Hasher, Sha3};
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    fn reference_sha3_256(message: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha3::v256();
+        hasher.update(message);
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+        digest
+    }
+
+    #[test]
+    fn test_sha3_pad() {
+        assert_eq!(sha3_pad(&[], 4), vec![0x06, 0, 0, 0x80]);
+        assert_eq!(sha3_pad(&[1, 2, 3], 4), vec![1, 2, 3, 0x86]);
+        assert_eq!(
+            sha3_pad(&[1, 2, 3, 4], 4),
+            vec![1, 2, 3, 4, 0x06, 0, 0, 0x80]
+        );
+    }
+
+    #[test]
+    fn test_sha3_256_empty() {
+        let (trace, digest) = generate_sha3_256_trace::<F>(&[]);
+        assert_eq!(trace.height(), 32);
+        assert_eq!(
+            digest,
+            [
+                0xa7, 0xff, 0xc6, 0xf8, 0xbf, 0x1e, 0xd7, 0x66, 0x51, 0xc1, 0x47, 0x56, 0xa0, 0x61,
+                0xd6, 0x62, 0xf5, 0x80, 0xff, 0x4d, 0xe4, 0x3b, 0x49, 0xfa, 0x82, 0xd8, 0x0a, 0x4b,
+                0x80, 0xf8, 0x43, 0x4a,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sha3_256_multiple_blocks() {
+        let mut rng = thread_rng();
+        for len in [1, 135, 136, 137, 500] {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let (_, digest) = generate_sha3_256_trace::<F>(&message);
+            assert_eq!(digest, reference_sha3_256(&message));
+        }
+    }
+}
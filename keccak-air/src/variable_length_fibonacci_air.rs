@@ -0,0 +1,214 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+
+use crate::{FibonacciCols, SeededFibonacciAir};
+
+/// `SeededFibonacciAir` for a sequence of any length, whose trace is padded past the real rows up
+/// to a power-of-two height.
+///
+/// Each row carries an `is_real` flag, which is 1 on a prefix of the trace and 0 on the padding
+/// rows after it, and a running count of the real rows. The Fibonacci transitions only apply
+/// between real rows, and the output is read from the last real row rather than the last row.
+///
+/// The public values are the output, i.e. the `c` column of the last real row, followed by the
+/// number of real rows. See `public_values`.
+pub struct VariableLengthFibonacciAir<F> {
+    pub air: SeededFibonacciAir<F>,
+}
+
+impl<F> VariableLengthFibonacciAir<F> {
+    pub fn new(air: SeededFibonacciAir<F>) -> Self {
+        Self { air }
+    }
+}
+
+impl<F: AbstractField> VariableLengthFibonacciAir<F> {
+    /// Generates a trace with `num_real_rows` rows of the sequence, padded with zero rows up to the
+    /// next power of two.
+    ///
+    /// # Panics
+    /// Panics if `num_real_rows` is zero.
+    pub fn generate_trace(&self, num_real_rows: usize) -> RowMajorMatrix<F> {
+        assert!(num_real_rows > 0, "the trace needs at least one real row");
+        let num_rows = num_real_rows.next_power_of_two();
+        let mut values = Vec::with_capacity(num_rows * NUM_VARIABLE_LENGTH_FIBONACCI_COLS);
+        let (mut a, mut b) = (self.air.a0.clone(), self.air.b0.clone());
+        for row in 0..num_real_rows {
+            let c = a.clone() + b.clone();
+            values.extend([a, b.clone(), c.clone(), F::one()]);
+            values.push(F::from_canonical_usize(row + 1));
+            (a, b) = (b, c);
+        }
+        for _ in num_real_rows..num_rows {
+            values.extend([F::zero(), F::zero(), F::zero(), F::zero()]);
+            values.push(F::from_canonical_usize(num_real_rows));
+        }
+        RowMajorMatrix::new(values, NUM_VARIABLE_LENGTH_FIBONACCI_COLS)
+    }
+
+    /// The public values for proving a trace of `num_real_rows` real rows, i.e. the output and the
+    /// number of real rows.
+    pub fn public_values(&self, num_real_rows: usize) -> Vec<F> {
+        vec![
+            self.air.expected_output(num_real_rows),
+            F::from_canonical_usize(num_real_rows),
+        ]
+    }
+}
+
+impl<F: Sync> BaseAir<F> for VariableLengthFibonacciAir<F> {
+    fn width(&self) -> usize {
+        NUM_VARIABLE_LENGTH_FIBONACCI_COLS
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for VariableLengthFibonacciAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: &VariableLengthFibonacciCols<AB::Var> = main.row_slice(0).borrow();
+        let next: &VariableLengthFibonacciCols<AB::Var> = main.row_slice(1).borrow();
+        let output = builder.public_values()[0];
+        let num_real_rows = builder.public_values()[1];
+
+        // The real rows are a nonempty prefix of the trace, and `count` counts them.
+        builder.assert_bool(local.is_real);
+        builder.when_first_row().assert_one(local.is_real);
+        builder.when_first_row().assert_one(local.count);
+        builder
+            .when_transition()
+            .assert_zero(next.is_real * (AB::Expr::one() - local.is_real));
+        builder
+            .when_transition()
+            .assert_eq(next.count, local.count + next.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.count, num_real_rows);
+
+        builder
+            .when_first_row()
+            .assert_eq(self.air.a0, local.cols.a);
+        builder
+            .when_first_row()
+            .assert_eq(self.air.b0, local.cols.b);
+
+        // c = a + b, which the zero padding rows satisfy too.
+        builder.assert_eq(local.cols.a + local.cols.b, local.cols.c);
+
+        // a' <- b and b' <- c, as long as the next row is real.
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .assert_eq(local.cols.b, next.cols.a);
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .assert_eq(local.cols.c, next.cols.b);
+
+        // The last real row is either followed by padding or is the last row.
+        builder
+            .when_transition()
+            .when(local.is_real - next.is_real)
+            .assert_eq(local.cols.c, output);
+        builder
+            .when_last_row()
+            .when(local.is_real)
+            .assert_eq(local.cols.c, output);
+    }
+}
+
+pub const NUM_VARIABLE_LENGTH_FIBONACCI_COLS: usize = size_of::<VariableLengthFibonacciCols<u8>>();
+
+/// The row layout of `VariableLengthFibonacciAir`.
+#[repr(C)]
+pub struct VariableLengthFibonacciCols<T> {
+    /// The terms of the sequence on a real row, and zeros on a padding row.
+    pub cols: FibonacciCols<T>,
+    /// 1 on a real row, and 0 on a padding row.
+    pub is_real: T,
+    /// The number of real rows up to and including this one.
+    pub count: T,
+}
+
+impl<T> VariableLengthFibonacciCols<T> {
+    /// Views a row of `NUM_VARIABLE_LENGTH_FIBONACCI_COLS` elements as a
+    /// `VariableLengthFibonacciCols`.
+    ///
+    /// # Panics
+    /// Panics if `slice.len() != NUM_VARIABLE_LENGTH_FIBONACCI_COLS`, in every build profile.
+    pub fn from_slice(slice: &[T]) -> &Self {
+        assert_eq!(
+            slice.len(),
+            NUM_VARIABLE_LENGTH_FIBONACCI_COLS,
+            "expected a row of {} elements, got {}",
+            NUM_VARIABLE_LENGTH_FIBONACCI_COLS,
+            slice.len()
+        );
+        // SAFETY: `VariableLengthFibonacciCols<T>` is `repr(C)` and its fields are a
+        // `FibonacciCols<T>`, which is itself made of `T`s, and two `T`s, so it has the same size
+        // and alignment as `[T; NUM_VARIABLE_LENGTH_FIBONACCI_COLS]`, with no padding. We checked
+        // the length.
+        unsafe { &*(slice.as_ptr() as *const Self) }
+    }
+}
+
+impl<T> Borrow<VariableLengthFibonacciCols<T>> for [T] {
+    fn borrow(&self) -> &VariableLengthFibonacciCols<T> {
+        VariableLengthFibonacciCols::from_slice(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::Matrix;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn test_padded_trace() {
+        let air = VariableLengthFibonacciAir::new(SeededFibonacciAir::<F>::default());
+        let trace = air.generate_trace(5);
+        assert_eq!(trace.height(), 8);
+
+        // The real rows are those of `SeededFibonacciAir`, ending with 5, 8, 13.
+        let last_real: &VariableLengthFibonacciCols<F> = trace.row_slice(4).borrow();
+        assert_eq!(last_real.cols.c, F::from_canonical_u8(13));
+        assert_eq!(last_real.is_real, F::one());
+        assert_eq!(
+            air.public_values(5),
+            [last_real.cols.c, F::from_canonical_u8(5)]
+        );
+
+        let last: &VariableLengthFibonacciCols<F> = trace.row_slice(7).borrow();
+        assert_eq!(last.cols.as_array(), [F::zero(); 3]);
+        assert_eq!(last.is_real, F::zero());
+        assert_eq!(last.count, F::from_canonical_u8(5));
+    }
+
+    #[test]
+    fn test_unpadded_trace() {
+        let air = VariableLengthFibonacciAir::new(SeededFibonacciAir::<F>::default());
+        let trace = air.generate_trace(8);
+        let unpadded = air.air.generate_trace(8);
+        for (row, unpadded_row) in trace.rows().zip(unpadded.rows()) {
+            let cols: &VariableLengthFibonacciCols<F> = row.borrow();
+            assert_eq!(cols.cols.as_array()[..], unpadded_row[..]);
+            assert_eq!(cols.is_real, F::one());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the trace needs at least one real row")]
+    fn test_generate_empty_trace() {
+        VariableLengthFibonacciAir::new(SeededFibonacciAir::<F>::default()).generate_trace(0);
+    }
+}
@@ -0,0 +1,137 @@
+use p3_field::{AbstractField, PrimeField64};
+use p3_keccak_air::{FibonacciAir, FibonacciCols};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::{
+    get_constraint_info, get_max_constraint_degree, prove, verify, ConstraintInfo, ConstraintKind,
+    Proof, VerificationError,
+};
+
+mod common;
+
+use common::{make_config, Challenger, MyConfig, Val};
+
+const NUM_ROWS: usize = 1 << 6;
+
+fn prove_and_verify(
+    trace: RowMajorMatrix<Val>,
+    public_values: Vec<Val>,
+) -> Result<(), VerificationError<Val>> {
+    let (config, perm) = make_config(trace.height(), 1);
+
+    let air = FibonacciAir {};
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
+
+/// A trace whose row 5 has `c = a + b + 1`, with the honest trace's output as its public value.
+fn forged_trace() -> (RowMajorMatrix<Val>, Vec<Val>) {
+    let air = FibonacciAir {};
+    let mut trace = air.generate_trace::<Val>(NUM_ROWS);
+    FibonacciCols::from_slice_mut(trace.row_mut(5)).c += Val::one();
+    (trace, vec![air.expected_output::<Val>(NUM_ROWS)])
+}
+
+#[test]
+fn honest_trace_is_accepted() -> Result<(), VerificationError<Val>> {
+    let air = FibonacciAir {};
+    prove_and_verify(
+        air.generate_trace::<Val>(NUM_ROWS),
+        vec![air.expected_output::<Val>(NUM_ROWS)],
+    )
+}
+
+// Debug builds check every constraint before proving, so a forged trace never gets as far as a
+// proof.
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 5")]
+fn forged_trace_is_rejected() {
+    let (trace, public_values) = forged_trace();
+    let _ = prove_and_verify(trace, public_values);
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn forged_trace_is_rejected() {
+    let (trace, public_values) = forged_trace();
+    let result = prove_and_verify(trace, public_values);
+    assert!(matches!(
+        result,
+        Err(VerificationError::OodEvaluationMismatch { .. })
+    ));
+}
+
+// Unlike a forged trace, a tampered proof gets as far as the verifier in every build profile.
+#[test]
+fn tampered_proof_is_rejected() {
+    let air = FibonacciAir {};
+    let trace = air.generate_trace::<Val>(NUM_ROWS);
+    let public_values = vec![air.expected_output::<Val>(NUM_ROWS)];
+    let (config, perm) = make_config(trace.height(), 1);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let verify_with = |proof: &Proof<MyConfig>, public_values: &Vec<Val>| {
+        let mut challenger = Challenger::new(perm.clone());
+        verify(&config, &air, &mut challenger, proof, public_values)
+    };
+    verify_with(&proof, &public_values).expect("verification failed");
+
+    // The honest proof, checked against a different output.
+    let wrong_output = vec![public_values[0] + Val::one()];
+    assert!(verify_with(&proof, &wrong_output).is_err());
+
+    // The proof with the trace's first column at `zeta` altered.
+    let mut json = serde_json::to_value(&proof).unwrap();
+    let opened = &mut json["opened_values"]["trace_opens"][0][1][0]["value"][0];
+    let altered = Val::from_canonical_u64(opened.as_u64().unwrap()) + Val::one();
+    *opened = altered.as_canonical_u64().into();
+    let tampered: Proof<MyConfig> = serde_json::from_value(json).unwrap();
+    assert!(verify_with(&tampered, &public_values).is_err());
+}
+
+// `FibonacciAir` reads its output from the public values, so verifying it against none must fail
+// rather than panic while the verifier evaluates the AIR.
+#[test]
+fn missing_public_values_are_rejected() {
+    let air = FibonacciAir {};
+    let trace = air.generate_trace::<Val>(NUM_ROWS);
+    let public_values = vec![air.expected_output::<Val>(NUM_ROWS)];
+    let (config, perm) = make_config(trace.height(), 1);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    let result = verify(&config, &air, &mut challenger, &proof, &vec![]);
+    assert!(matches!(
+        result,
+        Err(VerificationError::NumPublicValuesMismatch {
+            expected: 0,
+            got: 1
+        })
+    ));
+}
+
+#[test]
+fn constraint_info() {
+    let air = FibonacciAir {};
+    let info = |kind, degree| ConstraintInfo { kind, degree };
+    // The selectors of boundary constraints count towards their degree, but the transition
+    // selector doesn't.
+    assert_eq!(
+        get_constraint_info::<Val, _>(&air, 1),
+        [
+            info(ConstraintKind::FirstRow, 2),
+            info(ConstraintKind::FirstRow, 2),
+            info(ConstraintKind::EveryRow, 1),
+            info(ConstraintKind::Transition, 1),
+            info(ConstraintKind::Transition, 1),
+            info(ConstraintKind::LastRow, 2),
+        ]
+    );
+    assert_eq!(get_max_constraint_degree::<Val, _>(&air, 1), 2);
+}
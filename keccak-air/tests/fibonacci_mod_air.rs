@@ -0,0 +1,23 @@
+use p3_keccak_air::FibonacciModAir;
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+#[test]
+fn prove_fibonacci_mod_7() -> Result<(), VerificationError<Val>> {
+    let num_rows = 64;
+    let air = FibonacciModAir::new(7);
+    let trace = air.generate_trace(num_rows);
+    let public_values = vec![air.expected_output(num_rows)];
+
+    let (config, perm) = make_config(trace.height(), 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
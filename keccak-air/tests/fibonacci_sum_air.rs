@@ -0,0 +1,43 @@
+use p3_field::AbstractField;
+use p3_keccak_air::{FibonacciSumAir, SeededFibonacciAir};
+use p3_matrix::Matrix;
+use p3_uni_stark::{check_constraints, prove, verify, ConstraintViolation, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+const NUM_ROWS: usize = 1 << 6;
+
+#[test]
+fn prove_fibonacci_sum() -> Result<(), VerificationError<Val>> {
+    let air = FibonacciSumAir::new(SeededFibonacciAir::<Val>::default());
+    let trace = air.generate_trace(NUM_ROWS);
+    let public_values = air.public_values(NUM_ROWS);
+
+    let (config, perm) = make_config(trace.height(), 1);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
+
+#[test]
+fn wrong_sum_is_rejected() {
+    let air = FibonacciSumAir::new(SeededFibonacciAir::<Val>::default());
+    let trace = air.generate_trace(NUM_ROWS);
+    let mut public_values = air.public_values(NUM_ROWS);
+    public_values[1] += Val::one();
+
+    // The sum's last-row constraint is the last one, after `FibonacciAir`'s six and the sum's
+    // first-row and transition constraints.
+    assert_eq!(
+        check_constraints(&air, &trace, &public_values),
+        Err(ConstraintViolation {
+            row: NUM_ROWS - 1,
+            constraint: 8
+        })
+    );
+}
@@ -0,0 +1,22 @@
+use p3_field::AbstractField;
+use p3_keccak_air::LinearRecurrenceAir;
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+#[test]
+fn prove_tribonacci() -> Result<(), VerificationError<Val>> {
+    let air = LinearRecurrenceAir::new([Val::one(); 3], [Val::zero(), Val::zero(), Val::one()]);
+    let trace = air.generate_trace(128);
+
+    let (config, perm) = make_config(trace.height(), 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![])
+}
@@ -0,0 +1,24 @@
+use p3_field::AbstractField;
+use p3_keccak_air::MultiFibonacciAir;
+use p3_matrix::Matrix;
+use p3_uni_stark::{prove, verify, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+#[test]
+fn prove_two_fibonacci_instances() -> Result<(), VerificationError<Val>> {
+    let air = MultiFibonacciAir::new(2, 64);
+    let seeds = [(Val::one(), Val::one()), (Val::two(), Val::one())];
+    let trace = air.generate_trace(&seeds);
+    let public_values = air.public_values(&seeds);
+
+    let (config, perm) = make_config(trace.height(), 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
@@ -0,0 +1,24 @@
+//! Checks that trace generation can be used from a `no_std` crate with only `alloc`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec;
+
+use p3_baby_bear::BabyBear;
+use p3_keccak_air::{generate_fibonacci_trace, generate_trace_rows, NUM_KECCAK_COLS, NUM_ROUNDS};
+use p3_matrix::Matrix;
+
+#[test]
+fn generate_keccak_trace_no_std() {
+    let trace = generate_trace_rows::<BabyBear>(vec![[0; 25]; 2]);
+    assert_eq!(trace.width(), NUM_KECCAK_COLS);
+    assert_eq!(trace.height(), (2 * NUM_ROUNDS).next_power_of_two());
+}
+
+#[test]
+fn generate_fibonacci_trace_no_std() {
+    let trace = generate_fibonacci_trace::<BabyBear>(16);
+    assert_eq!(trace.height(), 16);
+}
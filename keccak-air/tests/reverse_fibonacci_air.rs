@@ -0,0 +1,51 @@
+use p3_field::AbstractField;
+use p3_keccak_air::ReverseFibonacciAir;
+use p3_matrix::Matrix;
+use p3_uni_stark::{check_constraints, prove, verify, ConstraintViolation, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+const NUM_ROWS: usize = 1 << 6;
+
+/// The reverse AIR starting from the last two terms of a `NUM_ROWS`-row `FibonacciAir` trace,
+/// `F(NUM_ROWS + 1)` and `F(NUM_ROWS + 2)`, so that it runs back to `F(1) = 1`.
+fn reverse_air() -> ReverseFibonacciAir<Val> {
+    let (mut b, mut c) = (Val::one(), Val::one());
+    for _ in 0..NUM_ROWS {
+        (b, c) = (c, b + c);
+    }
+    ReverseFibonacciAir::new(b, c)
+}
+
+#[test]
+fn prove_reverse_fibonacci() -> Result<(), VerificationError<Val>> {
+    let air = reverse_air();
+    let trace = air.generate_trace(NUM_ROWS);
+    let public_values = vec![air.expected_output(NUM_ROWS)];
+    assert_eq!(public_values, [Val::one()]);
+
+    let (config, perm) = make_config(trace.height(), 1);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
+
+#[test]
+fn wrong_earliest_term_is_rejected() {
+    let air = reverse_air();
+    let trace = air.generate_trace(NUM_ROWS);
+
+    // The boundary on the earliest term is the last constraint, on the last row.
+    assert_eq!(
+        check_constraints(&air, &trace, &[Val::two()]),
+        Err(ConstraintViolation {
+            row: NUM_ROWS - 1,
+            constraint: 5
+        })
+    );
+}
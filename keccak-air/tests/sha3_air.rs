@@ -0,0 +1,81 @@
+use p3_field::AbstractField;
+use p3_keccak_air::{
+    generate_sha3_256_trace, sha3_256_public_values, Sha3Air, NUM_KECCAK_COLS, NUM_ROUNDS,
+};
+use p3_matrix::Matrix;
+use p3_uni_stark::{check_constraints, prove, verify, VerificationError};
+use tiny_keccak::{Hasher, Sha3};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+fn reference_sha3_256(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3::v256();
+    hasher.update(message);
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    digest
+}
+
+/// Proves the hash of `message`, and verifies the proof against `claimed_digest`.
+fn prove_and_verify(
+    message: &[u8],
+    claimed_digest: &[u8; 32],
+) -> Result<(), VerificationError<Val>> {
+    let (trace, digest) = generate_sha3_256_trace::<Val>(message);
+    assert_eq!(digest, reference_sha3_256(message));
+
+    // The absorption constraints have degree 4, so the quotient domain is 4 times the trace's
+    // height, which the blowup must cover.
+    let (config, perm) = make_config(trace.height(), 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let public_values = sha3_256_public_values(&digest);
+    let proof = prove(&config, &Sha3Air {}, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    let claimed = sha3_256_public_values(claimed_digest);
+    verify(&config, &Sha3Air {}, &mut challenger, &proof, &claimed)
+}
+
+#[test]
+fn prove_sha3_256_empty() -> Result<(), VerificationError<Val>> {
+    prove_and_verify(&[], &reference_sha3_256(&[]))
+}
+
+#[test]
+fn prove_sha3_256_multiple_blocks() -> Result<(), VerificationError<Val>> {
+    // Three blocks, with four padding permutations after them.
+    let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+    prove_and_verify(&message, &reference_sha3_256(&message))
+}
+
+#[test]
+fn wrong_digest_is_rejected() {
+    let mut digest = reference_sha3_256(&[]);
+    digest[0] ^= 1;
+    assert!(prove_and_verify(&[], &digest).is_err());
+
+    // The squeezed permutation's final row no longer matches the claimed digest.
+    let (trace, _) = generate_sha3_256_trace::<Val>(&[]);
+    let violation =
+        check_constraints(&Sha3Air {}, &trace, &sha3_256_public_values(&digest)).unwrap_err();
+    assert_eq!(violation.row, NUM_ROUNDS - 1);
+}
+
+#[test]
+fn unchained_block_is_rejected() {
+    let message = [0u8; 200];
+    let (mut trace, digest) = generate_sha3_256_trace::<Val>(&message);
+
+    // Flip the first bit of the second block, on every row of its permutation, without changing
+    // the permutation's preimage.
+    for row in NUM_ROUNDS..2 * NUM_ROUNDS {
+        let bit = &mut trace.row_mut(row)[NUM_KECCAK_COLS];
+        *bit = Val::one() - *bit;
+    }
+    let violation =
+        check_constraints(&Sha3Air {}, &trace, &sha3_256_public_values(&digest)).unwrap_err();
+    assert_eq!(violation.row, NUM_ROUNDS - 1);
+}
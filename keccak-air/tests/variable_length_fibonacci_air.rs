@@ -0,0 +1,57 @@
+use p3_field::AbstractField;
+use p3_keccak_air::{SeededFibonacciAir, VariableLengthFibonacciAir};
+use p3_matrix::Matrix;
+use p3_uni_stark::{check_constraints, prove, verify, VerificationError};
+
+mod common;
+
+use common::{make_config, Challenger, Val};
+
+fn prove_and_verify(num_real_rows: usize) -> Result<(), VerificationError<Val>> {
+    let air = VariableLengthFibonacciAir::new(SeededFibonacciAir::<Val>::default());
+    let trace = air.generate_trace(num_real_rows);
+    let public_values = air.public_values(num_real_rows);
+
+    let (config, perm) = make_config(trace.height(), 1);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values)
+}
+
+#[test]
+fn prove_padded_sequence() -> Result<(), VerificationError<Val>> {
+    // 100 real rows, padded to 128.
+    prove_and_verify(100)
+}
+
+#[test]
+fn prove_unpadded_sequence() -> Result<(), VerificationError<Val>> {
+    prove_and_verify(64)
+}
+
+#[test]
+fn output_is_read_from_last_real_row() {
+    let air = VariableLengthFibonacciAir::new(SeededFibonacciAir::<Val>::default());
+    let trace = air.generate_trace(100);
+    assert_eq!(trace.height(), 128);
+    assert_eq!(
+        check_constraints(&air, &trace, &air.public_values(100)),
+        Ok(())
+    );
+
+    // The output of a 128-row sequence, or any other term than the 100th, is rejected on the last
+    // real row.
+    let mut public_values = air.public_values(100);
+    public_values[0] = SeededFibonacciAir::<Val>::default().expected_output(128);
+    let violation = check_constraints(&air, &trace, &public_values).unwrap_err();
+    assert_eq!(violation.row, 99);
+
+    // So is claiming a different number of real rows.
+    let mut public_values = air.public_values(100);
+    public_values[1] += Val::one();
+    let violation = check_constraints(&air, &trace, &public_values).unwrap_err();
+    assert_eq!(violation.row, 127);
+}
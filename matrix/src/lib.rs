@@ -34,7 +34,7 @@ pub trait Matrix<T> {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Dimensions {
     pub width: usize,
     pub height: usize,
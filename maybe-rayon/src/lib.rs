@@ -1,3 +1,8 @@
+//! Parallel iterators backed by rayon with the `parallel` feature, or by their serial `core`
+//! equivalents without it, in which case this crate is `no_std`.
+
+#![cfg_attr(not(feature = "parallel"), no_std)]
+
 #[cfg(feature = "parallel")]
 pub mod prelude {
     pub use rayon::join;
@@ -15,3 +20,28 @@ pub mod prelude {
 
     pub use super::serial::*;
 }
+
+/// The number of items below which work is done serially even with the `parallel` feature, as
+/// splitting a small input across threads costs more than it saves.
+pub const PARALLEL_THRESHOLD: usize = 1 << 10;
+
+/// Whether work over `len` items should be split across threads, i.e. whether the `parallel`
+/// feature is enabled and `len` is at least `PARALLEL_THRESHOLD`.
+pub const fn should_parallelize(len: usize) -> bool {
+    cfg!(feature = "parallel") && len >= PARALLEL_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_inputs_are_serial() {
+        assert!(!should_parallelize(0));
+        assert!(!should_parallelize(PARALLEL_THRESHOLD - 1));
+        assert_eq!(
+            should_parallelize(PARALLEL_THRESHOLD),
+            cfg!(feature = "parallel")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Compares the time to commit to the same number of leaves with a Poseidon2 Merkle tree and a
+//! Monolith Merkle tree.
+//!
+//! Both hashes use a width-16 permutation over a 31-bit field, a sponge rate of 8 elements and
+//! 8-element digests, with the round counts targeting 128-bit security. Monolith is only
+//! implemented on scalars, so neither tree uses packing here; see the `merkle_tree` bench for
+//! packed Poseidon2.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_commit::DirectMmcs;
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_mersenne_31::Mersenne31;
+use p3_monolith::{
+    MonolithCompress, MonolithHash, MonolithMdsMatrixMersenne31, MonolithMersenne31,
+};
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::{
+    CryptographicHasher, PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
+};
+use rand::distributions::{Distribution, Standard};
+use rand::thread_rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const LOG_LEAVES: usize = 16;
+const COLS: usize = 135;
+const DIGEST_ELEMS: usize = 8;
+
+fn bench_poseidon2_vs_monolith(criterion: &mut Criterion) {
+    bench_poseidon2(criterion);
+    bench_monolith(criterion);
+}
+
+fn bench_poseidon2(criterion: &mut Criterion) {
+    type F = BabyBear;
+
+    type Perm = Poseidon2<F, DiffusionMatrixBabybear, 16, 7>;
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+
+    type H = PaddingFreeSponge<Perm, 16, 8, DIGEST_ELEMS>;
+    let h = H::new(perm.clone());
+
+    type C = TruncatedPermutation<Perm, 2, DIGEST_ELEMS, 16>;
+    let c = C::new(perm);
+
+    bench_commit::<F, H, C>(criterion, "poseidon2-babybear-16", h, c);
+}
+
+fn bench_monolith(criterion: &mut Criterion) {
+    type F = Mersenne31;
+
+    type Mds = MonolithMdsMatrixMersenne31<6>;
+    type Perm = MonolithMersenne31<Mds, 16, 5>;
+    let perm = Perm::new(MonolithMdsMatrixMersenne31);
+
+    type H = MonolithHash<Mds, 16, 5, 8, DIGEST_ELEMS>;
+    let h = H::new(perm.clone());
+
+    type C = MonolithCompress<Mds, 16, 5, DIGEST_ELEMS>;
+    let c = C::new(perm);
+
+    bench_commit::<F, H, C>(criterion, "monolith-mersenne31-16", h, c);
+}
+
+fn bench_commit<F, H, C>(criterion: &mut Criterion, hash_name: &str, h: H, c: C)
+where
+    F: Field,
+    H: CryptographicHasher<F, [F; DIGEST_ELEMS]> + Sync,
+    C: PseudoCompressionFunction<[F; DIGEST_ELEMS], 2> + Sync,
+    [F; DIGEST_ELEMS]: Serialize + DeserializeOwned,
+    Standard: Distribution<F>,
+{
+    let matrix = RowMajorMatrix::<F>::rand(&mut thread_rng(), 1 << LOG_LEAVES, COLS);
+    let leaves = vec![matrix];
+
+    let mut group = criterion.benchmark_group("FieldMerkleTreeMmcs::commit");
+    group.sample_size(10);
+
+    let id = BenchmarkId::new(
+        hash_name,
+        format!("leaves=2^{},digest={}", LOG_LEAVES, DIGEST_ELEMS),
+    );
+    let mmcs = FieldMerkleTreeMmcs::<F, F, H, C, DIGEST_ELEMS>::new(h, c);
+    group.bench_with_input(id, &leaves, |b, input| {
+        b.iter(|| mmcs.commit(input.clone()))
+    });
+}
+
+criterion_group!(benches, bench_poseidon2_vs_monolith);
+criterion_main!(benches);
@@ -9,6 +9,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Matrix, MatrixRowSlices};
 use p3_maybe_rayon::prelude::*;
 use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
@@ -107,6 +108,20 @@ impl<F: Clone, W: Clone, const DIGEST_ELEMS: usize> FieldMerkleTree<F, W, DIGEST
     {
         self.digest_layers.last().unwrap()[0].into()
     }
+
+    /// The row of each matrix opened at `index`, following the semantics of `Mmcs::open_batch`.
+    pub(crate) fn rows_at(&self, index: usize) -> Vec<Vec<F>> {
+        let max_height = self.leaves.iter().map(|m| m.height()).max().unwrap();
+        let log_max_height = log2_ceil_usize(max_height);
+
+        self.leaves
+            .iter()
+            .map(|matrix| {
+                let bits_reduced = log_max_height - log2_ceil_usize(matrix.height());
+                matrix.row_slice(index >> bits_reduced).to_vec()
+            })
+            .collect()
+    }
 }
 
 fn first_digest_layer<P, PW, H, const DIGEST_ELEMS: usize>(
@@ -1,3 +1,4 @@
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Reverse;
 use core::marker::PhantomData;
@@ -6,7 +7,7 @@ use itertools::Itertools;
 use p3_commit::{DirectMmcs, Mmcs};
 use p3_field::{PackedField, PackedValue};
 use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::{Dimensions, Matrix, MatrixRows};
+use p3_matrix::Dimensions;
 use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
 use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
@@ -64,16 +65,7 @@ where
         let max_height = self.get_max_height(prover_data);
         let log_max_height = log2_ceil_usize(max_height);
 
-        let openings = prover_data
-            .leaves
-            .iter()
-            .map(|matrix| {
-                let log2_height = log2_ceil_usize(matrix.height());
-                let bits_reduced = log_max_height - log2_height;
-                let reduced_index = index >> bits_reduced;
-                matrix.row(reduced_index).collect()
-            })
-            .collect_vec();
+        let openings = prover_data.rows_at(index);
 
         let proof = (0..log_max_height)
             .map(|i| prover_data.digest_layers[i][(index >> i) ^ 1])
@@ -82,6 +74,10 @@ where
         (openings, proof)
     }
 
+    fn root(&self, prover_data: &Self::ProverData) -> Self::Commitment {
+        prover_data.root()
+    }
+
     fn get_matrices<'a>(
         &'a self,
         prover_data: &'a Self::ProverData,
@@ -97,6 +93,11 @@ where
         opened_values: &[Vec<P::Scalar>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
+        // The opened values come from the prover, so they may not have one row per matrix.
+        if opened_values.len() != dimensions.len() {
+            return Err(());
+        }
+
         let mut heights_tallest_first = dimensions
             .iter()
             .enumerate()
@@ -150,6 +151,175 @@ where
             Err(())
         }
     }
+
+    /// Opens the rows at each of `indices` with a single proof, in which shared parts of the
+    /// authentication paths appear only once.
+    ///
+    /// Authentication paths of different indices share every node above their lowest common
+    /// ancestor, and a sibling which lies on another index's path can be recomputed by the
+    /// verifier. So rather than one path per index, the proof holds only the digests the verifier
+    /// can't derive itself, layer by layer from the leaves up, and in increasing node order within
+    /// each layer.
+    fn open_multi_batch(
+        &self,
+        indices: &[usize],
+        prover_data: &Self::ProverData,
+    ) -> (Vec<Vec<Vec<P::Scalar>>>, Vec<Self::Proof>) {
+        assert!(!indices.is_empty(), "No indices to open?");
+        let max_height = self.get_max_height(prover_data);
+        let log_max_height = log2_ceil_usize(max_height);
+
+        let opened_values = indices
+            .iter()
+            .map(|&index| prover_data.rows_at(index))
+            .collect();
+
+        let mut siblings = Vec::new();
+        let mut nodes = indices.iter().copied().sorted().dedup().collect_vec();
+        for layer in &prover_data.digest_layers[..log_max_height] {
+            for (i, &node) in nodes.iter().enumerate() {
+                let sibling_is_known = if node & 1 == 0 {
+                    nodes.get(i + 1) == Some(&(node ^ 1))
+                } else {
+                    i > 0 && nodes[i - 1] == node ^ 1
+                };
+                if !sibling_is_known {
+                    siblings.push(layer[node ^ 1]);
+                }
+            }
+            nodes = nodes.into_iter().map(|node| node >> 1).dedup().collect();
+        }
+
+        (opened_values, vec![siblings])
+    }
+
+    /// Verifies an opening produced by `open_multi_batch`, which has a single proof.
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<P::Scalar>>],
+        proofs: &[Self::Proof],
+    ) -> Result<(), ()> {
+        let [siblings] = proofs else {
+            return Err(());
+        };
+        if indices.is_empty()
+            || indices.len() != opened_values.len()
+            || opened_values
+                .iter()
+                .any(|rows| rows.len() != dimensions.len())
+        {
+            return Err(());
+        }
+
+        let mut heights_tallest_first = dimensions
+            .iter()
+            .enumerate()
+            .sorted_by_key(|(_, dims)| Reverse(dims.height))
+            .peekable();
+
+        let mut curr_height_padded = heights_tallest_first
+            .peek()
+            .unwrap()
+            .1
+            .height
+            .next_power_of_two();
+        let log_max_height = log2_ceil_usize(curr_height_padded);
+
+        // Only one opening of each row gets hashed below, so any two openings of the same row must
+        // agree.
+        for (i, j) in (0..indices.len()).tuple_combinations() {
+            for (mat, dims) in dimensions.iter().enumerate() {
+                let bits_reduced = log_max_height - log2_ceil_usize(dims.height);
+                if indices[i] >> bits_reduced == indices[j] >> bits_reduced
+                    && opened_values[i][mat] != opened_values[j][mat]
+                {
+                    return Err(());
+                }
+            }
+        }
+
+        let tallest_matrices = heights_tallest_first
+            .peeking_take_while(|(_, dims)| dims.height.next_power_of_two() == curr_height_padded)
+            .map(|(i, _)| i)
+            .collect_vec();
+
+        // For each node on the current layer that we can compute: its index, its digest, and the
+        // position in `indices` of an opening beneath it.
+        let mut layer = indices
+            .iter()
+            .enumerate()
+            .sorted_by_key(|&(_, &index)| index)
+            .dedup_by(|(_, a), (_, b)| a == b)
+            .map(|(pos, &index)| {
+                let digest = self.hash.hash_iter_slices(
+                    tallest_matrices
+                        .iter()
+                        .map(|&mat| opened_values[pos][mat].as_slice()),
+                );
+                (index, digest, pos)
+            })
+            .collect_vec();
+
+        let mut siblings = siblings.iter();
+        for _ in 0..log_max_height {
+            let mut next_layer = Vec::with_capacity(layer.len());
+            let mut i = 0;
+            while i < layer.len() {
+                let (node, digest, pos) = layer[i];
+                let (left, right) = match layer.get(i + 1) {
+                    Some(&(next_node, next_digest, _))
+                        if node & 1 == 0 && next_node == node ^ 1 =>
+                    {
+                        i += 1;
+                        (digest, next_digest)
+                    }
+                    _ => {
+                        let &sibling = siblings.next().ok_or(())?;
+                        if node & 1 == 0 {
+                            (digest, sibling)
+                        } else {
+                            (sibling, digest)
+                        }
+                    }
+                };
+                next_layer.push((node >> 1, self.compress.compress([left, right]), pos));
+                i += 1;
+            }
+            layer = next_layer;
+            curr_height_padded >>= 1;
+
+            let next_height = heights_tallest_first
+                .peek()
+                .map(|(_, dims)| dims.height)
+                .filter(|h| h.next_power_of_two() == curr_height_padded);
+            if let Some(next_height) = next_height {
+                let matrices_to_inject = heights_tallest_first
+                    .peeking_take_while(|(_, dims)| dims.height == next_height)
+                    .map(|(i, _)| i)
+                    .collect_vec();
+                for (_, digest, pos) in layer.iter_mut() {
+                    let rows_digest = self.hash.hash_iter_slices(
+                        matrices_to_inject
+                            .iter()
+                            .map(|&mat| opened_values[*pos][mat].as_slice()),
+                    );
+                    *digest = self.compress.compress([*digest, rows_digest]);
+                }
+            }
+        }
+
+        if siblings.next().is_some() {
+            return Err(());
+        }
+        // An index beyond the tallest matrix would leave us at a nonzero node.
+        match layer[..] {
+            [(0, root, _)] if commit == &root => Ok(()),
+            _ => Err(()),
+        }
+    }
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> DirectMmcs<P::Scalar>
@@ -176,16 +346,80 @@ where
     }
 }
 
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> FieldMerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedField,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Scalar, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    /// The commitment `commit_matrix` would return for the matrix with the given rows, computed
+    /// while streaming through them.
+    ///
+    /// Only one row and one digest per layer of the tree are held at a time, rather than the whole
+    /// matrix and the tree built from it, so memory use is logarithmic in the height. In exchange
+    /// there's no prover data, and so nothing to open; this suits e.g. recomputing a commitment to
+    /// a matrix too large to keep in memory. Every row must have the same width.
+    pub fn commit_streaming<I>(&self, rows: I) -> Hash<P::Scalar, PW::Value, DIGEST_ELEMS>
+    where
+        I: IntoIterator<Item = Vec<P::Scalar>>,
+    {
+        // The roots of the complete subtrees built so far, with their heights, tallest first.
+        // Consecutive heights strictly decrease, as in the binary representation of the number of
+        // leaves seen.
+        let mut stack: Vec<(usize, [PW::Value; DIGEST_ELEMS])> = Vec::new();
+        let mut push = |mut digest: [PW::Value; DIGEST_ELEMS]| {
+            let mut height = 0;
+            while let Some(&(top_height, top)) = stack.last() {
+                if top_height != height {
+                    break;
+                }
+                stack.pop();
+                digest = self.compress.compress([top, digest]);
+                height += 1;
+            }
+            stack.push((height, digest));
+        };
+
+        let mut num_rows = 0;
+        let mut width = None;
+        for row in rows {
+            assert_eq!(*width.get_or_insert(row.len()), row.len(), "Ragged rows");
+            push(self.hash.hash_slice(&row));
+            num_rows += 1;
+        }
+        assert!(num_rows > 0, "No rows given?");
+
+        // As in `FieldMerkleTree`, leaves beyond the height are padded up to a power of two with
+        // default digests.
+        let default_digest = [PW::Value::default(); DIGEST_ELEMS];
+        for _ in num_rows..num_rows.next_power_of_two() {
+            push(default_digest);
+        }
+
+        debug_assert_eq!(stack.len(), 1);
+        stack[0].1.into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec;
 
     use itertools::Itertools;
     use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
-    use p3_commit::{DirectMmcs, Mmcs};
+    use p3_commit::testing::InstrumentedMmcs;
+    use p3_commit::{DirectMmcs, ExtensionMmcs, Mmcs};
+    use p3_field::extension::BinomialExtensionField;
     use p3_field::{AbstractField, Field};
     use p3_matrix::dense::RowMajorMatrix;
-    use p3_matrix::{Dimensions, Matrix};
+    use p3_matrix::{Dimensions, Matrix, MatrixRowSlices};
     use p3_poseidon2::Poseidon2;
     use p3_symmetric::{
         CryptographicHasher, PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
@@ -235,6 +469,33 @@ mod tests {
         assert_eq!(commit, expected_result);
     }
 
+    #[test]
+    fn root_matches_commit() {
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 16, 3);
+
+        let (commit_1, data_1) = mmcs.commit_matrix(mat.clone());
+        let (commit_2, data_2) = mmcs.commit_matrix(mat);
+        assert_eq!(commit_1, commit_2);
+        assert_eq!(mmcs.root(&data_1), commit_1);
+        assert_eq!(mmcs.root(&data_2), mmcs.root(&data_1));
+    }
+
+    #[test]
+    fn commit_streaming_matches_commit() {
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+
+        // Heights which are and aren't powers of two, including a single row.
+        for height in [1, 8, 11, 16] {
+            let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), height, 3);
+            let rows = mat.rows().map(|row| row.to_vec()).collect_vec();
+            let (commit, _) = mmcs.commit_matrix(mat);
+            assert_eq!(mmcs.commit_streaming(rows), commit, "height {}", height);
+        }
+    }
+
     #[test]
     fn commit_single_2x2() {
         let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
@@ -487,4 +748,265 @@ mod tests {
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
             .expect("expected verification to succeed");
     }
+
+    #[test]
+    fn matrix_dimensions() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 32, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 5, 4),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 1),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (_, prover_data) = mmcs.commit(mats);
+
+        assert_eq!(mmcs.get_matrix_dimensions(&prover_data), dims);
+        assert_eq!(mmcs.get_matrix_heights(&prover_data), [32, 5, 8]);
+        assert_eq!(mmcs.get_matrix_widths(&prover_data), [3, 4, 1]);
+        assert_eq!(mmcs.get_max_height(&prover_data), 32);
+    }
+
+    #[test]
+    fn verify_batch_with_missing_row() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 32, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+        let (mut opened_values, proof) = mmcs.open_batch(17, &prover_data);
+
+        opened_values.pop();
+        assert!(mmcs
+            .verify_batch(&commit, &dims, 17, &opened_values, &proof)
+            .is_err());
+    }
+
+    #[test]
+    fn open_multi_batch() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 32, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 32, 1),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 5, 4),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [19, 3, 17, 2, 3];
+        let (opened_values, proofs) = mmcs.open_multi_batch(&indices, &prover_data);
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &proofs)
+            .expect("expected verification to succeed");
+
+        for (&index, opened_values) in indices.iter().zip(&opened_values) {
+            let (expected_values, proof) = mmcs.open_batch(index, &prover_data);
+            assert_eq!(opened_values, &expected_values);
+            assert_eq!(proof.len(), 5);
+        }
+        // Opening each index separately takes 5 siblings apiece, 25 in total. With shared paths,
+        // the repeated 3 is free, 2 and 3 are siblings, and the paths of 17 and 19 meet one layer
+        // up. The two pairs only meet at the root, so each still needs a sibling on the layers in
+        // between, leaving 7, in layer order and then node order.
+        let layers = &prover_data.digest_layers;
+        assert_eq!(
+            proofs,
+            [vec![
+                layers[0][16],
+                layers[0][18],
+                layers[1][0],
+                layers[2][1],
+                layers[2][5],
+                layers[3][1],
+                layers[3][3],
+            ]]
+        );
+    }
+
+    #[test]
+    fn verify_multi_batch_tampered_fails() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 4, 3),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [1, 9, 10, 1];
+        let opening = mmcs.open_multi_batch(&indices, &prover_data);
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opening.0, &opening.1)
+            .expect("expected verification to succeed");
+
+        type Opening = (Vec<Vec<Vec<F>>>, Vec<Vec<[F; 8]>>);
+        let tamper = |f: &dyn Fn(&mut Opening)| {
+            let mut opening = opening.clone();
+            f(&mut opening);
+            mmcs.verify_multi_batch(&commit, &dims, &indices, &opening.0, &opening.1)
+                .expect_err("expected verification to fail");
+        };
+        tamper(&|o| o.1[0][0][0] += F::one());
+        tamper(&|o| o.0[0][0][1] += F::one());
+        // Only one of two openings of the same row is hashed, so they must be checked to agree.
+        tamper(&|o| o.0[3][0][0] += F::one());
+        // 9 and 10 share a row of the smaller matrix.
+        tamper(&|o| o.0[2][1][0] += F::one());
+        tamper(&|o| {
+            o.1[0].pop();
+        });
+        // The siblings can't be split into one proof per index, as the default expects.
+        tamper(&|o| o.1.push(vec![]));
+
+        mmcs.verify_multi_batch(&commit, &dims, &[1, 9, 11, 1], &opening.0, &opening.1)
+            .expect_err("expected verification to fail");
+    }
+
+    #[test]
+    fn verify_multi_batch_shared_sibling() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![RowMajorMatrix::<F>::rand(&mut rng, 8, 2)];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        // The paths of 0 and 2 meet one layer up, so on the top layer both need the root of the
+        // right half of the tree, which the opening holds only once.
+        let indices = [0, 2];
+        let (opened_values, proofs) = mmcs.open_multi_batch(&indices, &prover_data);
+        let [siblings] = &proofs[..] else {
+            panic!("expected a single proof");
+        };
+        assert_eq!(siblings.len(), 3);
+        for index in indices {
+            let (_, proof) = mmcs.open_batch(index, &prover_data);
+            assert_eq!(proof.last(), siblings.last());
+        }
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &proofs)
+            .expect("expected verification to succeed");
+
+        let mut tampered = proofs.clone();
+        tampered[0][2][0] += F::one();
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &tampered)
+            .expect_err("expected verification to fail");
+
+        // Each index's own sibling must be used for that index.
+        let mut swapped = proofs.clone();
+        swapped[0].swap(0, 1);
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &swapped)
+            .expect_err("expected verification to fail");
+    }
+
+    #[test]
+    fn instrumented_access_log() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = InstrumentedMmcs::new(MyMmcs::new(hash, compress));
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 16, 2);
+        let dims = [mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit_matrix(mat);
+
+        // Log through a clone, as a PCS holding the MMCS would.
+        let pcs_mmcs = mmcs.clone();
+        for index in [5, 12] {
+            let (opened_values, proof) = pcs_mmcs.open_batch(index, &prover_data);
+            pcs_mmcs
+                .verify_batch(&commit, &dims, index, &opened_values, &proof)
+                .expect("expected verification to succeed");
+        }
+        assert_eq!(mmcs.access_log(), vec![5, 5, 12, 12]);
+    }
+
+    #[test]
+    fn extension_multi_batch_shares_siblings() {
+        type EF = BinomialExtensionField<F, 4>;
+
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = ExtensionMmcs::<F, EF, _>::new(MyMmcs::new(hash, compress));
+
+        let mat = RowMajorMatrix::<EF>::rand(&mut rng, 16, 3);
+        let dims = [mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit_matrix(mat.clone());
+
+        // The inner MMCS's openings are repacked into extension elements, and its single proof is
+        // passed through.
+        let indices = [6, 7];
+        let (opened_values, proofs) = mmcs.open_multi_batch(&indices, &prover_data);
+        assert_eq!(proofs.len(), 1);
+        for (&index, opened_values) in indices.iter().zip(&opened_values) {
+            assert_eq!(opened_values, &[mat.row_slice(index).to_vec()]);
+        }
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &proofs)
+            .expect("expected verification to succeed");
+
+        let mut tampered = opened_values.clone();
+        tampered[1][0][2] += EF::one();
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &tampered, &proofs)
+            .expect_err("expected verification to fail");
+    }
+
+    #[test]
+    fn default_multi_batch_opens_each_index() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        // `InstrumentedMmcs` keeps the default multi-batch methods, which loop over the indices.
+        let mmcs = InstrumentedMmcs::new(MyMmcs::new(hash, compress));
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 4, 3),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [9, 1, 9];
+        let (opened_values, proofs) = mmcs.open_multi_batch(&indices, &prover_data);
+        for ((&index, opened_values), proof) in indices.iter().zip(&opened_values).zip(&proofs) {
+            let (expected_values, expected_proof) = mmcs.open_batch(index, &prover_data);
+            assert_eq!(opened_values, &expected_values);
+            assert_eq!(proof, &expected_proof);
+        }
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &proofs)
+            .expect("expected verification to succeed");
+        // Each index is logged by the multi-batch opening, by the reference openings, and by the
+        // verification.
+        assert_eq!(mmcs.access_log(), [indices, indices, indices].concat());
+
+        let mut tampered = opened_values.clone();
+        tampered[2][1][0] += F::one();
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &tampered, &proofs)
+            .expect_err("expected verification to fail");
+    }
 }
@@ -0,0 +1,32 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use p3_field::AbstractField;
+use p3_mds::util::apply_circulant;
+use p3_mersenne_31::Mersenne31;
+use p3_monolith::MonolithMdsMatrixMersenne31;
+use p3_symmetric::Permutation;
+
+const MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH: [u64; 16] = [
+    61402, 17845, 26798, 59689, 12021, 40901, 41351, 27521, 56951, 12034, 53865, 43244, 7454,
+    33823, 28750, 1108,
+];
+
+fn bench_monolith_mds(c: &mut Criterion) {
+    let mds = MonolithMdsMatrixMersenne31::<6>;
+
+    let input: [Mersenne31; 16] = core::array::from_fn(Mersenne31::from_canonical_usize);
+    c.bench_function("monolith_mds::<Mersenne31, 16>", |b| {
+        b.iter(|| mds.permute(input))
+    });
+    c.bench_function("apply_circulant::<Mersenne31, 16>", |b| {
+        b.iter(|| apply_circulant(&MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH, input))
+    });
+
+    // Width 24 uses a Cauchy matrix, which isn't circulant, so it always takes the dense path.
+    let input: [Mersenne31; 24] = core::array::from_fn(Mersenne31::from_canonical_usize);
+    c.bench_function("monolith_mds::<Mersenne31, 24>", |b| {
+        b.iter(|| mds.permute(input))
+    });
+}
+
+criterion_group!(benches, bench_monolith_mds);
+criterion_main!(benches);
@@ -9,21 +9,27 @@ use alloc::vec::Vec;
 use p3_field::{AbstractField, PrimeField32};
 use p3_mds::MdsPermutation;
 use p3_mersenne_31::Mersenne31;
+use p3_symmetric::{
+    CryptographicPermutation, PaddingFreeSponge, Permutation, TruncatedPermutation,
+};
 use sha3::digest::{ExtendableOutput, Update};
 use sha3::{Shake128, Shake128Reader};
 
-use crate::util::get_random_u32;
+use crate::util::{final_s_box, get_random_u32, s_box};
 
-// The Monolith-31 permutation over Mersenne31.
+/// The Monolith-31 permutation over Mersenne31.
+///
+/// `WIDTH` may be any multiple of 4 between 8 and 24. For example, width 16 with
+/// `MonolithMdsMatrixMersenne31`, which uses the reference circulant MDS matrix at that width,
+/// gives an 8-to-8 compression function when wrapped in a `TruncatedPermutation`.
 // NUM_FULL_ROUNDS is the number of rounds - 1
 // (used to avoid const generics because we need an array of length NUM_FULL_ROUNDS)
+#[derive(Clone)]
 pub struct MonolithMersenne31<Mds, const WIDTH: usize, const NUM_FULL_ROUNDS: usize>
 where
     Mds: MdsPermutation<Mersenne31, WIDTH>,
 {
     pub round_constants: [[Mersenne31; WIDTH]; NUM_FULL_ROUNDS],
-    pub lookup1: Vec<u16>,
-    pub lookup2: Vec<u16>,
     pub mds: Mds,
 }
 
@@ -40,50 +46,15 @@ where
         assert_eq!(WIDTH % 4, 0);
 
         let round_constants = Self::instantiate_round_constants();
-        let lookup1 = Self::instantiate_lookup1();
-        let lookup2 = Self::instantiate_lookup2();
 
         Self {
             round_constants,
-            lookup1,
-            lookup2,
             mds,
         }
     }
 
-    fn s_box(y: u8) -> u8 {
-        let tmp = y ^ !y.rotate_left(1) & y.rotate_left(2) & y.rotate_left(3);
-        tmp.rotate_left(1)
-    }
-
     pub fn final_s_box(y: u8) -> u8 {
-        debug_assert_eq!(y >> 7, 0); // must be a 7-bit value
-
-        let y_rot_1 = (y >> 6) | (y << 1);
-        let y_rot_2 = (y >> 5) | (y << 2);
-
-        let tmp = (y ^ !y_rot_1 & y_rot_2) & 0x7F;
-        ((tmp >> 6) | (tmp << 1)) & 0x7F
-    }
-
-    fn instantiate_lookup1() -> Vec<u16> {
-        (0..=u16::MAX)
-            .map(|i| {
-                let hi = (i >> 8) as u8;
-                let lo = i as u8;
-                ((Self::s_box(hi) as u16) << 8) | Self::s_box(lo) as u16
-            })
-            .collect()
-    }
-
-    fn instantiate_lookup2() -> Vec<u16> {
-        (0..(1 << 15))
-            .map(|i| {
-                let hi = (i >> 8) as u8;
-                let lo: u8 = i as u8;
-                ((Self::final_s_box(hi) as u16) << 8) | Self::s_box(lo) as u16
-            })
-            .collect()
+        final_s_box(y)
     }
 
     fn random_field_element(shake: &mut Shake128Reader) -> Mersenne31 {
@@ -138,21 +109,16 @@ where
         }
     }
 
+    /// Applies the 8-bit S-box to each of the three low bytes of `el`, and the 7-bit S-box to its
+    /// high byte (which is a 7-bit value, since `el < 2^31`).
+    ///
+    /// This runs in constant time: the S-boxes are evaluated with bitwise operations rather than
+    /// looked up in a table, so neither branches nor memory accesses depend on `el`.
     #[inline]
     pub fn bar(&self, el: Mersenne31) -> Mersenne31 {
-        let val = &mut el.as_canonical_u32();
-
-        unsafe {
-            // get_unchecked here is safe because lookup table 1 contains 2^16 elements
-            let low = *self.lookup1.get_unchecked(*val as u16 as usize);
-
-            // get_unchecked here is safe because lookup table 2 contains 2^15 elements,
-            // and el >> 16 < 2^15 (since el < Mersenne31::ORDER_U32 < 2^31)
-            let high = *self.lookup2.get_unchecked((*val >> 16) as u16 as usize);
-            *val = (high as u32) << 16 | low as u32
-        }
-
-        Mersenne31::from_canonical_u32(*val)
+        let [b0, b1, b2, b3] = el.as_canonical_u32().to_le_bytes();
+        let val = u32::from_le_bytes([s_box(b0), s_box(b1), s_box(b2), final_s_box(b3)]);
+        Mersenne31::from_canonical_u32(val)
     }
 
     #[inline]
@@ -175,18 +141,159 @@ where
         Self::bricks(state);
         self.concrete(state);
     }
+
+    /// Runs the permutation on `state`, returning every intermediate state, e.g. for laying out
+    /// a trace of the permutation.
+    ///
+    /// The first entry is the input, followed by the state after the initial Concrete layer. Then,
+    /// for each of the `NUM_FULL_ROUNDS + 1` rounds, come the states after its Bars, Bricks and
+    /// Concrete layers, where the last includes the round constants (the final round has none).
+    /// So the last entry is the permutation's output, and there are `3 * NUM_FULL_ROUNDS + 5`
+    /// entries in total.
+    pub fn permute_with_trace(&self, mut state: [Mersenne31; WIDTH]) -> Vec<[Mersenne31; WIDTH]> {
+        let mut trace = Vec::with_capacity(3 * NUM_FULL_ROUNDS + 5);
+        trace.push(state);
+        self.concrete(&mut state);
+        trace.push(state);
+        let round_constants = self.round_constants.iter().map(Some).chain([None]);
+        for rc in round_constants {
+            self.bars(&mut state);
+            trace.push(state);
+            Self::bricks(&mut state);
+            trace.push(state);
+            self.concrete(&mut state);
+            if let Some(rc) = rc {
+                self.add_round_constants(&mut state, rc);
+            }
+            trace.push(state);
+        }
+        trace
+    }
+}
+
+impl<Mds, const WIDTH: usize, const NUM_FULL_ROUNDS: usize> Permutation<[Mersenne31; WIDTH]>
+    for MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>
+where
+    Mds: MdsPermutation<Mersenne31, WIDTH>,
+{
+    fn permute_mut(&self, input: &mut [Mersenne31; WIDTH]) {
+        self.permutation(input);
+    }
+}
+
+impl<Mds, const WIDTH: usize, const NUM_FULL_ROUNDS: usize>
+    CryptographicPermutation<[Mersenne31; WIDTH]>
+    for MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>
+where
+    Mds: MdsPermutation<Mersenne31, WIDTH>,
+{
+}
+
+/// A hash function built from a `PaddingFreeSponge` over the Monolith-31 permutation, with a rate
+/// of `RATE` elements, a capacity of `WIDTH - RATE` elements and an output of `OUT` elements.
+pub type MonolithHash<
+    Mds,
+    const WIDTH: usize,
+    const NUM_FULL_ROUNDS: usize,
+    const RATE: usize,
+    const OUT: usize,
+> = PaddingFreeSponge<MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>, WIDTH, RATE, OUT>;
+
+/// A 2-to-1 compression function over digests of `DIGEST_ELEMS` elements, which permutes the two
+/// inputs side by side with the Monolith-31 permutation, zero-padded to `WIDTH`, and truncates the
+/// result to its first `DIGEST_ELEMS` elements. `2 * DIGEST_ELEMS` must be at most `WIDTH`.
+///
+/// For example, with width 16 and `DIGEST_ELEMS = 8` this compresses two `MonolithHash` digests of
+/// 8 elements, as needed for a Merkle tree. Like `TruncatedPermutation<Perm, 2, 4, 8>`, a smaller
+/// `DIGEST_ELEMS` gives a narrower digest, which should match the hash's `OUT`.
+///
+/// Truncation doesn't weaken the permutation, but the digest size bounds the security of the
+/// tree: with `DIGEST_ELEMS` 31-bit elements, collisions can be found generically in about
+/// `2^(31 DIGEST_ELEMS / 2)` evaluations. So `DIGEST_ELEMS = 8` gives about 124-bit collision
+/// resistance, and a full 128 bits needs at least 9 elements, and so a width of at least 18. A
+/// smaller digest trades security for proof size.
+pub type MonolithCompress<
+    Mds,
+    const WIDTH: usize,
+    const NUM_FULL_ROUNDS: usize,
+    const DIGEST_ELEMS: usize,
+> = TruncatedPermutation<MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>, 2, DIGEST_ELEMS, WIDTH>;
+
+/// An incremental version of `MonolithHash`, which absorbs its input across any number of calls.
+///
+/// Input is written into the rate portion of the state as it arrives, and the state is permuted
+/// each time the rate fills up, so nothing is buffered beyond the state itself. The digest equals
+/// `MonolithHash` applied to the concatenation of all absorbed input.
+#[derive(Clone)]
+pub struct MonolithSponge<
+    Mds,
+    const WIDTH: usize,
+    const NUM_FULL_ROUNDS: usize,
+    const RATE: usize,
+    const OUT: usize,
+> where
+    Mds: MdsPermutation<Mersenne31, WIDTH>,
+{
+    monolith: MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>,
+    state: [Mersenne31; WIDTH],
+    /// The number of elements absorbed since the last permutation.
+    absorbed: usize,
+}
+
+impl<
+        Mds,
+        const WIDTH: usize,
+        const NUM_FULL_ROUNDS: usize,
+        const RATE: usize,
+        const OUT: usize,
+    > MonolithSponge<Mds, WIDTH, NUM_FULL_ROUNDS, RATE, OUT>
+where
+    Mds: MdsPermutation<Mersenne31, WIDTH>,
+{
+    pub fn new(monolith: MonolithMersenne31<Mds, WIDTH, NUM_FULL_ROUNDS>) -> Self {
+        assert!(RATE > 0 && RATE < WIDTH, "RATE must be in 1..WIDTH");
+        assert!(OUT <= WIDTH, "OUT must be at most WIDTH");
+        Self {
+            monolith,
+            state: [Mersenne31::zero(); WIDTH],
+            absorbed: 0,
+        }
+    }
+
+    pub fn absorb(&mut self, input: &[Mersenne31]) {
+        for &x in input {
+            self.state[self.absorbed] = x;
+            self.absorbed += 1;
+            if self.absorbed == RATE {
+                self.monolith.permutation(&mut self.state);
+                self.absorbed = 0;
+            }
+        }
+    }
+
+    /// Permutes any partially absorbed block, and returns the first `OUT` elements of the state.
+    pub fn finalize(mut self) -> [Mersenne31; OUT] {
+        if self.absorbed > 0 {
+            self.monolith.permutation(&mut self.state);
+        }
+        self.state[..OUT].try_into().unwrap()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use p3_field::AbstractField;
+    use alloc::vec::Vec;
+
+    use p3_field::{AbstractField, PrimeField32};
     use p3_mersenne_31::Mersenne31;
+    use p3_symmetric::{CryptographicHasher, Permutation, PseudoCompressionFunction};
 
-    use crate::monolith::MonolithMersenne31;
+    use crate::monolith::{MonolithCompress, MonolithHash, MonolithMersenne31, MonolithSponge};
     use crate::monolith_mds::MonolithMdsMatrixMersenne31;
+    use crate::util::{final_s_box, s_box};
 
     #[test]
-    fn test_monolith_31() {
+    fn test_monolith_31_width_16() {
         let mds = MonolithMdsMatrixMersenne31::<6>;
         let monolith: MonolithMersenne31<_, 16, 5> = MonolithMersenne31::new(mds);
 
@@ -213,4 +320,147 @@ mod tests {
         assert_eq!(input[14], Mersenne31::from_canonical_u64(1989726444));
         assert_eq!(input[15], Mersenne31::from_canonical_u64(1349325635));
     }
+
+    #[test]
+    fn test_permute_with_trace() {
+        let monolith: MonolithMersenne31<_, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31::<6>);
+        let input = core::array::from_fn(Mersenne31::from_canonical_usize);
+
+        let trace = monolith.permute_with_trace(input);
+        assert_eq!(trace.len(), 3 * 5 + 5);
+        assert_eq!(trace[0], input);
+        assert_eq!(*trace.last().unwrap(), monolith.permute(input));
+
+        // The second entry is the initial Concrete layer, and the third the first round's Bars.
+        let mut state = input;
+        monolith.concrete(&mut state);
+        assert_eq!(trace[1], state);
+        monolith.bars(&mut state);
+        assert_eq!(trace[2], state);
+    }
+
+    #[test]
+    fn test_monolith_hash() {
+        type Mds = MonolithMdsMatrixMersenne31<6>;
+        let monolith: MonolithMersenne31<Mds, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31);
+        let hash = MonolithHash::<Mds, 16, 5, 8, 8>::new(monolith.clone());
+
+        let input = [1, 2, 3, 4, 5].map(Mersenne31::from_canonical_u32);
+        let digest = hash.hash_slice(&input);
+        assert_eq!(digest, hash.hash_slice(&input));
+
+        // An input shorter than the rate is absorbed with a single permutation.
+        let mut state = [Mersenne31::zero(); 16];
+        state[..5].copy_from_slice(&input);
+        assert_eq!(digest[..], monolith.permute(state)[..8]);
+    }
+
+    #[test]
+    fn test_monolith_hash_rate() {
+        type Mds = MonolithMdsMatrixMersenne31<6>;
+        let monolith: MonolithMersenne31<Mds, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31);
+        let hash = MonolithHash::<Mds, 16, 5, 4, 4>::new(monolith.clone());
+
+        let input = [1, 2, 3, 4, 5].map(Mersenne31::from_canonical_u32);
+        let digest = hash.hash_slice(&input);
+        assert_eq!(digest, hash.hash_slice(&input));
+
+        // With a rate of 4, the input is absorbed in two blocks, the second overwriting only the
+        // first element of the rate.
+        let mut state = [Mersenne31::zero(); 16];
+        state[..4].copy_from_slice(&input[..4]);
+        monolith.permutation(&mut state);
+        state[0] = input[4];
+        monolith.permutation(&mut state);
+        assert_eq!(digest[..], state[..4]);
+    }
+
+    #[test]
+    fn test_monolith_compress() {
+        type Mds = MonolithMdsMatrixMersenne31<6>;
+        let monolith: MonolithMersenne31<Mds, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31);
+        let compress = MonolithCompress::<Mds, 16, 5, 8>::new(monolith.clone());
+
+        let left = core::array::from_fn(Mersenne31::from_canonical_usize);
+        let right = core::array::from_fn(|i| Mersenne31::from_canonical_usize(i + 8));
+        let digest = compress.compress([left, right]);
+        assert_eq!(digest, compress.compress([left, right]));
+        assert_ne!(digest, compress.compress([right, left]));
+
+        let mut other = right;
+        other[7] += Mersenne31::one();
+        assert_ne!(digest, compress.compress([left, other]));
+
+        // The two inputs fill the state, and the first half of the permuted state is kept.
+        let state = core::array::from_fn(Mersenne31::from_canonical_usize);
+        assert_eq!(digest[..], monolith.permute(state)[..8]);
+    }
+
+    #[test]
+    fn test_monolith_compress_truncated() {
+        type Mds = MonolithMdsMatrixMersenne31<6>;
+        let monolith: MonolithMersenne31<Mds, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31);
+        let compress = MonolithCompress::<Mds, 16, 5, 4>::new(monolith.clone());
+
+        let left = core::array::from_fn(Mersenne31::from_canonical_usize);
+        let right = core::array::from_fn(|i| Mersenne31::from_canonical_usize(i + 4));
+        let digest: [Mersenne31; 4] = compress.compress([left, right]);
+        assert_eq!(digest.len(), 4);
+
+        // The inputs fill the first half of the state, and the rest is zero.
+        let state =
+            core::array::from_fn(|i| Mersenne31::from_canonical_usize(if i < 8 { i } else { 0 }));
+        assert_eq!(digest[..], monolith.permute(state)[..4]);
+    }
+
+    #[test]
+    fn test_monolith_sponge_chunked() {
+        type Mds = MonolithMdsMatrixMersenne31<6>;
+        type Sponge = MonolithSponge<Mds, 16, 5, 8, 8>;
+        let monolith: MonolithMersenne31<Mds, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31);
+        let hash = MonolithHash::<Mds, 16, 5, 8, 8>::new(monolith.clone());
+
+        let input: Vec<Mersenne31> = (0..21).map(Mersenne31::from_canonical_u32).collect();
+        let mut sponge = Sponge::new(monolith.clone());
+        sponge.absorb(&input);
+        let digest = sponge.finalize();
+        assert_eq!(digest, hash.hash_slice(&input));
+
+        // Splitting the input anywhere, including on a rate boundary, gives the same digest.
+        for split in [0, 3, 8, 13, 21] {
+            let (first, second) = input.split_at(split);
+            let mut sponge = Sponge::new(monolith.clone());
+            sponge.absorb(first);
+            sponge.absorb(second);
+            assert_eq!(sponge.finalize(), digest, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn test_bar_matches_lookup_tables() {
+        // The lookup tables which `bar` used to read from.
+        let lookup1: Vec<u16> = (0..=u16::MAX)
+            .map(|i| ((s_box((i >> 8) as u8) as u16) << 8) | s_box(i as u8) as u16)
+            .collect();
+        let lookup2: Vec<u16> = (0..(1 << 15))
+            .map(|i: u16| ((final_s_box((i >> 8) as u8) as u16) << 8) | s_box(i as u8) as u16)
+            .collect();
+
+        let monolith: MonolithMersenne31<_, 16, 5> =
+            MonolithMersenne31::new(MonolithMdsMatrixMersenne31::<6>);
+        for val in (0..Mersenne31::ORDER_U32).step_by(9973) {
+            let low = lookup1[val as u16 as usize] as u32;
+            let high = lookup2[(val >> 16) as usize] as u32;
+            assert_eq!(
+                monolith.bar(Mersenne31::from_canonical_u32(val)),
+                Mersenne31::from_canonical_u32(high << 16 | low)
+            );
+        }
+    }
 }
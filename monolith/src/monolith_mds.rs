@@ -1,8 +1,9 @@
 //! Monolith-31's default MDS permutation.
 //! With significant inspiration from https://extgit.iaik.tugraz.at/krypto/zkfriendlyhashzoo/
 
-use p3_field::PrimeField32;
-use p3_mds::util::apply_circulant;
+use p3_field::{AbstractField, PrimeField32};
+use p3_mds::karatsuba_convolution::Convolve;
+use p3_mds::util::{dot_product, first_row_to_first_col};
 use p3_mds::MdsPermutation;
 use p3_mersenne_31::Mersenne31;
 use p3_symmetric::Permutation;
@@ -14,20 +15,51 @@ use crate::util::get_random_u32;
 #[derive(Clone)]
 pub struct MonolithMdsMatrixMersenne31<const NUM_ROUNDS: usize>;
 
-const MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH: [u64; 16] = [
+const MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH: [i64; 16] = [
     61402, 17845, 26798, 59689, 12021, 40901, 41351, 27521, 56951, 12034, 53865, 43244, 7454,
     33823, 28750, 1108,
 ];
 
+/// Convolution with the width-16 circulant matrix, using the Karatsuba-style algorithm from
+/// `p3_mds`, which takes `O(n^log2(3))` rather than `O(n^2)` operations.
+///
+/// The entries of the matrix are less than 2^16, so their sum is less than 2^20. For a convolution
+/// of size 16 with inputs less than 2^31, every intermediate value is then less than 2^59 in
+/// absolute value, so nothing overflows an `i64`.
+struct MonolithConvolveMersenne31;
+
+impl Convolve<Mersenne31, i64, i64, i64> for MonolithConvolveMersenne31 {
+    #[inline(always)]
+    fn read(input: Mersenne31) -> i64 {
+        input.as_canonical_u32() as i64
+    }
+
+    #[inline(always)]
+    fn parity_dot<const N: usize>(u: [i64; N], v: [i64; N]) -> i64 {
+        dot_product(u, v)
+    }
+
+    #[inline(always)]
+    fn reduce(z: i64) -> Mersenne31 {
+        debug_assert!(z >= 0);
+        Mersenne31::from_wrapped_u64(z as u64)
+    }
+}
+
 impl<const WIDTH: usize, const NUM_ROUNDS: usize> Permutation<[Mersenne31; WIDTH]>
     for MonolithMdsMatrixMersenne31<NUM_ROUNDS>
 {
     fn permute(&self, input: [Mersenne31; WIDTH]) -> [Mersenne31; WIDTH] {
         if WIDTH == 16 {
-            let matrix: [u64; WIDTH] = MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH[..]
-                .try_into()
-                .unwrap();
-            apply_circulant(&matrix, input)
+            const MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH_COL: [i64; 16] =
+                first_row_to_first_col(&MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH);
+            let input: [Mersenne31; 16] = input[..].try_into().unwrap();
+            let output = MonolithConvolveMersenne31::apply(
+                input,
+                MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH_COL,
+                MonolithConvolveMersenne31::conv16,
+            );
+            output[..].try_into().unwrap()
         } else {
             let mut shake = Shake128::default();
             shake.update("Monolith".as_bytes());
@@ -92,3 +124,75 @@ fn get_random_y_i<const WIDTH: usize>(
 
     res
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_field::{AbstractField, Field};
+    use p3_mds::util::apply_circulant;
+    use p3_mersenne_31::Mersenne31;
+    use p3_symmetric::Permutation;
+
+    use super::{MonolithMdsMatrixMersenne31, MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH};
+
+    type F = Mersenne31;
+
+    /// The matrix of `mds`, as an array of columns.
+    fn columns<const WIDTH: usize>(mds: &MonolithMdsMatrixMersenne31<6>) -> [[F; WIDTH]; WIDTH] {
+        core::array::from_fn(|j| {
+            let mut unit = [F::zero(); WIDTH];
+            unit[j] = F::one();
+            mds.permute(unit)
+        })
+    }
+
+    /// Checks that every 1x1 and 2x2 minor of the matrix is nonzero.
+    fn check_small_minors<const WIDTH: usize>() {
+        let cols = columns::<WIDTH>(&MonolithMdsMatrixMersenne31);
+        for j1 in 0..WIDTH {
+            for i1 in 0..WIDTH {
+                assert!(!cols[j1][i1].is_zero());
+                for j2 in j1 + 1..WIDTH {
+                    for i2 in i1 + 1..WIDTH {
+                        let minor = cols[j1][i1] * cols[j2][i2] - cols[j2][i1] * cols[j1][i2];
+                        assert!(!minor.is_zero());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_minors_width_8() {
+        check_small_minors::<8>();
+    }
+
+    #[test]
+    fn test_small_minors_width_16() {
+        check_small_minors::<16>();
+    }
+
+    #[test]
+    fn test_permute_mut() {
+        let mds = MonolithMdsMatrixMersenne31::<6>;
+        let input: [F; 16] = core::array::from_fn(|i| F::from_canonical_usize(i * i + 1));
+        let mut output = input;
+        mds.permute_mut(&mut output);
+        assert_eq!(output, mds.permute(input));
+    }
+
+    #[test]
+    fn test_convolution_matches_dense() {
+        let matrix = MATRIX_CIRC_MDS_16_MERSENNE31_MONOLITH.map(|x| x as u64);
+        let mds = MonolithMdsMatrixMersenne31::<6>;
+        for seed in 0..100u64 {
+            let input: [F; 16] = core::array::from_fn(|i| {
+                F::from_wrapped_u64((seed + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> i)
+            });
+            assert_eq!(mds.permute(input), apply_circulant(&matrix, input));
+        }
+
+        // The largest inputs maximise the intermediate values of the convolution.
+        let input = [F::neg_one(); 16];
+        assert_eq!(mds.permute(input), apply_circulant(&matrix, input));
+    }
+}
@@ -6,3 +6,26 @@ pub(crate) fn get_random_u32(shake: &mut Shake128Reader) -> u32 {
     shake.read(&mut rand);
     u32::from_le_bytes(rand)
 }
+
+// The S-boxes below are computed with bitwise operations only, with no data-dependent branches or
+// memory accesses (as a lookup table would have), so they run in constant time.
+
+/// The 8-bit Monolith S-box, `y -> (y ^ (!(y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1`.
+#[inline]
+pub(crate) fn s_box(y: u8) -> u8 {
+    let tmp = y ^ !y.rotate_left(1) & y.rotate_left(2) & y.rotate_left(3);
+    tmp.rotate_left(1)
+}
+
+/// The 7-bit Monolith S-box, `y -> (y ^ (!(y <<< 1) & (y <<< 2))) <<< 1`, with rotations of 7-bit
+/// values.
+#[inline]
+pub(crate) fn final_s_box(y: u8) -> u8 {
+    debug_assert_eq!(y >> 7, 0); // must be a 7-bit value
+
+    let y_rot_1 = (y >> 6) | (y << 1);
+    let y_rot_2 = (y >> 5) | (y << 2);
+
+    let tmp = (y ^ !y_rot_1 & y_rot_2) & 0x7F;
+    ((tmp >> 6) | (tmp << 1)) & 0x7F
+}
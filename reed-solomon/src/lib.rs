@@ -2,14 +2,19 @@
 
 #![allow(deprecated)] // TODO: Remove when UndefinedLDE is gone.
 
+mod reed_solomon_code;
+
 use std::marker::PhantomData;
 
 use p3_code::{
-    Code, CodeOrFamily, LinearCode, SystematicCode, SystematicCodeOrFamily, SystematicLinearCode,
+    Code, CodeOrFamily, DecodeError, LinearCode, SystematicCode, SystematicCodeOrFamily,
+    SystematicLinearCode,
 };
 use p3_field::Field;
 use p3_lde::UndefinedLde;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::MatrixRows;
+pub use reed_solomon_code::*;
 
 /// A Reed-Solomon code based on an `UndefinedLde`.
 pub struct UndefinedReedSolomonCode<F, L, In>
@@ -66,6 +71,19 @@ where
     fn codeword_len(&self) -> usize {
         self.n
     }
+
+    fn distance(&self) -> usize {
+        self.n - self.k + 1
+    }
+
+    /// Reed-Solomon decoding isn't implemented, so this only detects errors; see
+    /// `SystematicCode::decode_without_correction`.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        <Self as SystematicCode<F, In>>::decode_without_correction(self, word)
+    }
 }
 
 impl<F, L, In> LinearCode<F, In> for UndefinedReedSolomonCode<F, L, In>
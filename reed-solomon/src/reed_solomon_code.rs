@@ -0,0 +1,374 @@
+use std::marker::PhantomData;
+
+use p3_code::{
+    Code, CodeOrFamily, DecodeError, LinearCode, NamedCodeRegistry, SystematicCode,
+    SystematicCodeOrFamily, SystematicLinearCode,
+};
+use p3_dft::TwoAdicSubgroupDft;
+use p3_field::TwoAdicField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Matrix, MatrixRows};
+
+/// A systematic Reed-Solomon code built on a `TwoAdicSubgroupDft`.
+///
+/// A message of length `k = 2^log_message_len` is interpreted as the evaluations of a polynomial of
+/// degree less than `k` over the subgroup of order `k`. The codeword consists of the message itself,
+/// followed by the evaluations of the same polynomial over the coset `g K`, where `g` is the field's
+/// multiplicative generator and `K` is the subgroup of order `k << log_blowup`.
+pub struct ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    dft: Dft,
+    log_message_len: usize,
+    log_blowup: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F, Dft> ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    pub fn new(dft: Dft, log_message_len: usize, log_blowup: usize) -> Self {
+        Self {
+            dft,
+            log_message_len,
+            log_blowup,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, Dft, In> CodeOrFamily<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+    type Out = RowMajorMatrix<F>;
+
+    fn encode_batch(&self, messages: In) -> Self::Out {
+        assert_eq!(messages.height(), 1 << self.log_message_len);
+        let messages = messages.to_row_major_matrix();
+        let width = messages.width();
+
+        let parity = self
+            .dft
+            .coset_lde_batch(messages.clone(), self.log_blowup, F::generator())
+            .to_row_major_matrix();
+
+        let mut values = messages.values;
+        values.extend(parity.values);
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F, Dft, In> Code<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+    fn message_len(&self) -> usize {
+        1 << self.log_message_len
+    }
+
+    fn codeword_len(&self) -> usize {
+        (1 << self.log_message_len) + (1 << (self.log_message_len + self.log_blowup))
+    }
+
+    /// Every codeword symbol is an evaluation at a distinct point, so this is an MDS code, meeting
+    /// the Singleton bound `n - k + 1`.
+    fn distance(&self) -> usize {
+        <Self as Code<F, In>>::codeword_len(self) - <Self as Code<F, In>>::message_len(self) + 1
+    }
+
+    /// Reed-Solomon decoding isn't implemented, so this only detects errors; see
+    /// `SystematicCode::decode_without_correction`.
+    fn decode(&self, word: &[F]) -> Result<Vec<F>, DecodeError>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        <Self as SystematicCode<F, In>>::decode_without_correction(self, word)
+    }
+}
+
+impl<F, Dft, In> LinearCode<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+}
+
+impl<F, Dft, In> SystematicCodeOrFamily<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+}
+
+impl<F, Dft, In> SystematicCode<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+    fn parity_only(&self, message: &[F]) -> Vec<F>
+    where
+        In: From<RowMajorMatrix<F>>,
+    {
+        assert_eq!(message.len(), 1 << self.log_message_len);
+        self.dft
+            .coset_lde(message.to_vec(), self.log_blowup, F::generator())
+    }
+}
+
+impl<F, Dft, In> SystematicLinearCode<F, In> for ReedSolomonCode<F, Dft>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F>,
+    In: MatrixRows<F>,
+{
+}
+
+/// A `NamedCodeRegistry` holding the builtin codes of `p3_code`, as given by
+/// `NamedCodeRegistry::with_builtins`, and also a `ReedSolomonCode` registered as `"reed_solomon"`.
+/// All codes take messages of length `2^log_message_len`.
+pub fn registry_with_builtins<F, Dft>(
+    dft: Dft,
+    log_message_len: usize,
+    log_blowup: usize,
+) -> NamedCodeRegistry<F, RowMajorMatrix<F>, RowMajorMatrix<F>>
+where
+    F: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<F> + 'static,
+{
+    let mut registry = NamedCodeRegistry::with_builtins(1 << log_message_len);
+    registry
+        .register_named(
+            "reed_solomon",
+            Box::new(ReedSolomonCode::new(dft, log_message_len, log_blowup)),
+        )
+        .expect("\"reed_solomon\" isn't the name of a builtin");
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_code::{ConcatenatedCode, IdentityCode, SLCodeRegistry};
+    use p3_dft::Radix2DitParallel;
+    use p3_field::AbstractField;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type F = BabyBear;
+    type Mat = RowMajorMatrix<F>;
+    type Rs = ReedSolomonCode<F, Radix2DitParallel>;
+
+    #[test]
+    fn test_systematic_round_trip() {
+        let code = Rs::new(Radix2DitParallel, 4, 1);
+        assert_eq!(<Rs as Code<F, Mat>>::message_len(&code), 16);
+        assert_eq!(<Rs as Code<F, Mat>>::codeword_len(&code), 48);
+        assert_eq!(<Rs as SystematicCode<F, Mat>>::parity_len(&code), 32);
+
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..16).map(|_| rng.gen()).collect();
+        let codeword = <Rs as CodeOrFamily<F, Mat>>::encode(&code, &message);
+
+        assert_eq!(codeword.len(), 48);
+        assert_eq!(&codeword[..16], &message[..]);
+        assert_eq!(
+            &codeword[16..],
+            &Radix2DitParallel.coset_lde(message, 1, F::generator())[..]
+        );
+    }
+
+    #[test]
+    fn test_rate_and_distance() {
+        let code = Rs::new(Radix2DitParallel, 4, 1);
+        assert_eq!(<Rs as Code<F, Mat>>::rate(&code), (16, 48));
+        assert_eq!(<Rs as Code<F, Mat>>::distance(&code), 33);
+        assert_eq!(<Rs as Code<F, Mat>>::relative_distance(&code), 33.0 / 48.0);
+    }
+
+    #[test]
+    fn test_parity_only() {
+        let code = Rs::new(Radix2DitParallel, 3, 2);
+
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..8).map(|_| rng.gen()).collect();
+        let parity = <Rs as SystematicCode<F, Mat>>::parity_only(&code, &message);
+
+        assert_eq!(
+            parity.len(),
+            <Rs as SystematicCode<F, Mat>>::parity_len(&code)
+        );
+        assert_eq!(
+            [message.clone(), parity].concat(),
+            <Rs as CodeOrFamily<F, Mat>>::encode(&code, &message)
+        );
+    }
+
+    #[test]
+    fn test_is_codeword() {
+        let code = Rs::new(Radix2DitParallel, 3, 1);
+
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..8).map(|_| rng.gen()).collect();
+        let codeword = <Rs as CodeOrFamily<F, Mat>>::encode(&code, &message);
+        assert!(<Rs as SystematicCode<F, Mat>>::is_codeword(
+            &code, &codeword
+        ));
+
+        // Corrupting a single symbol, in either the message or the parity, breaks membership.
+        for i in [0, 7, 8, 23] {
+            let mut corrupted = codeword.clone();
+            corrupted[i] += F::one();
+            assert!(!<Rs as SystematicCode<F, Mat>>::is_codeword(
+                &code, &corrupted
+            ));
+        }
+
+        assert!(!<Rs as SystematicCode<F, Mat>>::is_codeword(
+            &code,
+            &codeword[1..]
+        ));
+    }
+
+    #[test]
+    fn test_decode_batch_round_trip() {
+        let code = Rs::new(Radix2DitParallel, 3, 1);
+
+        let mut rng = thread_rng();
+        let messages = Mat::rand(&mut rng, 8, 5);
+        let codewords = <Rs as CodeOrFamily<F, Mat>>::encode_batch(&code, messages.clone());
+        assert_eq!(
+            <Rs as Code<F, Mat>>::decode_batch(&code, &codewords),
+            Ok(messages.clone())
+        );
+
+        // The messages themselves are too short to be codewords.
+        assert_eq!(
+            <Rs as Code<F, Mat>>::decode_batch(&code, &messages),
+            Err(DecodeError::WrongLength {
+                expected: 24,
+                actual: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_batch_rejects_corrupted_word() {
+        let code = Rs::new(Radix2DitParallel, 3, 1);
+
+        let mut rng = thread_rng();
+        let messages = Mat::rand(&mut rng, 8, 5);
+        let mut codewords = <Rs as CodeOrFamily<F, Mat>>::encode_batch(&code, messages);
+
+        // Corrupt a parity symbol of the third codeword only; the rest are still codewords.
+        codewords.row_mut(13)[2] += F::one();
+        assert_eq!(
+            <Rs as Code<F, Mat>>::decode_batch(&code, &codewords),
+            Err(DecodeError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_decode_detects_errors() {
+        let code = Rs::new(Radix2DitParallel, 3, 1);
+
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..8).map(|_| rng.gen()).collect();
+        let codeword = <Rs as CodeOrFamily<F, Mat>>::encode(&code, &message);
+        assert_eq!(<Rs as Code<F, Mat>>::decode(&code, &codeword), Ok(message));
+
+        let mut corrupted = codeword.clone();
+        corrupted[20] += F::one();
+        assert_eq!(
+            <Rs as Code<F, Mat>>::decode(&code, &corrupted),
+            Err(DecodeError::TooManyErrors)
+        );
+        assert_eq!(
+            <Rs as Code<F, Mat>>::decode(&code, &codeword[1..]),
+            Err(DecodeError::WrongLength {
+                expected: 24,
+                actual: 23
+            })
+        );
+    }
+
+    #[test]
+    fn test_concatenated_with_identity_inner() {
+        type Concatenated = ConcatenatedCode<Rs, IdentityCode>;
+        let code = ConcatenatedCode::new(Rs::new(Radix2DitParallel, 2, 1), IdentityCode { len: 3 });
+        assert_eq!(<Concatenated as Code<F, Mat>>::message_len(&code), 12);
+        assert_eq!(<Concatenated as Code<F, Mat>>::codeword_len(&code), 36);
+        assert_eq!(<Concatenated as Code<F, Mat>>::distance(&code), 9);
+
+        let mut rng = thread_rng();
+        let message: Vec<F> = (0..12).map(|_| rng.gen()).collect();
+        let codeword = <Concatenated as CodeOrFamily<F, Mat>>::encode(&code, &message);
+        assert_eq!(codeword.len(), 36);
+
+        // With an identity inner code, position `r` of each block is an outer codeword in its own
+        // right.
+        for r in 0..3 {
+            let outer_message = message
+                .iter()
+                .skip(r)
+                .step_by(3)
+                .copied()
+                .collect::<Vec<_>>();
+            let outer_codeword = <Rs as CodeOrFamily<F, Mat>>::encode(&code.outer, &outer_message);
+            assert_eq!(
+                codeword
+                    .iter()
+                    .skip(r)
+                    .step_by(3)
+                    .copied()
+                    .collect::<Vec<_>>(),
+                outer_codeword
+            );
+        }
+        // The outer code is systematic, so the message is a prefix of the codeword.
+        assert_eq!(&codeword[..12], &message[..]);
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry = SLCodeRegistry::<F, Mat, Mat>::new(vec![
+            Box::new(Rs::new(Radix2DitParallel, 3, 1)),
+            Box::new(Rs::new(Radix2DitParallel, 2, 2)),
+        ]);
+        assert_eq!(registry.for_message_len(4).codeword_len(), 20);
+        assert_eq!(registry.for_message_len(8).codeword_len(), 24);
+    }
+
+    #[test]
+    fn test_named_registry_names() {
+        let mut registry = NamedCodeRegistry::<F, Mat, Mat>::new();
+        registry
+            .register_named("identity", Box::new(IdentityCode { len: 8 }))
+            .unwrap();
+        registry
+            .register_named("reed_solomon", Box::new(Rs::new(Radix2DitParallel, 3, 1)))
+            .unwrap();
+        assert_eq!(registry.names(), ["identity", "reed_solomon"]);
+        assert_eq!(registry.get("reed_solomon").unwrap().codeword_len(), 24);
+    }
+
+    #[test]
+    fn test_registry_with_builtins() {
+        let registry = registry_with_builtins::<F, _>(Radix2DitParallel, 3, 1);
+        assert_eq!(registry.names(), ["identity", "reed_solomon"]);
+        assert_eq!(registry.get("identity").unwrap().codeword_len(), 8);
+        assert_eq!(registry.get("reed_solomon").unwrap().codeword_len(), 24);
+    }
+}
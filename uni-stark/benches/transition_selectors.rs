@@ -0,0 +1,101 @@
+//! Measures the cost of calling `when_transition` once per constraint, as most AIRs do, against
+//! calling it once and reusing the filtered builder for every constraint.
+//!
+//! The prover's folder holds each selector precomputed for the point being evaluated, so
+//! `when_transition` only copies it, and the two should take the same time.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::testing::MockPcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::PaddingFreeSponge;
+use p3_uni_stark::{prove, StarkConfig};
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyPcs = MockPcs<Val, Radix2DitParallel, MyHash, 8>;
+type Challenger = DuplexChallenger<Val, Perm, 16>;
+type MyConfig = StarkConfig<MyPcs, Challenge, Challenger>;
+
+const NUM_CONSTRAINTS: usize = 20;
+const LOG_NUM_ROWS: usize = 14;
+
+/// One counter per column, each constrained to increase by one on every transition. With `HOIST`,
+/// `eval` calls `when_transition` once rather than once per constraint.
+struct CountersAir<const HOIST: bool>;
+
+impl<F, const HOIST: bool> BaseAir<F> for CountersAir<HOIST> {
+    fn width(&self) -> usize {
+        NUM_CONSTRAINTS
+    }
+}
+
+impl<AB: AirBuilder, const HOIST: bool> Air<AB> for CountersAir<HOIST> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        if HOIST {
+            let mut builder = builder.when_transition();
+            for i in 0..NUM_CONSTRAINTS {
+                builder.assert_eq(next[i], local[i] + AB::F::one());
+            }
+        } else {
+            for i in 0..NUM_CONSTRAINTS {
+                builder
+                    .when_transition()
+                    .assert_eq(next[i], local[i] + AB::F::one());
+            }
+        }
+    }
+}
+
+fn generate_trace() -> RowMajorMatrix<Val> {
+    let values = (0..1 << LOG_NUM_ROWS)
+        .flat_map(|row| (0..NUM_CONSTRAINTS).map(move |col| row + col))
+        .map(Val::from_canonical_usize)
+        .collect();
+    RowMajorMatrix::new(values, NUM_CONSTRAINTS)
+}
+
+fn bench_prove<const HOIST: bool>(criterion: &mut Criterion, name: &str) {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let config = MyConfig::new(MyPcs::new(Radix2DitParallel, MyHash::new(perm.clone())));
+    let trace = generate_trace();
+
+    let mut group = criterion.benchmark_group("transition_selectors");
+    group.sample_size(10);
+
+    let param = format!("rows=2^{},constraints={}", LOG_NUM_ROWS, NUM_CONSTRAINTS);
+    group.bench_with_input(BenchmarkId::new(name, param), &trace, |b, trace| {
+        b.iter(|| {
+            let mut challenger = Challenger::new(perm.clone());
+            prove(
+                &config,
+                &CountersAir::<HOIST>,
+                &mut challenger,
+                trace.clone(),
+                &vec![],
+            )
+        })
+    });
+}
+
+fn bench_transition_selectors(criterion: &mut Criterion) {
+    bench_prove::<false>(criterion, "per-constraint");
+    bench_prove::<true>(criterion, "hoisted");
+}
+
+criterion_group!(benches, bench_transition_selectors);
+criterion_main!(benches);
@@ -1,36 +1,40 @@
 use alloc::vec::Vec;
 
-use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, TwoRowMatrixView};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
 use p3_field::Field;
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::{Matrix, MatrixRowSlices};
 use tracing::instrument;
 
+use crate::folder::{transition_selector, transition_selectors};
+
 #[instrument(name = "check constraints", skip_all)]
-pub(crate) fn check_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>, public_values: &Vec<F>)
-where
+pub(crate) fn debug_check_constraints<F, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    public_values: &Vec<F>,
+) where
     F: Field,
     A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
 {
     let height = main.height();
+    let width = main.width();
+    let window_size = air.window_size();
 
     (0..height).for_each(|i| {
-        let i_next = (i + 1) % height;
-
-        let main_local = main.row_slice(i);
-        let main_next = main.row_slice(i_next);
-        let main = TwoRowMatrixView {
-            local: main_local,
-            next: main_next,
-        };
+        let window = (0..window_size)
+            .flat_map(|r| main.row_slice((i + r) % height).iter().copied())
+            .collect::<Vec<_>>();
 
         let mut builder = DebugConstraintBuilder {
             row_index: i,
-            main,
+            main: RowMajorMatrixView::new(&window, width),
             public_values,
             is_first_row: F::from_bool(i == 0),
             is_last_row: F::from_bool(i == height - 1),
-            is_transition: F::from_bool(i != height - 1),
+            is_transition: transition_selectors(window_size, |r| {
+                F::from_bool((i + r) % height != height - 1)
+            }),
         };
 
         air.eval(&mut builder);
@@ -41,11 +45,11 @@ where
 /// be detected early.
 pub struct DebugConstraintBuilder<'a, F: Field> {
     row_index: usize,
-    main: TwoRowMatrixView<'a, F>,
+    main: RowMajorMatrixView<'a, F>,
     public_values: &'a [F],
     is_first_row: F,
     is_last_row: F,
-    is_transition: F,
+    is_transition: Vec<F>,
 }
 
 impl<'a, F> AirBuilder for DebugConstraintBuilder<'a, F>
@@ -55,7 +59,7 @@ where
     type F = F;
     type Expr = F;
     type Var = F;
-    type M = TwoRowMatrixView<'a, F>;
+    type M = RowMajorMatrixView<'a, F>;
 
     fn is_first_row(&self) -> Self::Expr {
         self.is_first_row
@@ -66,11 +70,7 @@ where
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("only supports a window size of 2")
-        }
+        *transition_selector(&self.is_transition, size)
     }
 
     fn main(&self) -> Self::M {
@@ -64,3 +64,97 @@ where
         &self.pcs
     }
 }
+
+/// A choice of FRI's `log_blowup` and `proof_of_work_bits`. See `fri_params_for_security`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriSecurityParams {
+    pub log_blowup: usize,
+    pub proof_of_work_bits: usize,
+}
+
+/// Chooses the smallest `log_blowup` for which FRI with `num_queries` queries reaches
+/// `security_bits` of conjectured security, grinding at most `max_proof_of_work_bits` bits, along
+/// with the fewest proof-of-work bits that then make up the difference.
+///
+/// This uses the conjectured soundness of FRI, where each query contributes `log_blowup` bits and
+/// grinding contributes `proof_of_work_bits`, i.e.
+/// `security_bits <= log_blowup * num_queries + proof_of_work_bits`. Grinding is preferred, so the
+/// blowup is only raised above 2 once `max_proof_of_work_bits` can't make up the difference.
+pub fn fri_params_for_security(
+    security_bits: usize,
+    num_queries: usize,
+    max_proof_of_work_bits: usize,
+) -> FriSecurityParams {
+    assert!(num_queries > 0, "need at least one query");
+    let from_queries = security_bits.saturating_sub(max_proof_of_work_bits);
+    let log_blowup = from_queries.div_ceil(num_queries).max(1);
+    let proof_of_work_bits = security_bits.saturating_sub(log_blowup * num_queries);
+    FriSecurityParams {
+        log_blowup,
+        proof_of_work_bits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The conjectured security of the given parameters.
+    fn security_bits(params: FriSecurityParams, num_queries: usize) -> usize {
+        params.log_blowup * num_queries + params.proof_of_work_bits
+    }
+
+    #[test]
+    fn test_fri_params_for_100_bits() {
+        // 100 queries at rate 1/2 give 100 bits on their own.
+        let params = fri_params_for_security(100, 100, 16);
+        assert_eq!(
+            params,
+            FriSecurityParams {
+                log_blowup: 1,
+                proof_of_work_bits: 0
+            }
+        );
+
+        // 84 queries at rate 1/2 leave 16 bits to grinding.
+        let params = fri_params_for_security(100, 84, 16);
+        assert_eq!(
+            params,
+            FriSecurityParams {
+                log_blowup: 1,
+                proof_of_work_bits: 16
+            }
+        );
+
+        // With 28 queries, rate 1/2 would need 72 bits of grinding, so the rate goes down to 1/8
+        // (28 * 3 = 84 bits), and grinding covers the remaining 16.
+        let params = fri_params_for_security(100, 28, 16);
+        assert_eq!(
+            params,
+            FriSecurityParams {
+                log_blowup: 3,
+                proof_of_work_bits: 16
+            }
+        );
+
+        // Without grinding, 28 queries need rate 1/16 (28 * 4 = 112 bits).
+        let params = fri_params_for_security(100, 28, 0);
+        assert_eq!(
+            params,
+            FriSecurityParams {
+                log_blowup: 4,
+                proof_of_work_bits: 0
+            }
+        );
+
+        for num_queries in 1..=100 {
+            let params = fri_params_for_security(100, num_queries, 16);
+            assert!(security_bits(params, num_queries) >= 100);
+            assert!(params.proof_of_work_bits <= 16);
+            // Lowering the blowup would need more than 16 bits of grinding.
+            if params.log_blowup > 1 {
+                assert!(100 - (params.log_blowup - 1) * num_queries > 16);
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
+use p3_field::Field;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
+use p3_matrix::{Matrix, MatrixRowSlices};
+use tracing::instrument;
+
+use crate::folder::{transition_selector, transition_selectors};
+
+/// Evaluates `air` on every row of `trace`, without proving anything, and returns the first
+/// constraint which doesn't hold.
+///
+/// Rows are checked in order, and within a row constraints are numbered in the order `air.eval`
+/// asserts them, as in `get_symbolic_constraints`. As in the STARK itself, the window of rows an
+/// AIR sees wraps around from the last row to the first.
+#[instrument(name = "check constraint satisfaction", skip_all)]
+pub fn check_constraints<F, A>(
+    air: &A,
+    trace: &RowMajorMatrix<F>,
+    public_values: &[F],
+) -> Result<(), ConstraintViolation>
+where
+    F: Field,
+    A: for<'a> Air<ConstraintCheckingBuilder<'a, F>>,
+{
+    let height = trace.height();
+    let window_size = air.window_size();
+    for row in 0..height {
+        let window = (0..window_size)
+            .flat_map(|r| trace.row_slice((row + r) % height).iter().copied())
+            .collect::<Vec<_>>();
+        let mut builder = ConstraintCheckingBuilder {
+            main: RowMajorMatrixView::new(&window, trace.width()),
+            public_values,
+            is_first_row: F::from_bool(row == 0),
+            is_last_row: F::from_bool(row == height - 1),
+            is_transition: transition_selectors(window_size, |r| {
+                F::from_bool((row + r) % height != height - 1)
+            }),
+            num_constraints: 0,
+            first_failure: None,
+        };
+        air.eval(&mut builder);
+        if let Some(constraint) = builder.first_failure {
+            return Err(ConstraintViolation { row, constraint });
+        }
+    }
+    Ok(())
+}
+
+/// A constraint which `check_constraints` found not to hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// The row on which the constraint failed. A transition constraint is reported on the first
+    /// row of its window.
+    pub row: usize,
+    /// The index of the constraint, in the order the AIR asserts its constraints.
+    pub constraint: usize,
+}
+
+impl Display for ConstraintViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} had a nonzero value on row {}",
+            self.constraint, self.row
+        )
+    }
+}
+
+impl core::error::Error for ConstraintViolation {}
+
+/// An `AirBuilder` which evaluates each constraint on concrete values, and records the index of
+/// the first one which is nonzero rather than panicking.
+pub struct ConstraintCheckingBuilder<'a, F: Field> {
+    main: RowMajorMatrixView<'a, F>,
+    public_values: &'a [F],
+    is_first_row: F,
+    is_last_row: F,
+    is_transition: Vec<F>,
+    num_constraints: usize,
+    first_failure: Option<usize>,
+}
+
+impl<'a, F> AirBuilder for ConstraintCheckingBuilder<'a, F>
+where
+    F: Field,
+{
+    type F = F;
+    type Expr = F;
+    type Var = F;
+    type M = RowMajorMatrixView<'a, F>;
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.is_first_row
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.is_last_row
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        *transition_selector(&self.is_transition, size)
+    }
+
+    fn main(&self) -> Self::M {
+        self.main
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        if !x.into().is_zero() && self.first_failure.is_none() {
+            self.first_failure = Some(self.num_constraints);
+        }
+        self.num_constraints += 1;
+    }
+}
+
+impl<'a, F: Field> AirBuilderWithPublicValues for ConstraintCheckingBuilder<'a, F> {
+    fn public_values(&self) -> &[Self::F] {
+        self.public_values
+    }
+}
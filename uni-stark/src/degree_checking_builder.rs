@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+
+use p3_air::{AirBuilder, AirBuilderWithPublicValues};
+use p3_field::Field;
+
+use crate::symbolic_builder::SymbolicAirBuilder;
+use crate::symbolic_expression::SymbolicExpression;
+use crate::symbolic_variable::SymbolicVariable;
+
+/// An `AirBuilder` which evaluates constraints symbolically, like `SymbolicAirBuilder`, and panics
+/// as soon as a constraint exceeds `max_degree`.
+///
+/// Degrees are counted as in `get_max_constraint_degree`, so the first- and last-row selectors
+/// count as degree 1 and the transition selector as degree 0. Since the panic happens inside
+/// `eval`, a backtrace points at the offending constraint.
+pub struct DegreeCheckingBuilder<F: Field> {
+    inner: SymbolicAirBuilder<F>,
+    max_degree: usize,
+    degrees: Vec<usize>,
+}
+
+impl<F: Field> DegreeCheckingBuilder<F> {
+    pub fn new(width: usize, num_public_values: usize, max_degree: usize) -> Self {
+        Self::with_window_size(width, 2, num_public_values, max_degree)
+    }
+
+    /// A builder for an AIR whose `window_size` isn't the default of 2.
+    pub fn with_window_size(
+        width: usize,
+        window_size: usize,
+        num_public_values: usize,
+        max_degree: usize,
+    ) -> Self {
+        Self {
+            inner: SymbolicAirBuilder::new(width, window_size, num_public_values),
+            max_degree,
+            degrees: Vec::new(),
+        }
+    }
+
+    /// The degree of each constraint asserted so far, in order.
+    pub fn degrees(&self) -> &[usize] {
+        &self.degrees
+    }
+
+    /// The largest degree of any constraint asserted so far, or 0 if there are none.
+    pub fn max_seen_degree(&self) -> usize {
+        self.degrees.iter().copied().max().unwrap_or(0)
+    }
+}
+
+impl<F: Field> AirBuilder for DegreeCheckingBuilder<F> {
+    type F = F;
+    type Expr = SymbolicExpression<F>;
+    type Var = SymbolicVariable<F>;
+    type M = <SymbolicAirBuilder<F> as AirBuilder>::M;
+
+    fn main(&self) -> Self::M {
+        self.inner.main()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.inner.is_last_row()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        let x = x.into();
+        let degree = x.degree_multiple();
+        assert!(
+            degree <= self.max_degree,
+            "constraint {} has degree {}, but the maximum is {}",
+            self.degrees.len(),
+            degree,
+            self.max_degree
+        );
+        self.degrees.push(degree);
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<F: Field> AirBuilderWithPublicValues for DegreeCheckingBuilder<F> {
+    fn public_values(&self) -> &[Self::F] {
+        self.inner.public_values()
+    }
+}
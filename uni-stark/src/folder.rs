@@ -1,26 +1,31 @@
 use alloc::vec::Vec;
 
-use p3_air::{AirBuilder, AirBuilderWithPublicValues, TwoRowMatrixView};
+use p3_air::{AirBuilder, AirBuilderWithPublicValues};
 use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrixView;
 
 use crate::{PackedChallenge, PackedVal, StarkGenericConfig, Val};
 
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
-    pub main: TwoRowMatrixView<'a, PackedVal<SC>>,
+    /// The AIR's window of rows, starting at the current one.
+    pub main: RowMajorMatrixView<'a, PackedVal<SC>>,
     pub public_values: &'a Vec<Val<SC>>,
     pub is_first_row: PackedVal<SC>,
     pub is_last_row: PackedVal<SC>,
-    pub is_transition: PackedVal<SC>,
+    /// The transition selectors for each window size from 2 up to the AIR's, in that order.
+    pub is_transition: Vec<PackedVal<SC>>,
     pub alpha: SC::Challenge,
     pub accumulator: PackedChallenge<SC>,
 }
 
 pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
-    pub main: TwoRowMatrixView<'a, SC::Challenge>,
+    /// The AIR's window of rows, starting at the current one.
+    pub main: RowMajorMatrixView<'a, SC::Challenge>,
     pub public_values: &'a Vec<Val<SC>>,
     pub is_first_row: SC::Challenge,
     pub is_last_row: SC::Challenge,
-    pub is_transition: SC::Challenge,
+    /// The transition selectors for each window size from 2 up to the AIR's, in that order.
+    pub is_transition: Vec<SC::Challenge>,
     pub alpha: SC::Challenge,
     pub accumulator: SC::Challenge,
 }
@@ -29,7 +34,7 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
     type F = Val<SC>;
     type Expr = PackedVal<SC>;
     type Var = PackedVal<SC>;
-    type M = TwoRowMatrixView<'a, PackedVal<SC>>;
+    type M = RowMajorMatrixView<'a, PackedVal<SC>>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -44,11 +49,7 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
-        }
+        *transition_selector(&self.is_transition, size)
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -68,7 +69,7 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
     type F = Val<SC>;
     type Expr = SC::Challenge;
     type Var = SC::Challenge;
-    type M = TwoRowMatrixView<'a, SC::Challenge>;
+    type M = RowMajorMatrixView<'a, SC::Challenge>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -83,11 +84,7 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
-        }
+        *transition_selector(&self.is_transition, size)
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -101,3 +98,31 @@ impl<'a, SC: StarkGenericConfig> AirBuilderWithPublicValues for VerifierConstrai
         self.public_values
     }
 }
+
+/// The selector for transitions over windows of `size` rows, from selectors for sizes `2..`.
+pub(crate) fn transition_selector<T>(is_transition: &[T], size: usize) -> &T {
+    size.checked_sub(2)
+        .and_then(|i| is_transition.get(i))
+        .unwrap_or_else(|| {
+            panic!(
+                "transition window of size {} is outside the AIR's windows of 2..={}",
+                size,
+                is_transition.len() + 1
+            )
+        })
+}
+
+/// The transition selectors for windows of `2..=window_size` rows, given the one for 2 rows as a
+/// function of the row within the window. A window is a transition when each of its consecutive
+/// pairs of rows is.
+pub(crate) fn transition_selectors<T: AbstractField>(
+    window_size: usize,
+    is_transition: impl Fn(usize) -> T,
+) -> Vec<T> {
+    (0..window_size.saturating_sub(1))
+        .scan(T::one(), |acc, row| {
+            *acc = acc.clone() * is_transition(row);
+            Some(acc.clone())
+        })
+        .collect()
+}
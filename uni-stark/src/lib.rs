@@ -5,24 +5,30 @@
 extern crate alloc;
 
 mod config;
+mod constraint_checker;
+mod degree_checking_builder;
 mod folder;
 mod proof;
 mod prover;
+mod recursive_verifier_air;
 mod symbolic_builder;
 mod symbolic_expression;
 mod symbolic_variable;
 mod verifier;
 mod zerofier_coset;
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "debug-constraints"))]
 mod check_constraints;
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "debug-constraints"))]
 pub use check_constraints::*;
 pub use config::*;
+pub use constraint_checker::*;
+pub use degree_checking_builder::*;
 pub use folder::*;
 pub use proof::*;
 pub use prover::*;
+pub use recursive_verifier_air::*;
 pub use symbolic_builder::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
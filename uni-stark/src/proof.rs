@@ -1,9 +1,12 @@
 use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
 
 use p3_commit::Pcs;
-use serde::{Deserialize, Serialize};
+use p3_field::{AbstractExtensionField, Field};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::StarkGenericConfig;
+use crate::{StarkGenericConfig, Val};
 
 type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
@@ -14,24 +17,246 @@ type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenger,
 >>::Proof;
 
+/// The version of the proof format, which `prove` stamps on every proof. It's bumped whenever the
+/// serialized layout of `Proof` changes, and proofs of any other version are rejected when they're
+/// deserialized.
+pub const PROOF_VERSION: u32 = 1;
+
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct Proof<SC: StarkGenericConfig> {
+    /// Always `PROOF_VERSION`. It's the first field, so it can be checked before the rest of the
+    /// proof, whose layout may differ between versions.
+    #[serde(deserialize_with = "deserialize_version")]
+    pub(crate) version: u32,
     pub(crate) commitments: Commitments<Com<SC>>,
     pub(crate) opened_values: OpenedValues<SC::Challenge>,
     pub(crate) opening_proof: PcsProof<SC>,
     pub(crate) degree_bits: usize,
+    /// The public values this proof is bound to. They're observed by the challenger, so the proof
+    /// is only valid for them.
+    pub(crate) public_values: Vec<Val<SC>>,
 }
 
-#[derive(Serialize, Deserialize)]
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// The version of the proof format this proof was written in, i.e. `PROOF_VERSION`.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn commitments(&self) -> &Commitments<Com<SC>> {
+        &self.commitments
+    }
+
+    pub fn opened_values(&self) -> &OpenedValues<SC::Challenge> {
+        &self.opened_values
+    }
+
+    /// The log2 of the trace height.
+    pub fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+
+    /// The trace height, `2^degree_bits`, or `None` if `degree_bits` is too large for that to fit
+    /// in a `usize`. `degree_bits` comes from the prover, so this can't be assumed to succeed.
+    pub fn trace_height(&self) -> Option<usize> {
+        (self.degree_bits < usize::BITS as usize).then(|| 1 << self.degree_bits)
+    }
+
+    /// The public values this proof claims.
+    pub fn public_values(&self) -> &[Val<SC>] {
+        &self.public_values
+    }
+
+    /// Encodes this proof with postcard, a compact binary format. Field elements and lengths are
+    /// varint-encoded, so this is considerably smaller than a JSON encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        postcard::to_allocvec(self).expect("serializing a proof can't fail")
+    }
+
+    /// Decodes a proof produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofDecodeError> {
+        let (version, _) =
+            postcard::take_from_bytes::<u32>(bytes).map_err(ProofDecodeError::Malformed)?;
+        if version != PROOF_VERSION {
+            return Err(ProofDecodeError::UnsupportedVersion { version });
+        }
+        postcard::from_bytes(bytes).map_err(ProofDecodeError::Malformed)
+    }
+
+    /// An upper bound on the length of `to_bytes`, in bytes. The parts of the proof which the STARK
+    /// defines are counted from their shapes rather than encoded.
+    ///
+    /// postcard writes lengths, `degree_bits` and field elements as varints. Lengths are known
+    /// exactly, but each field element of the opened values and the public values is counted at the
+    /// longest varint its field's order allows, so small elements are overcounted by a few bytes
+    /// each. The commitments and the opening proof are opaque to the STARK, so only their encodings
+    /// are measured, streamed into a counter without being buffered.
+    pub fn size_in_bytes(&self) -> usize {
+        let challenge_bytes =
+            <SC::Challenge as AbstractExtensionField<Val<SC>>>::D * max_varint_len::<Val<SC>>();
+        let trace_opens = &self.opened_values.trace_opens;
+        let quotient_chunks = &self.opened_values.quotient_chunks;
+        let opened_values = varint_len(trace_opens.len())
+            + trace_opens
+                .iter()
+                .map(|(shift, values)| {
+                    varint_len(*shift) + varint_len(values.len()) + values.len() * challenge_bytes
+                })
+                .sum::<usize>()
+            + varint_len(quotient_chunks.len())
+            + quotient_chunks
+                .iter()
+                .map(|chunk| varint_len(chunk.len()) + chunk.len() * challenge_bytes)
+                .sum::<usize>();
+        let public_values = varint_len(self.public_values.len())
+            + self.public_values.len() * max_varint_len::<Val<SC>>();
+
+        varint_len(self.version as usize)
+            + encoded_len(&self.commitments)
+            + opened_values
+            + encoded_len(&self.opening_proof)
+            + varint_len(self.degree_bits)
+            + public_values
+    }
+}
+
+/// The length of postcard's varint encoding of `n`.
+fn varint_len(n: usize) -> usize {
+    (usize::BITS - n.leading_zeros()).max(1).div_ceil(7) as usize
+}
+
+/// The length of the longest varint an element of `F` can be encoded as.
+fn max_varint_len<F: Field>() -> usize {
+    F::bits().div_ceil(7)
+}
+
+/// The length of the postcard encoding of `value`.
+fn encoded_len<T: Serialize>(value: &T) -> usize {
+    postcard::to_extend(value, ByteCounter::default())
+        .expect("serializing a proof can't fail")
+        .0
+}
+
+// These are written by hand, since a derive would require `SC` itself to be `Clone` or `Debug`.
+impl<SC: StarkGenericConfig> Clone for Proof<SC>
+where
+    PcsProof<SC>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            version: self.version,
+            commitments: self.commitments.clone(),
+            opened_values: self.opened_values.clone(),
+            opening_proof: self.opening_proof.clone(),
+            degree_bits: self.degree_bits,
+            public_values: self.public_values.clone(),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> Debug for Proof<SC>
+where
+    Com<SC>: Debug,
+    PcsProof<SC>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Proof")
+            .field("version", &self.version)
+            .field("commitments", &self.commitments)
+            .field("opened_values", &self.opened_values)
+            .field("opening_proof", &self.opening_proof)
+            .field("degree_bits", &self.degree_bits)
+            .field("public_values", &self.public_values)
+            .finish()
+    }
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+    if version != PROOF_VERSION {
+        return Err(D::Error::custom(UnsupportedVersion(version)));
+    }
+    Ok(version)
+}
+
+/// An error returned by `Proof::from_bytes`.
+#[derive(Debug)]
+pub enum ProofDecodeError {
+    /// The proof was written in a version of the proof format other than `PROOF_VERSION`, e.g. by
+    /// an incompatible version of this crate.
+    UnsupportedVersion { version: u32 },
+    /// The bytes aren't a valid encoding of a proof.
+    Malformed(postcard::Error),
+}
+
+impl Display for ProofDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion { version } => write!(f, "{}", UnsupportedVersion(*version)),
+            Self::Malformed(e) => write!(f, "malformed proof: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for ProofDecodeError {}
+
+/// The message for a proof of an unsupported version, shared by `from_bytes` and deserialization.
+struct UnsupportedVersion(u32);
+
+impl Display for UnsupportedVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported proof version {}, expected {}",
+            self.0, PROOF_VERSION
+        )
+    }
+}
+
+/// The trace commitment and openings produced by `prove_trace_only`, in place of a full proof.
+///
+/// **This isn't a proof, and can't be verified.** There's no quotient, so nothing ties the trace
+/// to the AIR's constraints; it only shows what the trace commits and opens to.
+pub struct TraceOnlyProof<SC: StarkGenericConfig> {
+    /// The commitment to the trace, which is the same as `prove` would give for the trace.
+    pub trace_commit: Com<SC>,
+    /// The trace's openings, as in `OpenedValues::trace_opens`. The opening point is sampled
+    /// straight after the trace commitment, so it differs from the one `prove` would use.
+    pub trace_opens: Vec<(usize, Vec<SC::Challenge>)>,
+    /// The log2 of the trace height.
+    pub degree_bits: usize,
+}
+
+/// Counts the bytes written to it, discarding them.
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl Extend<u8> for ByteCounter {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.0 += iter.into_iter().count();
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Commitments<Com> {
-    pub(crate) trace: Com,
-    pub(crate) quotient_chunks: Com,
+    pub trace: Com,
+    pub quotient_chunks: Com,
 }
 
-#[derive(Serialize, Deserialize)]
+impl<Com> Commitments<Com> {
+    /// Both commitments in the order the prover makes them, `[trace, quotient_chunks]`, e.g. for
+    /// observing them in another transcript.
+    pub fn roots(&self) -> [&Com; 2] {
+        [&self.trace, &self.quotient_chunks]
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OpenedValues<Challenge> {
-    pub(crate) trace_local: Vec<Challenge>,
-    pub(crate) trace_next: Vec<Challenge>,
-    pub(crate) quotient_chunks: Vec<Vec<Challenge>>,
+    /// The trace's openings at `zeta` times each power of the trace domain's generator up to the
+    /// AIR's window size, tagged with the power. For the default window these are the local and
+    /// next rows, with shifts 0 and 1.
+    pub trace_opens: Vec<(usize, Vec<Challenge>)>,
+    pub quotient_chunks: Vec<Vec<Challenge>>,
 }
@@ -2,27 +2,36 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use itertools::{izip, Itertools};
-use p3_air::{Air, TwoRowMatrixView};
+use p3_air::{Air, BaseAir};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{AbstractExtensionField, AbstractField, PackedValue};
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::{Matrix, MatrixGet};
 use p3_maybe_rayon::prelude::*;
+use p3_maybe_rayon::should_parallelize;
 use p3_util::log2_strict_usize;
 use tracing::{info_span, instrument};
 
+use crate::folder::transition_selectors;
 use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
 use crate::{
-    Commitments, Domain, OpenedValues, PackedChallenge, PackedVal, Proof, ProverConstraintFolder,
-    StarkGenericConfig, Val,
+    Commitments, ConstraintCheckingBuilder, ConstraintViolation, Domain, OpenedValues,
+    PackedChallenge, PackedVal, Proof, ProverConstraintFolder, StarkGenericConfig, TraceOnlyProof,
+    Val, PROOF_VERSION,
 };
 
+/// Proves that `trace` satisfies `air` with the given public values.
+///
+/// `challenger` is used in whatever state it's passed in, and is left in its final state. Anything
+/// it has already observed, e.g. domain separation or values from an enclosing protocol, is part
+/// of the transcript: every challenge depends on it, so the proof only verifies with a challenger
+/// in the same state. This makes it possible to embed a proof in a larger Fiat-Shamir transcript.
 #[instrument(skip_all)]
 pub fn prove<
     SC,
-    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
-    #[cfg(not(debug_assertions))] A,
+    #[cfg(any(debug_assertions, feature = "debug-constraints"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-constraints")))] A,
 >(
     config: &SC,
     air: &A,
@@ -34,8 +43,8 @@ where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
 {
-    #[cfg(debug_assertions)]
-    crate::check_constraints::check_constraints(air, &trace, public_values);
+    #[cfg(any(debug_assertions, feature = "debug-constraints"))]
+    crate::check_constraints::debug_check_constraints(air, &trace, public_values);
 
     let degree = trace.height();
     let log_degree = log2_strict_usize(degree);
@@ -50,6 +59,7 @@ where
         info_span!("commit to trace data").in_scope(|| pcs.commit(vec![(trace_domain, trace)]));
 
     challenger.observe(trace_commit.clone());
+    challenger.observe_slice(public_values);
     let alpha: SC::Challenge = challenger.sample_ext_element();
 
     let quotient_domain =
@@ -79,11 +89,12 @@ where
     };
 
     let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
+    let window_size = <A as BaseAir<Val<SC>>>::window_size(air);
+    let trace_points = window_points::<SC>(trace_domain, zeta, window_size);
 
     let (opened_values, opening_proof) = pcs.open(
         vec![
-            (&trace_data, vec![vec![zeta, zeta_next]]),
+            (&trace_data, vec![trace_points]),
             (
                 &quotient_data,
                 // open every chunk at zeta
@@ -92,22 +103,82 @@ where
         ],
         challenger,
     );
-    let trace_local = opened_values[0][0][0].clone();
-    let trace_next = opened_values[0][0][1].clone();
+    let trace_opens = opened_values[0][0]
+        .iter()
+        .cloned()
+        .enumerate()
+        .collect_vec();
     let quotient_chunks = opened_values[1].iter().map(|v| v[0].clone()).collect_vec();
+    // `verify` derives the number of chunks from the AIR's constraint degree in the same way, and
+    // rejects proofs with any other number as malformed.
+    assert_eq!(
+        quotient_chunks.len(),
+        quotient_degree,
+        "expected {} quotient chunks for the AIR's constraint degree, got {}",
+        quotient_degree,
+        quotient_chunks.len()
+    );
     let opened_values = OpenedValues {
-        trace_local,
-        trace_next,
+        trace_opens,
         quotient_chunks,
     };
     Proof {
+        version: PROOF_VERSION,
         commitments,
         opened_values,
         opening_proof,
         degree_bits: log_degree,
+        public_values: public_values.clone(),
     }
 }
 
+/// Commits to `trace` and opens it as `prove` does, but checks the constraints directly instead of
+/// proving them, and stops before computing or committing to the quotient.
+///
+/// This is meant for iterating on an AIR, as it's much cheaper than `prove` and reports the first
+/// constraint the trace violates. The result **can't be verified**; see `TraceOnlyProof`.
+#[instrument(skip_all)]
+pub fn prove_trace_only<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+) -> Result<TraceOnlyProof<SC>, ConstraintViolation>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ConstraintCheckingBuilder<'a, Val<SC>>>,
+{
+    crate::constraint_checker::check_constraints(air, &trace, public_values)?;
+
+    let degree = trace.height();
+    let pcs = config.pcs();
+    let trace_domain = pcs.natural_domain_for_degree(degree);
+
+    let (trace_commit, trace_data) =
+        info_span!("commit to trace data").in_scope(|| pcs.commit(vec![(trace_domain, trace)]));
+
+    challenger.observe(trace_commit.clone());
+    challenger.observe_slice(public_values);
+
+    // There's no quotient to wait for, so the opening point is sampled right away.
+    let zeta: SC::Challenge = challenger.sample();
+    let window_size = <A as BaseAir<Val<SC>>>::window_size(air);
+    let trace_points = window_points::<SC>(trace_domain, zeta, window_size);
+    let (opened_values, _) = pcs.open(vec![(&trace_data, vec![trace_points])], challenger);
+    let trace_opens = opened_values[0][0]
+        .iter()
+        .cloned()
+        .enumerate()
+        .collect_vec();
+
+    Ok(TraceOnlyProof {
+        trace_commit,
+        trace_opens,
+        degree_bits: log2_strict_usize(degree),
+    })
+}
+
 #[instrument(name = "compute quotient polynomial", skip_all)]
 fn quotient_values<SC, A, Mat>(
     air: &A,
@@ -117,6 +188,33 @@ fn quotient_values<SC, A, Mat>(
     trace_on_quotient_domain: Mat,
     alpha: SC::Challenge,
 ) -> Vec<SC::Challenge>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    Mat: MatrixGet<Val<SC>> + Sync,
+{
+    quotient_values_with::<SC, A, Mat>(
+        air,
+        public_values,
+        trace_domain,
+        quotient_domain,
+        trace_on_quotient_domain,
+        alpha,
+        should_parallelize(quotient_domain.size()),
+    )
+}
+
+/// `quotient_values`, splitting the chunks across threads if `parallel` is set. Without the
+/// `parallel` feature, the parallel path runs on `p3_maybe_rayon`'s serial iterators.
+fn quotient_values_with<SC, A, Mat>(
+    air: &A,
+    public_values: &Vec<Val<SC>>,
+    trace_domain: Domain<SC>,
+    quotient_domain: Domain<SC>,
+    trace_on_quotient_domain: Mat,
+    alpha: SC::Challenge,
+    parallel: bool,
+) -> Vec<SC::Challenge>
 where
     SC: StarkGenericConfig,
     A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
@@ -124,6 +222,7 @@ where
 {
     let quotient_size = quotient_domain.size();
     let width = trace_on_quotient_domain.width();
+    let window_size = air.window_size();
     let sels = trace_domain.selectors_on_coset(quotient_domain);
 
     let qdb = log2_strict_usize(quotient_domain.size()) - log2_strict_usize(trace_domain.size());
@@ -131,59 +230,268 @@ where
 
     assert!(quotient_size >= PackedVal::<SC>::WIDTH);
 
-    (0..quotient_size)
-        .into_par_iter()
-        .step_by(PackedVal::<SC>::WIDTH)
-        .flat_map_iter(|i_start| {
-            let wrap = |i| i % quotient_size;
-            let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
+    let quotient_chunk = |i_start: usize| {
+        let wrap = |i| i % quotient_size;
+        let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
 
-            let is_first_row = *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
-            let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
-            let is_transition = *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
-            let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
+        let is_first_row = *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
+        let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
+        let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
 
-            let local = (0..width)
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        trace_on_quotient_domain.get(wrap(i_start + offset), col)
-                    })
-                })
-                .collect_vec();
-
-            let next = (0..width)
-                .map(|col| {
+        // The window's rows are `next_step` apart on the quotient domain, as consecutive trace
+        // rows are.
+        let is_transition = transition_selectors(window_size, |row| {
+            PackedVal::<SC>::from_fn(|offset| {
+                sels.is_transition[wrap(i_start + row * next_step + offset)]
+            })
+        });
+        let window = (0..window_size)
+            .flat_map(|row| {
+                (0..width).map(move |col| {
                     PackedVal::<SC>::from_fn(|offset| {
-                        trace_on_quotient_domain.get(wrap(i_start + next_step + offset), col)
+                        trace_on_quotient_domain.get(wrap(i_start + row * next_step + offset), col)
                     })
                 })
-                .collect_vec();
-
-            let accumulator = PackedChallenge::<SC>::zero();
-            let mut folder = ProverConstraintFolder {
-                main: TwoRowMatrixView {
-                    local: &local,
-                    next: &next,
-                },
-                public_values,
-                is_first_row,
-                is_last_row,
-                is_transition,
-                alpha,
-                accumulator,
-            };
-            air.eval(&mut folder);
-
-            // quotient(x) = constraints(x) / Z_H(x)
-            let quotient = folder.accumulator * inv_zeroifier;
-
-            // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
-            (0..PackedVal::<SC>::WIDTH).map(move |idx_in_packing| {
-                let quotient_value = (0..<SC::Challenge as AbstractExtensionField<Val<SC>>>::D)
-                    .map(|coeff_idx| quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing])
-                    .collect::<Vec<_>>();
-                SC::Challenge::from_base_slice(&quotient_value)
             })
+            .collect_vec();
+
+        let accumulator = PackedChallenge::<SC>::zero();
+        let mut folder = ProverConstraintFolder {
+            main: RowMajorMatrixView::new(&window, width),
+            public_values,
+            is_first_row,
+            is_last_row,
+            is_transition,
+            alpha,
+            accumulator,
+        };
+        air.eval(&mut folder);
+
+        // quotient(x) = constraints(x) / Z_H(x)
+        let quotient = folder.accumulator * inv_zeroifier;
+
+        // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
+        (0..PackedVal::<SC>::WIDTH).map(move |idx_in_packing| {
+            let quotient_value = (0..<SC::Challenge as AbstractExtensionField<Val<SC>>>::D)
+                .map(|coeff_idx| quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing])
+                .collect::<Vec<_>>();
+            SC::Challenge::from_base_slice(&quotient_value)
         })
-        .collect()
+    };
+
+    if parallel {
+        (0..quotient_size)
+            .into_par_iter()
+            .step_by(PackedVal::<SC>::WIDTH)
+            .flat_map_iter(quotient_chunk)
+            .collect()
+    } else {
+        (0..quotient_size)
+            .step_by(PackedVal::<SC>::WIDTH)
+            .flat_map(quotient_chunk)
+            .collect()
+    }
+}
+
+/// The points at which the trace is opened: `zeta` and the `window_size - 1` points after it.
+pub(crate) fn window_points<SC: StarkGenericConfig>(
+    trace_domain: Domain<SC>,
+    zeta: SC::Challenge,
+    window_size: usize,
+) -> Vec<SC::Challenge> {
+    let mut points = vec![zeta];
+    while points.len() < window_size {
+        let next = trace_domain.next_point(*points.last().unwrap()).unwrap();
+        points.push(next);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_air::{AirBuilder, AirBuilderWithPublicValues};
+    use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+    use p3_challenger::DuplexChallenger;
+    use p3_commit::testing::MockPcs;
+    use p3_dft::Radix2DitParallel;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_matrix::MatrixRowSlices;
+    use p3_maybe_rayon::PARALLEL_THRESHOLD;
+    use p3_poseidon2::Poseidon2;
+    use p3_symmetric::PaddingFreeSponge;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{StarkConfig, VerifierConstraintFolder};
+
+    type F = BabyBear;
+    type Challenge = BinomialExtensionField<F, 4>;
+    type Perm = Poseidon2<F, DiffusionMatrixBabybear, 16, 7>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyPcs = MockPcs<F, Radix2DitParallel, MyHash, 8>;
+    type MyConfig = StarkConfig<MyPcs, Challenge, DuplexChallenger<F, Perm, 16>>;
+
+    /// Constrains `(a, b) -> (b, a + b)` on transitions, starting from the public values `a, b`.
+    struct FibonacciAir;
+
+    impl<F> BaseAir<F> for FibonacciAir {
+        fn width(&self) -> usize {
+            2
+        }
+    }
+
+    impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let pis = builder.public_values();
+            let (a, b) = (pis[0], pis[1]);
+            let local = main.row_slice(0);
+            let next = main.row_slice(1);
+
+            builder.when_first_row().assert_eq(local[0], a);
+            builder.when_first_row().assert_eq(local[1], b);
+            builder.when_transition().assert_eq(local[1], next[0]);
+            builder
+                .when_transition()
+                .assert_eq(local[0] + local[1], next[1]);
+        }
+    }
+
+    /// The trace and quotient domains for `trace`, and the trace's evaluations on the latter, as
+    /// `prove` computes them.
+    #[allow(clippy::type_complexity)]
+    fn quotient_inputs<SC, A>(
+        config: &SC,
+        air: &A,
+        trace: RowMajorMatrix<Val<SC>>,
+        public_values: &Vec<Val<SC>>,
+    ) -> (Domain<SC>, Domain<SC>, RowMajorMatrix<Val<SC>>)
+    where
+        SC: StarkGenericConfig,
+        A: Air<SymbolicAirBuilder<Val<SC>>>,
+    {
+        let pcs = config.pcs();
+        let log_degree = log2_strict_usize(trace.height());
+        let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, public_values.len());
+        let trace_domain = pcs.natural_domain_for_degree(trace.height());
+        let quotient_domain =
+            trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
+        let (_, trace_data) = pcs.commit(vec![(trace_domain, trace)]);
+        let trace_on_quotient_domain =
+            pcs.get_evaluations_on_domain(&trace_data, 0, quotient_domain);
+        (trace_domain, quotient_domain, trace_on_quotient_domain)
+    }
+
+    /// A `FibonacciAir` trace of `num_rows` rows, starting from `0, 1`.
+    fn fibonacci_trace(num_rows: usize) -> RowMajorMatrix<F> {
+        let mut values = vec![F::zero(), F::one()];
+        for _ in 1..num_rows {
+            let (a, b) = (values[values.len() - 2], values[values.len() - 1]);
+            values.extend([b, a + b]);
+        }
+        RowMajorMatrix::new(values, 2)
+    }
+
+    /// Returns `quotient_values` for `trace`, along with the same values evaluated one point at a
+    /// time, without packing, using the verifier's folder.
+    fn quotient_and_serial_reference<SC, A>(
+        config: &SC,
+        air: &A,
+        trace: RowMajorMatrix<Val<SC>>,
+        public_values: &Vec<Val<SC>>,
+        alpha: SC::Challenge,
+    ) -> (Vec<SC::Challenge>, Vec<SC::Challenge>)
+    where
+        SC: StarkGenericConfig,
+        A: Air<SymbolicAirBuilder<Val<SC>>>
+            + for<'a> Air<ProverConstraintFolder<'a, SC>>
+            + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        let width = trace.width();
+        let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, public_values.len());
+        let (trace_domain, quotient_domain, trace_on_quotient_domain) =
+            quotient_inputs(config, air, trace, public_values);
+
+        let quotient = quotient_values::<SC, A, _>(
+            air,
+            public_values,
+            trace_domain,
+            quotient_domain,
+            trace_on_quotient_domain.clone(),
+            alpha,
+        );
+
+        let quotient_size = quotient_domain.size();
+        let next_step = 1 << log_quotient_degree;
+        let sels = trace_domain.selectors_on_coset(quotient_domain);
+        let row = |i: usize| {
+            (0..width)
+                .map(|col| SC::Challenge::from_base(trace_on_quotient_domain.get(i, col)))
+                .collect_vec()
+        };
+        let reference = (0..quotient_size)
+            .map(|i| {
+                let window = [row(i), row((i + next_step) % quotient_size)].concat();
+                let mut folder = VerifierConstraintFolder::<SC> {
+                    main: RowMajorMatrixView::new(&window, width),
+                    public_values,
+                    is_first_row: SC::Challenge::from_base(sels.is_first_row[i]),
+                    is_last_row: SC::Challenge::from_base(sels.is_last_row[i]),
+                    is_transition: vec![SC::Challenge::from_base(sels.is_transition[i])],
+                    alpha,
+                    accumulator: SC::Challenge::zero(),
+                };
+                air.eval(&mut folder);
+                folder.accumulator * sels.inv_zeroifier[i]
+            })
+            .collect_vec();
+
+        (quotient, reference)
+    }
+
+    /// The packed quotient matches the one computed a point at a time. The trace has
+    /// `PARALLEL_THRESHOLD` rows, so with the `parallel` feature the quotient is split across
+    /// threads.
+    #[test]
+    fn test_quotient_values_match_serial_reference() {
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+        let config = MyConfig::new(MyPcs::new(Radix2DitParallel, MyHash::new(perm)));
+
+        let trace = fibonacci_trace(PARALLEL_THRESHOLD);
+        let public_values = vec![F::zero(), F::one()];
+        let alpha = Challenge::from_base_fn(|i| F::from_canonical_usize(i + 2));
+
+        let (quotient, reference) =
+            quotient_and_serial_reference(&config, &FibonacciAir, trace, &public_values, alpha);
+        assert!(quotient.len() >= PARALLEL_THRESHOLD);
+        assert_eq!(quotient, reference);
+    }
+
+    /// The serial and parallel paths of `quotient_values` give identical chunks for the same
+    /// input, whichever features this is built with.
+    #[test]
+    fn test_parallel_quotient_values_match_serial() {
+        let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+        let config = MyConfig::new(MyPcs::new(Radix2DitParallel, MyHash::new(perm)));
+
+        let trace = fibonacci_trace(PARALLEL_THRESHOLD);
+        let public_values = vec![F::zero(), F::one()];
+        let alpha = Challenge::from_base_fn(|i| F::from_canonical_usize(i + 2));
+        let (trace_domain, quotient_domain, trace_on_quotient_domain) =
+            quotient_inputs(&config, &FibonacciAir, trace, &public_values);
+
+        let [serial, parallel] = [false, true].map(|parallel| {
+            quotient_values_with::<MyConfig, _, _>(
+                &FibonacciAir,
+                &public_values,
+                trace_domain,
+                quotient_domain,
+                trace_on_quotient_domain.clone(),
+                alpha,
+                parallel,
+            )
+        });
+        assert_eq!(serial.len(), quotient_domain.size());
+        assert_eq!(serial, parallel);
+    }
 }
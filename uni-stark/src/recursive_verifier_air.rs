@@ -0,0 +1,246 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::extension::{BinomialExtensionField, BinomiallyExtendable};
+use p3_field::{AbstractExtensionField, AbstractField};
+use p3_matrix::MatrixRowSlices;
+
+use crate::{Proof, StarkGenericConfig, Val};
+
+/// The columns of a `RecursiveVerifierAir` row, which holds every value `verify` computes or reads
+/// while checking one proof, one base field element per column.
+///
+/// Challenge field elements take `extension_degree` columns each, holding their base field
+/// coefficients. The groups are laid out in the order the verifier produces them: the challenges,
+/// the opened values, the out-of-domain constraint check, and finally the last FRI query.
+///
+/// The FRI part assumes a PCS like `TwoAdicFriPcs`, which folds by 2 in each round until it reaches
+/// a constant, so that a trace of height `2^degree_bits` has `degree_bits` rounds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RecursiveVerifierLayout {
+    pub num_public_values: usize,
+    pub trace_width: usize,
+    /// The number of points the trace is opened at, i.e. the AIR's window size.
+    pub num_trace_opens: usize,
+    pub num_quotient_chunks: usize,
+    pub extension_degree: usize,
+    pub num_fri_rounds: usize,
+}
+
+/// The columns of one folding round of the FRI query in a `RecursiveVerifierLayout`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriRoundCols {
+    /// The low bit of the query index in this round, which says which side of the pair the
+    /// current evaluation is on.
+    pub index_bit: usize,
+    /// The domain point `x` of the current evaluation.
+    pub x: usize,
+    /// The sibling's evaluation, opened from this round's commitment.
+    pub sibling: Range<usize>,
+    /// The evaluation folded at `beta`, which is the next round's current evaluation.
+    pub folded: Range<usize>,
+}
+
+impl RecursiveVerifierLayout {
+    /// The layout for verifying proofs shaped like `proof`.
+    pub fn for_proof<SC: StarkGenericConfig>(proof: &Proof<SC>) -> Self {
+        Self {
+            num_public_values: proof.public_values.len(),
+            trace_width: proof
+                .opened_values
+                .trace_opens
+                .first()
+                .map_or(0, |(_, values)| values.len()),
+            num_trace_opens: proof.opened_values.trace_opens.len(),
+            num_quotient_chunks: proof.opened_values.quotient_chunks.len(),
+            extension_degree: <SC::Challenge as AbstractExtensionField<Val<SC>>>::D,
+            num_fri_rounds: proof.degree_bits,
+        }
+    }
+
+    /// The number of columns, i.e. the number of base field elements the verifier handles.
+    pub fn width(&self) -> usize {
+        self.final_poly().end
+    }
+
+    /// The constraint-folding challenge.
+    pub fn alpha(&self) -> Range<usize> {
+        self.ext_after(0)
+    }
+
+    /// The out-of-domain point.
+    pub fn zeta(&self) -> Range<usize> {
+        self.ext_after(self.alpha().end)
+    }
+
+    /// The FRI folding challenges, one per round.
+    pub fn betas(&self) -> Range<usize> {
+        let start = self.zeta().end;
+        start..start + self.num_fri_rounds * self.extension_degree
+    }
+
+    /// The beta of FRI round `round`.
+    pub fn beta(&self, round: usize) -> Range<usize> {
+        assert!(round < self.num_fri_rounds);
+        self.ext_after(self.betas().start + round * self.extension_degree)
+    }
+
+    /// The trace's openings at `zeta` and the points after it.
+    pub fn trace_opens(&self) -> Range<usize> {
+        let start = self.betas().end;
+        start..start + self.num_trace_opens * self.trace_width * self.extension_degree
+    }
+
+    /// The trace's opening at the `shift`th point after `zeta`.
+    pub fn trace_open(&self, shift: usize) -> Range<usize> {
+        assert!(shift < self.num_trace_opens);
+        let len = self.trace_width * self.extension_degree;
+        let start = self.trace_opens().start + shift * len;
+        start..start + len
+    }
+
+    /// The quotient chunks' openings at `zeta`.
+    pub fn quotient_chunks(&self) -> Range<usize> {
+        let start = self.trace_opens().end;
+        start..start + self.num_quotient_chunks * self.extension_degree
+    }
+
+    /// The AIR's constraints at `zeta`, folded with `alpha` and divided by the zerofier.
+    pub fn folded_constraints(&self) -> Range<usize> {
+        self.ext_after(self.quotient_chunks().end)
+    }
+
+    /// The quotient at `zeta`, recombined from its chunks.
+    pub fn quotient(&self) -> Range<usize> {
+        self.ext_after(self.folded_constraints().end)
+    }
+
+    /// The query's reduced opening, which the first FRI round starts from.
+    pub fn reduced_opening(&self) -> Range<usize> {
+        self.ext_after(self.quotient().end)
+    }
+
+    /// The columns of FRI round `round` of the query.
+    pub fn fri_round(&self, round: usize) -> FriRoundCols {
+        assert!(round < self.num_fri_rounds);
+        let start = self.reduced_opening().end + round * self.fri_round_width();
+        let sibling = self.ext_after(start + 2);
+        FriRoundCols {
+            index_bit: start,
+            x: start + 1,
+            folded: self.ext_after(sibling.end),
+            sibling,
+        }
+    }
+
+    /// FRI's final polynomial, which the query must fold to.
+    pub fn final_poly(&self) -> Range<usize> {
+        self.ext_after(self.reduced_opening().end + self.num_fri_rounds * self.fri_round_width())
+    }
+
+    fn fri_round_width(&self) -> usize {
+        2 + 2 * self.extension_degree
+    }
+
+    fn ext_after(&self, start: usize) -> Range<usize> {
+        start..start + self.extension_degree
+    }
+}
+
+/// An in-circuit permutation, to which `RecursiveVerifierAir` delegates the verifier's hashing.
+pub trait VerifierHashAir<AB: AirBuilder>: Sync {
+    /// Constrains everything in `row` the verifier gets from hashing: the challenges sampled from
+    /// the transcript, the query index and so each round's `index_bit` and the first round's `x`,
+    /// and the Merkle openings of the query's evaluations.
+    fn eval_hashing(&self, builder: &mut AB, layout: &RecursiveVerifierLayout, row: &[AB::Var]);
+}
+
+/// A first step towards recursion: an AIR in which each row verifies one STARK proof.
+///
+/// This is only a scaffold. The row is laid out as in `RecursiveVerifierLayout`, and the AIR
+/// constrains the last FRI query, from the reduced opening through each fold to the final
+/// polynomial, with its hashing delegated to `hasher`. The out-of-domain constraint check only
+/// has columns so far, and the reduced opening isn't yet tied to the opened values.
+///
+/// `D` is the extension degree of the inner proof's challenge field, which must be a binomial
+/// extension of this AIR's field.
+pub struct RecursiveVerifierAir<H, const D: usize> {
+    pub layout: RecursiveVerifierLayout,
+    pub hasher: H,
+}
+
+impl<H, const D: usize> RecursiveVerifierAir<H, D> {
+    pub fn new(layout: RecursiveVerifierLayout, hasher: H) -> Self {
+        assert_eq!(
+            layout.extension_degree, D,
+            "layout is for a different extension degree"
+        );
+        Self { layout, hasher }
+    }
+}
+
+impl<F, H: Sync, const D: usize> BaseAir<F> for RecursiveVerifierAir<H, D> {
+    fn width(&self) -> usize {
+        self.layout.width()
+    }
+}
+
+impl<AB, H, const D: usize> Air<AB> for RecursiveVerifierAir<H, D>
+where
+    AB: AirBuilder,
+    <AB::Expr as AbstractField>::F: BinomiallyExtendable<D>,
+    H: VerifierHashAir<AB>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let row = main.row_slice(0);
+        let layout = &self.layout;
+
+        self.hasher.eval_hashing(builder, layout, row);
+
+        let ext = |cols: Range<usize>| -> BinomialExtensionField<AB::Expr, D> {
+            let coeffs = row[cols].iter().map(|&v| v.into()).collect::<Vec<_>>();
+            AbstractExtensionField::from_base_slice(&coeffs)
+        };
+
+        let mut eval = ext(layout.reduced_opening());
+        for round in 0..layout.num_fri_rounds {
+            let cols = layout.fri_round(round);
+            let bit = row[cols.index_bit];
+            let x: AB::Expr = row[cols.x].into();
+            let sibling = ext(cols.sibling);
+            let folded = ext(cols.folded);
+            let beta = ext(layout.beta(round));
+
+            builder.assert_bool(bit);
+
+            // The current evaluation is at `x` and its sibling at `-x`. They're the first and second
+            // of the pair `(x0, e0), (x1, e1)`, or the other way around if `bit` is set.
+            let bit: AB::Expr = bit.into();
+            let sign = AB::Expr::one() - bit.clone().double();
+            let x0 = BinomialExtensionField::from(sign * x.clone());
+            let bit = BinomialExtensionField::from(bit);
+            let e0 = eval.clone() + bit.clone() * (sibling.clone() - eval.clone());
+            let e1 = sibling.clone() + bit * (eval - sibling);
+
+            // folded = e0 + (beta - x0) (e1 - e0) / (x1 - x0), with x1 - x0 = -2 x0.
+            let constraint =
+                x0.clone().double() * (folded.clone() - e0.clone()) + (beta - x0) * (e1 - e0);
+            for c in constraint.as_base_slice() {
+                builder.assert_zero(c.clone());
+            }
+
+            if round + 1 < layout.num_fri_rounds {
+                let next_x = row[layout.fri_round(round + 1).x];
+                builder.assert_eq(next_x, x.square());
+            }
+            eval = folded;
+        }
+
+        let final_poly = ext(layout.final_poly());
+        for c in (eval - final_poly).as_base_slice() {
+            builder.assert_zero(c.clone());
+        }
+    }
+}
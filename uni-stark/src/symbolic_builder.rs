@@ -18,7 +18,11 @@ where
     A: Air<SymbolicAirBuilder<F>>,
 {
     // We pad to at least degree 2, since a quotient argument doesn't make sense with smaller degrees.
-    let constraint_degree = get_max_constraint_degree(air, num_public_values).max(2);
+    // A transition selector over a window of `w` rows has degree `w - 1`, which degree counting
+    // treats as slack; padding to `w` keeps the quotient within the chunks we allot it.
+    let constraint_degree = get_max_constraint_degree(air, num_public_values)
+        .max(2)
+        .max(air.window_size());
 
     // The quotient's actual degree is approximately (max_constraint_degree - 1) n,
     // where subtracting 1 comes from division by the zerofier.
@@ -48,25 +52,77 @@ where
     F: Field,
     A: Air<SymbolicAirBuilder<F>>,
 {
-    let mut builder = SymbolicAirBuilder::new(air.width(), num_public_values);
+    let mut builder = SymbolicAirBuilder::new(air.width(), air.window_size(), num_public_values);
     air.eval(&mut builder);
     builder.constraints()
 }
 
+/// Which rows a constraint applies to, according to the selector it's filtered by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// A boundary constraint on the first row, from `when_first_row`.
+    FirstRow,
+    /// A boundary constraint on the last row, from `when_last_row`.
+    LastRow,
+    /// A transition constraint, from `when_transition` or `when_transition_window`.
+    Transition,
+    /// A constraint on every row, with no selector.
+    EveryRow,
+}
+
+/// A summary of one of an AIR's constraints, as reported by `get_constraint_info`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintInfo {
+    pub kind: ConstraintKind,
+    /// The constraint's degree, counted as in `get_max_constraint_degree`.
+    pub degree: usize,
+}
+
+/// Describes each of `air`'s constraints, in the order `air.eval` asserts them, e.g. for auditing
+/// an AIR.
+///
+/// A constraint's kind comes from the first selector it was filtered by. Other conditions from
+/// `when` count towards its degree but not its kind, so a constraint filtered only by them is
+/// reported as `EveryRow`.
+pub fn get_constraint_info<F, A>(air: &A, num_public_values: usize) -> Vec<ConstraintInfo>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    get_symbolic_constraints(air, num_public_values)
+        .iter()
+        .map(|c| {
+            let kind = match c {
+                SymbolicExpression::Mul { x, .. } => match **x {
+                    SymbolicExpression::IsFirstRow => ConstraintKind::FirstRow,
+                    SymbolicExpression::IsLastRow => ConstraintKind::LastRow,
+                    SymbolicExpression::IsTransition => ConstraintKind::Transition,
+                    _ => ConstraintKind::EveryRow,
+                },
+                _ => ConstraintKind::EveryRow,
+            };
+            ConstraintInfo {
+                kind,
+                degree: c.degree_multiple(),
+            }
+        })
+        .collect()
+}
+
 /// An `AirBuilder` for evaluating constraints symbolically, and recording them for later use.
 pub struct SymbolicAirBuilder<F: Field> {
     main: RowMajorMatrix<SymbolicVariable<F>>,
+    window_size: usize,
     public_values: Vec<F>,
     constraints: Vec<SymbolicExpression<F>>,
 }
 
 impl<F: Field> SymbolicAirBuilder<F> {
-    pub(crate) fn new(width: usize, num_public_values: usize) -> Self {
-        let values = [false, true]
-            .into_iter()
-            .flat_map(|is_next| {
+    pub(crate) fn new(width: usize, window_size: usize, num_public_values: usize) -> Self {
+        let values = (0..window_size)
+            .flat_map(|row_offset| {
                 (0..width).map(move |column| SymbolicVariable {
-                    is_next,
+                    row_offset,
                     column,
                     _phantom: PhantomData,
                 })
@@ -74,6 +130,7 @@ impl<F: Field> SymbolicAirBuilder<F> {
             .collect();
         Self {
             main: RowMajorMatrix::new(values, width),
+            window_size,
             // TODO replace zeros once we have SymbolicExpression::PublicValue
             public_values: vec![F::zero(); num_public_values],
             constraints: vec![],
@@ -104,10 +161,13 @@ impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
+        if (2..=self.window_size).contains(&size) {
             SymbolicExpression::IsTransition
         } else {
-            panic!("uni-stark only supports a window size of 2")
+            panic!(
+                "transition window of size {} is outside the AIR's windows of 2..={}",
+                size, self.window_size
+            )
         }
     }
 
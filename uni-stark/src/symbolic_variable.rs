@@ -5,18 +5,19 @@ use p3_field::Field;
 
 use crate::symbolic_expression::SymbolicExpression;
 
-/// A variable within the evaluation window, i.e. a column in either the local or next row.
+/// A variable within the evaluation window, i.e. a column in one of the window's rows.
 #[derive(Copy, Clone, Debug)]
 pub struct SymbolicVariable<F: Field> {
-    pub is_next: bool,
+    /// The row within the window, with 0 the local row and 1 the next.
+    pub row_offset: usize,
     pub column: usize,
     pub(crate) _phantom: PhantomData<F>,
 }
 
 impl<F: Field> SymbolicVariable<F> {
-    pub fn new(is_next: bool, column: usize) -> Self {
+    pub fn new(row_offset: usize, column: usize) -> Self {
         Self {
-            is_next,
+            row_offset,
             column,
             _phantom: PhantomData,
         }
@@ -1,16 +1,26 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
 
 use itertools::Itertools;
-use p3_air::{Air, BaseAir, TwoRowMatrixView};
+use p3_air::{Air, BaseAir};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{AbstractExtensionField, AbstractField, Field};
-use tracing::instrument;
+use p3_commit::{OpeningError, Pcs, PolynomialSpace};
+use p3_field::{AbstractExtensionField, AbstractField, Field, PackedValue};
+use p3_matrix::dense::RowMajorMatrixView;
+use p3_maybe_rayon::prelude::*;
+use p3_util::log2_ceil_usize;
+use tracing::{info_span, instrument};
 
+use crate::folder::transition_selectors;
+use crate::prover::window_points;
 use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
-use crate::{Proof, StarkGenericConfig, Val, VerifierConstraintFolder};
+use crate::{PackedVal, Proof, StarkGenericConfig, Val, VerifierConstraintFolder};
 
+/// Verifies `proof` against `air` and the given public values.
+///
+/// As with `prove`, `challenger` is used in whatever state it's passed in, so it must be in the
+/// state the prover's challenger was in when proving began, having observed the same values.
 #[instrument(skip_all)]
 pub fn verify<SC, A>(
     config: &SC,
@@ -18,73 +28,197 @@ pub fn verify<SC, A>(
     challenger: &mut SC::Challenger,
     proof: &Proof<SC>,
     public_values: &Vec<Val<SC>>,
-) -> Result<(), VerificationError>
+) -> Result<(), VerificationError<Val<SC>>>
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    check_num_public_values(public_values, &proof.public_values)?;
+    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, public_values.len());
+    verify_with_log_quotient_degree(
+        config,
+        air,
+        challenger,
+        proof,
+        public_values,
+        log_quotient_degree,
+    )
+}
+
+/// Verifies many proofs for the same AIR, each against its own public values.
+///
+/// This is equivalent to calling `verify` on each proof with a fresh clone of `challenger`, but
+/// the AIR's quotient degree is only derived once, and with the `parallel` feature the proofs are
+/// verified in parallel. On failure, the error holds the index of the first invalid proof.
+#[instrument(skip_all, fields(num_proofs = proofs.len()))]
+pub fn verify_batch<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &SC::Challenger,
+    proofs: &[Proof<SC>],
+    public_values: &[Vec<Val<SC>>],
+) -> Result<(), BatchVerificationError<Val<SC>>>
+where
+    SC: StarkGenericConfig + Sync,
+    SC::Challenger: Clone + Sync,
+    Proof<SC>: Sync,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>> + Sync,
+{
+    assert_eq!(
+        proofs.len(),
+        public_values.len(),
+        "expected one set of public values per proof"
+    );
+    let (Some(first_proof), Some(first_public_values)) = (proofs.first(), public_values.first())
+    else {
+        return Ok(());
+    };
+    check_num_public_values(first_public_values, &first_proof.public_values)
+        .map_err(|error| BatchVerificationError { index: 0, error })?;
+    let num_public_values = first_public_values.len();
+    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, num_public_values);
+
+    let results: Vec<_> = proofs
+        .par_iter()
+        .zip(public_values.par_iter())
+        .map(|(proof, public_values)| {
+            check_num_public_values(public_values, &proof.public_values)?;
+            // The quotient degree can only depend on the number of public values.
+            let log_quotient_degree = if public_values.len() == num_public_values {
+                log_quotient_degree
+            } else {
+                get_log_quotient_degree::<Val<SC>, A>(air, public_values.len())
+            };
+            verify_with_log_quotient_degree(
+                config,
+                air,
+                &mut challenger.clone(),
+                proof,
+                public_values,
+                log_quotient_degree,
+            )
+        })
+        .collect();
+
+    results
+        .into_iter()
+        .enumerate()
+        .try_for_each(|(index, result)| {
+            result.map_err(|error| BatchVerificationError { index, error })
+        })
+}
+
+fn verify_with_log_quotient_degree<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    log_quotient_degree: usize,
+) -> Result<(), VerificationError<Val<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
 {
     let Proof {
+        version: _,
         commitments,
         opened_values,
         opening_proof,
         degree_bits,
+        public_values: proven_public_values,
     } = proof;
 
-    let degree = 1 << degree_bits;
-    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, public_values.len());
     let quotient_degree = 1 << log_quotient_degree;
 
-    let pcs = config.pcs();
-    let trace_domain = pcs.natural_domain_for_degree(degree);
-    let quotient_domain =
-        trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
-    let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
-
+    // Check the shape of the opened values first, so a malformed proof is rejected before any
+    // work is done on it.
     let air_width = <A as BaseAir<Val<SC>>>::width(air);
-    let valid_shape = opened_values.trace_local.len() == air_width
-        && opened_values.trace_next.len() == air_width
+    let window_size = <A as BaseAir<Val<SC>>>::window_size(air);
+    let valid_shape = opened_values.trace_opens.len() == window_size
+        && opened_values
+            .trace_opens
+            .iter()
+            .enumerate()
+            .all(|(i, (shift, values))| *shift == i && values.len() == air_width)
         && opened_values.quotient_chunks.len() == quotient_degree
         && opened_values
             .quotient_chunks
             .iter()
             .all(|qc| qc.len() == <SC::Challenge as AbstractExtensionField<Val<SC>>>::D);
     if !valid_shape {
-        return Err(VerificationError::InvalidProofShape);
+        return Err(VerificationError::InvalidShape);
     }
 
-    challenger.observe(commitments.trace.clone());
-    let alpha: SC::Challenge = challenger.sample_ext_element();
-    challenger.observe(commitments.quotient_chunks.clone());
-
-    let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
-
-    pcs.verify(
-        vec![
-            (
-                commitments.trace.clone(),
-                vec![(
-                    trace_domain,
-                    vec![
-                        (zeta, opened_values.trace_local.clone()),
-                        (zeta_next, opened_values.trace_next.clone()),
-                    ],
-                )],
-            ),
-            (
-                commitments.quotient_chunks.clone(),
-                quotient_chunks_domains
-                    .iter()
-                    .zip(&opened_values.quotient_chunks)
-                    .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
-                    .collect_vec(),
-            ),
-        ],
-        opening_proof,
-        challenger,
-    )
-    .map_err(|_| VerificationError::InvalidOpeningArgument)?;
+    check_public_values(public_values, proven_public_values)?;
+
+    let pcs = config.pcs();
 
+    // `degree_bits` is untrusted, so bound it before deriving any domains or shifts from it. The
+    // trace must hold at least one window, and the quotient domain, which is split into the
+    // `quotient_degree` chunks, must hold at least one packed value, as the prover requires. The
+    // PCS, and the width of a shift, bound it from above.
+    let min_degree_bits = log2_ceil_usize(window_size)
+        .max(log2_ceil_usize(PackedVal::<SC>::WIDTH).saturating_sub(log_quotient_degree));
+    let max_degree_bits = pcs
+        .max_log_degree()
+        .unwrap_or(usize::MAX)
+        .min(usize::BITS as usize - 1 - log_quotient_degree);
+    if !(min_degree_bits..=max_degree_bits).contains(degree_bits) {
+        return Err(VerificationError::InvalidShape);
+    }
+
+    let degree = 1 << degree_bits;
+
+    let trace_domain = pcs.natural_domain_for_degree(degree);
+    let quotient_domain =
+        trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
+    let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
+
+    let (alpha, zeta) = info_span!("derive challenges").in_scope(|| {
+        challenger.observe(commitments.trace.clone());
+        challenger.observe_slice(public_values);
+        let alpha: SC::Challenge = challenger.sample_ext_element();
+        challenger.observe(commitments.quotient_chunks.clone());
+
+        let zeta: SC::Challenge = challenger.sample();
+        (alpha, zeta)
+    });
+    let trace_points = window_points::<SC>(trace_domain, zeta, window_size);
+
+    info_span!("verify opening argument").in_scope(|| {
+        pcs.verify(
+            vec![
+                (
+                    commitments.trace.clone(),
+                    vec![(
+                        trace_domain,
+                        trace_points
+                            .iter()
+                            .zip(&opened_values.trace_opens)
+                            .map(|(&point, (_, values))| (point, values.clone()))
+                            .collect_vec(),
+                    )],
+                ),
+                (
+                    commitments.quotient_chunks.clone(),
+                    quotient_chunks_domains
+                        .iter()
+                        .zip(&opened_values.quotient_chunks)
+                        .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
+                        .collect_vec(),
+                ),
+            ],
+            opening_proof,
+            challenger,
+        )
+        .map_err(|e| VerificationError::InvalidOpeningArgument {
+            query: e.query_index(),
+            commitment: e.round_index().and_then(ProofCommitment::from_round),
+        })
+    })?;
+
+    let _span = info_span!("check constraints at zeta").entered();
     let zps = quotient_chunks_domains
         .iter()
         .enumerate()
@@ -114,16 +248,23 @@ where
         .sum::<SC::Challenge>();
 
     let sels = trace_domain.selectors_at_point(zeta);
+    let is_transition = transition_selectors(window_size, |row| {
+        trace_domain
+            .selectors_at_point(trace_points[row])
+            .is_transition
+    });
+    let window = opened_values
+        .trace_opens
+        .iter()
+        .flat_map(|(_, values)| values.iter().copied())
+        .collect_vec();
 
     let mut folder = VerifierConstraintFolder {
-        main: TwoRowMatrixView {
-            local: &opened_values.trace_local,
-            next: &opened_values.trace_next,
-        },
+        main: RowMajorMatrixView::new(&window, air_width),
         public_values,
         is_first_row: sels.is_first_row,
         is_last_row: sels.is_last_row,
-        is_transition: sels.is_transition,
+        is_transition,
         alpha,
         accumulator: SC::Challenge::zero(),
     };
@@ -133,18 +274,152 @@ where
     // Finally, check that
     //     folded_constraints(zeta) / Z_H(zeta) = quotient(zeta)
     if folded_constraints * sels.inv_zeroifier != quotient {
-        return Err(VerificationError::OodEvaluationMismatch);
+        return Err(VerificationError::OodEvaluationMismatch {
+            zeta: zeta.as_base_slice().to_vec(),
+        });
     }
 
     Ok(())
 }
 
+/// Checks that a proof has as many public values as the verifier expects.
+///
+/// This must be done before the AIR is evaluated symbolically with the verifier's public values,
+/// since the AIR may index into them, and would panic on too few.
+fn check_num_public_values<F>(expected: &[F], got: &[F]) -> Result<(), VerificationError<F>> {
+    if expected.len() != got.len() {
+        return Err(VerificationError::NumPublicValuesMismatch {
+            expected: expected.len(),
+            got: got.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a proof's public values are the ones the verifier expects.
+fn check_public_values<F: Field>(expected: &[F], got: &[F]) -> Result<(), VerificationError<F>> {
+    check_num_public_values(expected, got)?;
+    match expected.iter().zip(got).position(|(e, g)| e != g) {
+        Some(index) => Err(VerificationError::PublicValueMismatch {
+            index,
+            expected: expected[index],
+            got: got[index],
+        }),
+        None => Ok(()),
+    }
+}
+
 #[derive(Debug)]
-pub enum VerificationError {
-    InvalidProofShape,
-    /// An error occurred while verifying the claimed openings.
-    InvalidOpeningArgument,
+pub enum VerificationError<F> {
+    /// The proof's dimensions are inconsistent with the AIR or the config, e.g. its `degree_bits`
+    /// is outside the range the config supports or it has the wrong number of quotient chunks.
+    InvalidShape,
+    /// The proof was generated for a different number of public values than it's being verified
+    /// against.
+    NumPublicValuesMismatch { expected: usize, got: usize },
+    /// The proof was generated for different public values than the ones it's being verified
+    /// against. `index` is the first public value which differs, `expected` is the verifier's value
+    /// and `got` is the one the proof was generated for.
+    ///
+    /// Unlike the other variants, this doesn't mean the proof is invalid, only that it proves a
+    /// different statement.
+    PublicValueMismatch { index: usize, expected: F, got: F },
+    /// An error occurred while verifying the claimed openings. `query` is the index of the failing
+    /// query and `commitment` the commitment whose opening was inconsistent, where the PCS reports
+    /// them.
+    InvalidOpeningArgument {
+        query: Option<usize>,
+        commitment: Option<ProofCommitment>,
+    },
     /// Out-of-domain evaluation mismatch, i.e. `constraints(zeta)` did not match
-    /// `quotient(zeta) Z_H(zeta)`.
-    OodEvaluationMismatch,
+    /// `quotient(zeta) Z_H(zeta)`. `zeta` holds the out-of-domain point's base field coefficients.
+    OodEvaluationMismatch { zeta: Vec<F> },
 }
+
+impl<F: Display> Display for VerificationError<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidShape => write!(f, "proof has an invalid shape"),
+            Self::NumPublicValuesMismatch { expected, got } => write!(
+                f,
+                "expected {} public values, but the proof has {}",
+                expected, got
+            ),
+            Self::PublicValueMismatch {
+                index,
+                expected,
+                got,
+            } => write!(
+                f,
+                "public value {} is {}, but the proof was generated for {}",
+                index, expected, got
+            ),
+            Self::InvalidOpeningArgument { query, commitment } => {
+                write!(f, "invalid opening argument")?;
+                if let Some(commitment) = commitment {
+                    write!(f, " for the {} commitment", commitment)?;
+                }
+                if let Some(query) = query {
+                    write!(f, " at query {}", query)?;
+                }
+                Ok(())
+            }
+            Self::OodEvaluationMismatch { zeta } => {
+                write!(f, "out-of-domain evaluation mismatch at zeta = [")?;
+                for (i, coeff) in zeta.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", coeff)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl<F: Debug + Display> core::error::Error for VerificationError<F> {}
+
+/// One of the commitments in a `Proof`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProofCommitment {
+    Trace,
+    QuotientChunks,
+}
+
+impl ProofCommitment {
+    /// The commitment opened in round `round` of the PCS opening, in the order `verify` passes
+    /// them.
+    fn from_round(round: usize) -> Option<Self> {
+        match round {
+            0 => Some(Self::Trace),
+            1 => Some(Self::QuotientChunks),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ProofCommitment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trace => write!(f, "trace"),
+            Self::QuotientChunks => write!(f, "quotient"),
+        }
+    }
+}
+
+/// The error returned by `verify_batch`.
+#[derive(Debug)]
+pub struct BatchVerificationError<F> {
+    /// The index of the first proof which failed to verify.
+    pub index: usize,
+    pub error: VerificationError<F>,
+}
+
+impl<F: Display> Display for BatchVerificationError<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "proof {} failed to verify: {}", self.index, self.error)
+    }
+}
+
+impl<F: Debug + Display> core::error::Error for BatchVerificationError<F> {}
@@ -0,0 +1,64 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_matrix::MatrixRowSlices;
+use p3_uni_stark::DegreeCheckingBuilder;
+
+/// Constrains `next[0] = local[0] + local[1]` on transitions, a degree-1 constraint.
+struct LinearAir;
+
+impl<F> BaseAir<F> for LinearAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for LinearAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + local[1]);
+    }
+}
+
+/// Constrains `local[1] = local[0]^3`, a degree-3 constraint.
+struct CubeAir;
+
+impl<F> BaseAir<F> for CubeAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CubeAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let x = local[0];
+        builder.assert_eq(local[1], x * x * x);
+    }
+}
+
+#[test]
+fn test_degree_1_air() {
+    let mut builder = DegreeCheckingBuilder::<BabyBear>::new(2, 0, 2);
+    LinearAir.eval(&mut builder);
+    assert_eq!(builder.degrees(), [1]);
+    assert_eq!(builder.max_seen_degree(), 1);
+}
+
+#[test]
+fn test_degree_3_air_within_bound() {
+    let mut builder = DegreeCheckingBuilder::<BabyBear>::new(2, 0, 3);
+    CubeAir.eval(&mut builder);
+    assert_eq!(builder.max_seen_degree(), 3);
+}
+
+#[test]
+#[should_panic(expected = "constraint 0 has degree 3, but the maximum is 2")]
+fn test_degree_3_air_over_bound() {
+    let mut builder = DegreeCheckingBuilder::<BabyBear>::new(2, 0, 2);
+    CubeAir.eval(&mut builder);
+}
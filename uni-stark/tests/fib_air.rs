@@ -2,20 +2,29 @@ use std::borrow::Borrow;
 
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
-use p3_challenger::DuplexChallenger;
-use p3_commit::ExtensionMmcs;
+use p3_challenger::{CanSample, DuplexChallenger};
+use p3_commit::testing::MockPcs;
+use p3_commit::{ExtensionMmcs, Mmcs};
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
-use p3_field::{AbstractField, Field, PrimeField64};
-use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_field::{AbstractField, Field, PrimeField64, TwoAdicField};
+use p3_fri::{FriConfig, FriProof, TwoAdicFriPcs, VerifierParams};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Matrix, MatrixRowSlices};
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::Poseidon2;
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{prove, verify, StarkConfig};
-use p3_util::log2_ceil_usize;
+use p3_uni_stark::{
+    check_constraints, prove, prove_trace_only, verify, verify_batch, Commitments,
+    ConstraintViolation, DegreeCheckingBuilder, OpenedValues, Proof, ProofCommitment,
+    ProofDecodeError, RecursiveVerifierAir, RecursiveVerifierLayout, StarkConfig,
+    VerificationError, PROOF_VERSION,
+};
+use proptest::collection;
+use proptest::prelude::{any, prop_oneof, Just, Strategy};
+use proptest::test_runner::TestRunner;
 use rand::thread_rng;
+use serde::{Deserialize, Serialize};
 
 /// For testing the public values feature
 
@@ -114,23 +123,145 @@ type Dft = Radix2DitParallel;
 type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
 
-#[test]
-fn test_public_value() {
+/// A randomly generated permutation, and the MMCS over `Val` that hashes with it.
+fn perm_and_mmcs() -> (Perm, ValMmcs) {
     let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
-    let val_mmcs = ValMmcs::new(hash, compress);
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft {};
-    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
-    let fri_config = FriConfig {
+    (perm, ValMmcs::new(hash, compress))
+}
+
+/// The FRI parameters used throughout these tests, committing to FRI's rounds with `val_mmcs`.
+fn fri_config(val_mmcs: &ValMmcs) -> FriConfig<ChallengeMmcs> {
+    FriConfig {
         log_blowup: 2,
         num_queries: 28,
         proof_of_work_bits: 8,
-        mmcs: challenge_mmcs,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    }
+}
+
+/// A config for traces of `2^log_n` rows, along with the permutation that the prover's and
+/// verifier's challengers should be built from.
+fn make_config(log_n: usize) -> (MyConfig, Perm) {
+    let (perm, val_mmcs) = perm_and_mmcs();
+    let fri_config = fri_config(&val_mmcs);
+    let pcs = Pcs::new(log_n, Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_public_value() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let mut challenger = Challenger::new(perm.clone());
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+    assert_eq!(proof.public_values(), &pis[..]);
+}
+
+#[test]
+fn test_prove_over_shifted_coset() {
+    let (perm, val_mmcs) = perm_and_mmcs();
+    // The trace domain is the coset of the subgroup of order 8 which makes up the rest of the
+    // subgroup of order 16.
+    let shift = Val::two_adic_generator(4);
+    let pcs = Pcs::with_shift(3, shift, Dft {}, val_mmcs.clone(), fri_config(&val_mmcs));
+    let config = MyConfig::new(pcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+
+    // The proof is bound to the domain, so it doesn't verify over the subgroup itself.
+    let fri_config = fri_config(&val_mmcs);
+    let unshifted_config = MyConfig::new(Pcs::new(3, Dft {}, val_mmcs, fri_config));
+    let mut challenger = Challenger::new(perm);
+    assert!(verify(
+        &unshifted_config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &pis
+    )
+    .is_err());
+}
+
+#[test]
+fn test_verifier_params_round_trip() {
+    let (perm, val_mmcs) = perm_and_mmcs();
+    let fri_config = fri_config(&val_mmcs);
+    let challenge_mmcs = fri_config.mmcs.clone();
+    let shift = Val::two_adic_generator(4);
+    let pcs = Pcs::with_shift(3, shift, Dft {}, val_mmcs.clone(), fri_config);
+    let params = pcs.verifier_params();
+    let config = MyConfig::new(pcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let json = serde_json::to_string(&params).expect("serialization failed");
+    let decoded: VerifierParams<Val> = serde_json::from_str(&json).expect("deserialization failed");
+    assert_eq!(decoded, params);
+    assert_eq!(decoded.shift, shift);
+    assert_eq!(decoded.fri.num_queries, 28);
+
+    let pcs = Pcs::from_verifier_params(decoded, Dft {}, val_mmcs, challenge_mmcs);
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[test]
+fn test_without_proof_of_work() {
+    let (perm, val_mmcs) = perm_and_mmcs();
+    let fri_config = FriConfig {
+        proof_of_work_bits: 0,
+        ..fri_config(&val_mmcs)
     };
-    let pcs = Pcs::new(log2_ceil_usize(trace.height()), dft, val_mmcs, fri_config);
+    let pcs = Pcs::new(3, Dft {}, val_mmcs, fri_config);
     let config = MyConfig::new(pcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+
+    let mut prover_challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciAir {},
+        &mut prover_challenger,
+        trace,
+        &pis,
+    );
+    let mut verifier_challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &FibonacciAir {},
+        &mut verifier_challenger,
+        &proof,
+        &pis,
+    )
+    .expect("verification failed");
+
+    // Skipping the proof of work leaves both transcripts in the same state.
+    let prover_sample: Val = prover_challenger.sample();
+    let verifier_sample: Val = verifier_challenger.sample();
+    assert_eq!(prover_sample, verifier_sample);
+}
+
+#[test]
+fn test_verify_with_wrong_public_values() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
     let mut challenger = Challenger::new(perm.clone());
     let pis = vec![
         BabyBear::from_canonical_u64(0),
@@ -138,28 +269,48 @@ fn test_public_value() {
         BabyBear::from_canonical_u64(21),
     ];
     let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let wrong_pis = vec![
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(34),
+    ];
+    let mut challenger = Challenger::new(perm.clone());
+    let result = verify(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &wrong_pis,
+    );
+    assert!(matches!(
+        result,
+        Err(VerificationError::PublicValueMismatch { index: 0, expected, got })
+            if expected == Val::one() && got == Val::zero()
+    ));
+
     let mut challenger = Challenger::new(perm);
-    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+    let result = verify(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &wrong_pis[..2].to_vec(),
+    );
+    assert!(matches!(
+        result,
+        Err(VerificationError::NumPublicValuesMismatch {
+            expected: 2,
+            got: 3
+        })
+    ));
 }
 
 #[test]
 #[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
 fn test_incorrect_public_value() {
-    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
-    let hash = MyHash::new(perm.clone());
-    let compress = MyCompress::new(perm.clone());
-    let val_mmcs = ValMmcs::new(hash, compress);
-    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft {};
-    let fri_config = FriConfig {
-        log_blowup: 2,
-        num_queries: 28,
-        proof_of_work_bits: 8,
-        mmcs: challenge_mmcs,
-    };
+    let (config, perm) = make_config(3);
     let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
-    let pcs = Pcs::new(log2_ceil_usize(trace.height()), dft, val_mmcs, fri_config);
-    let config = MyConfig::new(pcs);
     let mut challenger = Challenger::new(perm.clone());
     let pis = vec![
         BabyBear::from_canonical_u64(0),
@@ -168,3 +319,557 @@ fn test_incorrect_public_value() {
     ];
     prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
 }
+
+#[cfg(any(debug_assertions, feature = "debug-constraints"))]
+#[test]
+#[should_panic(expected = "constraints had nonzero value on row 2")]
+fn test_broken_trace_panics_with_row() {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let pcs = MockPcs::<Val, Dft, MyHash, 8>::new(Dft {}, MyHash::new(perm.clone()));
+    let config = StarkConfig::<_, Challenge, Challenger>::new(pcs);
+
+    // Row 3 should be (2, 3). Breaking its right column violates `b' = a + b` between rows 2
+    // and 3, which is reported on row 2.
+    let mut trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    trace.row_mut(3)[1] += Val::one();
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    prove(
+        &config,
+        &FibonacciAir {},
+        &mut Challenger::new(perm),
+        trace,
+        &pis,
+    );
+}
+
+#[test]
+fn test_check_constraints() {
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    assert_eq!(check_constraints(&FibonacciAir {}, &trace, &pis), Ok(()));
+
+    // As above, breaking row 3 violates `b' = a + b`, the fourth constraint, on row 2.
+    let mut broken = trace.clone();
+    broken.row_mut(3)[1] += Val::one();
+    let violation = check_constraints(&FibonacciAir {}, &broken, &pis).unwrap_err();
+    assert_eq!(
+        violation,
+        ConstraintViolation {
+            row: 2,
+            constraint: 3
+        }
+    );
+    assert_eq!(
+        violation.to_string(),
+        "constraint 3 had a nonzero value on row 2"
+    );
+
+    // A wrong claimed output only violates the last-row constraint.
+    let wrong_pis = [0, 1, 22].map(Val::from_canonical_u64).to_vec();
+    assert_eq!(
+        check_constraints(&FibonacciAir {}, &trace, &wrong_pis),
+        Err(ConstraintViolation {
+            row: 7,
+            constraint: 4
+        })
+    );
+}
+
+#[test]
+fn test_verify_batch() {
+    let (config, perm) = make_config(3);
+    let challenger = Challenger::new(perm);
+
+    // F(0) = a, F(1) = b, and the trace has 8 rows, so the result is F(8) = 13a + 21b.
+    let seeds = [(0, 1), (1, 1), (2, 3)];
+    let mut pis: Vec<Vec<Val>> = seeds
+        .iter()
+        .map(|&(a, b)| {
+            [a, b, 13 * a + 21 * b]
+                .map(Val::from_canonical_u64)
+                .to_vec()
+        })
+        .collect();
+    let proofs: Vec<_> = seeds
+        .iter()
+        .zip(&pis)
+        .map(|(&(a, b), pis)| {
+            let trace = generate_trace_rows::<Val>(a, b, 1 << 3);
+            prove(
+                &config,
+                &FibonacciAir {},
+                &mut challenger.clone(),
+                trace,
+                pis,
+            )
+        })
+        .collect();
+
+    verify_batch(&config, &FibonacciAir {}, &challenger, &proofs, &pis)
+        .expect("verification failed");
+
+    pis[1][2] += Val::one();
+    let err = verify_batch(&config, &FibonacciAir {}, &challenger, &proofs, &pis).unwrap_err();
+    assert_eq!(err.index, 1);
+    assert!(matches!(
+        err.error,
+        VerificationError::PublicValueMismatch { index: 2, .. }
+    ));
+
+    // Too few public values for the AIR to read its output from are rejected before the AIR is
+    // evaluated with them.
+    pis[0].pop();
+    let err = verify_batch(&config, &FibonacciAir {}, &challenger, &proofs, &pis).unwrap_err();
+    assert_eq!(err.index, 0);
+    assert!(matches!(
+        err.error,
+        VerificationError::NumPublicValuesMismatch {
+            expected: 2,
+            got: 3
+        }
+    ));
+}
+
+#[test]
+fn test_mock_pcs() {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let pcs = MockPcs::<Val, Dft, MyHash, 8>::new(Dft {}, MyHash::new(perm.clone()));
+    let config = StarkConfig::<_, Challenge, Challenger>::new(pcs);
+
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+/// Mirrors the field layout of `Proof<MyConfig>`, so a test can re-encode a proof with altered
+/// fields through postcard. `OpeningProof` can be `RawPcsProof` to alter the opening proof too.
+#[derive(Serialize, Deserialize)]
+struct RawProof<OpeningProof = <Pcs as p3_commit::Pcs<Challenge, Challenger>>::Proof> {
+    version: u32,
+    commitments: Commitments<<Pcs as p3_commit::Pcs<Challenge, Challenger>>::Commitment>,
+    opened_values: OpenedValues<Challenge>,
+    opening_proof: OpeningProof,
+    degree_bits: usize,
+    public_values: Vec<Val>,
+}
+
+/// Mirrors the field layout of `TwoAdicFriPcsProof`.
+#[derive(Serialize, Deserialize)]
+struct RawPcsProof {
+    fri_proof: FriProof<Challenge, ChallengeMmcs, Val>,
+    query_openings: Vec<Vec<RawBatchOpening>>,
+}
+
+/// Mirrors the field layout of `BatchOpening`.
+#[derive(Serialize, Deserialize)]
+struct RawBatchOpening {
+    opened_values: Vec<Vec<Val>>,
+    opening_proof: <ValMmcs as Mmcs<Val>>::Proof,
+}
+
+#[test]
+fn test_verify_with_invalid_degree_bits() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let bytes = postcard::to_allocvec(&proof).unwrap();
+    for degree_bits in [0, 4, 63, usize::MAX] {
+        let mut raw: RawProof = postcard::from_bytes(&bytes).unwrap();
+        raw.degree_bits = degree_bits;
+        let tampered: Proof<MyConfig> =
+            postcard::from_bytes(&postcard::to_allocvec(&raw).unwrap()).unwrap();
+        assert_eq!(tampered.degree_bits(), degree_bits);
+        assert_eq!(
+            tampered.trace_height(),
+            1usize.checked_shl(degree_bits as u32)
+        );
+
+        let mut challenger = Challenger::new(perm.clone());
+        let result = verify(&config, &FibonacciAir {}, &mut challenger, &tampered, &pis);
+        assert!(matches!(result, Err(VerificationError::InvalidShape)));
+    }
+}
+
+#[test]
+fn test_verify_with_invalid_opened_values_shape() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let bytes = postcard::to_allocvec(&proof).unwrap();
+    let mutations: [fn(&mut OpenedValues<Challenge>); 8] = [
+        |ov| ov.trace_opens[0].1.push(Challenge::zero()),
+        |ov| ov.trace_opens[1].1.truncate(1),
+        |ov| ov.trace_opens.truncate(1),
+        |ov| ov.trace_opens[1].0 = 2,
+        |ov| ov.quotient_chunks.push(ov.quotient_chunks[0].clone()),
+        |ov| ov.quotient_chunks.clear(),
+        |ov| ov.quotient_chunks[0].push(Challenge::zero()),
+        |ov| ov.quotient_chunks[0].truncate(1),
+    ];
+    for mutate in mutations {
+        let mut raw: RawProof = postcard::from_bytes(&bytes).unwrap();
+        mutate(&mut raw.opened_values);
+        let tampered: Proof<MyConfig> =
+            postcard::from_bytes(&postcard::to_allocvec(&raw).unwrap()).unwrap();
+
+        // The shape is checked before anything else, even the public values.
+        let wrong_pis = [0, 1, 22].map(Val::from_canonical_u64).to_vec();
+        for pis in [&pis, &wrong_pis] {
+            let mut challenger = Challenger::new(perm.clone());
+            let result = verify(&config, &FibonacciAir {}, &mut challenger, &tampered, pis);
+            assert!(matches!(result, Err(VerificationError::InvalidShape)));
+        }
+    }
+}
+
+#[test]
+fn test_wrong_public_values_vs_invalid_proof() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    // A valid proof checked against the wrong claimed output.
+    let wrong_pis = [0, 1, 22].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let result = verify(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &wrong_pis,
+    );
+    assert!(matches!(
+        result,
+        Err(VerificationError::PublicValueMismatch { index: 2, .. })
+    ));
+
+    // An invalid proof checked against the right public values.
+    let mut raw: RawProof = postcard::from_bytes(&postcard::to_allocvec(&proof).unwrap()).unwrap();
+    raw.opened_values.trace_opens[0].1[0] += Challenge::one();
+    let tampered: Proof<MyConfig> =
+        postcard::from_bytes(&postcard::to_allocvec(&raw).unwrap()).unwrap();
+    let mut challenger = Challenger::new(perm);
+    let result = verify(&config, &FibonacciAir {}, &mut challenger, &tampered, &pis);
+    assert!(matches!(
+        result,
+        Err(VerificationError::InvalidOpeningArgument { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_opening_error_message() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    // Alter the trace value opened by the fourth query.
+    let mut raw: RawProof<RawPcsProof> =
+        postcard::from_bytes(&postcard::to_allocvec(&proof).unwrap()).unwrap();
+    raw.opening_proof.query_openings[3][0].opened_values[0][0] += Val::one();
+    let tampered: Proof<MyConfig> =
+        postcard::from_bytes(&postcard::to_allocvec(&raw).unwrap()).unwrap();
+    let mut challenger = Challenger::new(perm);
+    let error = verify(&config, &FibonacciAir {}, &mut challenger, &tampered, &pis).unwrap_err();
+    assert!(matches!(
+        error,
+        VerificationError::InvalidOpeningArgument {
+            query: Some(3),
+            commitment: Some(ProofCommitment::Trace),
+        }
+    ));
+    let message = error.to_string();
+    assert!(message.contains("query 3"), "{}", message);
+    assert!(message.contains("trace commitment"), "{}", message);
+}
+
+#[test]
+fn test_verify_with_truncated_query_openings() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let bytes = postcard::to_allocvec(&proof).unwrap();
+
+    // Drop the last query, a round of the first query, and a matrix of the first query's trace
+    // opening. Each is rejected for its shape rather than panicking or going unchecked.
+    let truncations: [fn(&mut RawPcsProof); 3] = [
+        |proof| {
+            proof.query_openings.pop();
+        },
+        |proof| {
+            proof.query_openings[0].pop();
+        },
+        |proof| {
+            proof.query_openings[0][0].opened_values.pop();
+        },
+    ];
+    for truncate in truncations {
+        let mut raw: RawProof<RawPcsProof> = postcard::from_bytes(&bytes).unwrap();
+        truncate(&mut raw.opening_proof);
+        let tampered: Proof<MyConfig> =
+            postcard::from_bytes(&postcard::to_allocvec(&raw).unwrap()).unwrap();
+        let mut challenger = Challenger::new(perm.clone());
+        let result = verify(&config, &FibonacciAir {}, &mut challenger, &tampered, &pis);
+        assert!(matches!(
+            result,
+            Err(VerificationError::InvalidOpeningArgument {
+                query: None,
+                commitment: None,
+            })
+        ));
+    }
+}
+
+#[test]
+fn test_decode_arbitrary_bytes() {
+    let (config, perm, pis, _) = decoding_fixture();
+    // Start with the current version, so that most inputs get past the version check.
+    let bytes = collection::vec(any::<u8>(), 0..1024).prop_map(|mut bytes| {
+        bytes.insert(0, PROOF_VERSION as u8);
+        bytes
+    });
+    TestRunner::default()
+        .run(&bytes, |bytes| {
+            decode_and_verify(&config, &perm, &pis, &bytes, None);
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_decode_corrupted_proof() {
+    let (config, perm, pis, proof_bytes) = decoding_fixture();
+    let len = proof_bytes.len();
+    let corruptions = (
+        collection::vec((0..len, any::<u8>()), 1..8),
+        prop_oneof![Just(len), 0..len],
+    );
+    TestRunner::default()
+        .run(&corruptions, |(writes, truncated_len)| {
+            let mut bytes = proof_bytes.clone();
+            for (i, byte) in writes {
+                bytes[i] = byte;
+            }
+            bytes.truncate(truncated_len);
+            decode_and_verify(&config, &perm, &pis, &bytes, Some(&proof_bytes));
+            Ok(())
+        })
+        .unwrap();
+}
+
+/// A config, and the encoding of a valid proof with the given public values.
+fn decoding_fixture() -> (MyConfig, Perm, Vec<Val>, Vec<u8>) {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    (config, perm, pis, proof.to_bytes())
+}
+
+/// Decodes `bytes` and, if they're a proof other than the valid one encoded in `valid`, checks
+/// that `verify` rejects it. Neither step may panic.
+fn decode_and_verify(
+    config: &MyConfig,
+    perm: &Perm,
+    pis: &Vec<Val>,
+    bytes: &[u8],
+    valid: Option<&[u8]>,
+) {
+    let Ok(proof) = Proof::<MyConfig>::from_bytes(bytes) else {
+        return;
+    };
+    let mut challenger = Challenger::new(perm.clone());
+    let result = verify(config, &FibonacciAir {}, &mut challenger, &proof, pis);
+    // A corruption can leave the proof intact, e.g. by writing a byte's own value back.
+    if valid != Some(&proof.to_bytes()[..]) {
+        assert!(result.is_err(), "accepted a corrupted proof");
+    }
+}
+
+#[test]
+fn test_clone_proof() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let copy = proof.clone();
+    assert_eq!(copy.to_bytes(), proof.to_bytes());
+    assert_eq!(format!("{:?}", copy), format!("{:?}", proof));
+    for proof in [&proof, &copy] {
+        let mut challenger = Challenger::new(perm.clone());
+        verify(&config, &FibonacciAir {}, &mut challenger, proof, &pis)
+            .expect("verification failed");
+    }
+}
+
+#[test]
+fn test_commitment_roots() {
+    let commitments = Commitments {
+        trace: 1,
+        quotient_chunks: 2,
+    };
+    assert_eq!(commitments.roots(), [&1, &2]);
+}
+
+#[test]
+fn test_prove_trace_only() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        trace.clone(),
+        &pis,
+    );
+    let mut challenger = Challenger::new(perm.clone());
+    let trace_only = prove_trace_only(&config, &FibonacciAir {}, &mut challenger, trace, &pis)
+        .expect("trace satisfies the constraints");
+
+    assert_eq!(trace_only.trace_commit, proof.commitments().trace);
+    assert_eq!(trace_only.degree_bits, 3);
+    let shifts = trace_only
+        .trace_opens
+        .iter()
+        .map(|(shift, values)| {
+            assert_eq!(values.len(), NUM_FIBONACCI_COLS);
+            *shift
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(shifts, [0, 1]);
+
+    // The constraints are still checked, even though they aren't proven.
+    let mut trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    trace.values[5] += Val::one();
+    let mut challenger = Challenger::new(perm);
+    assert!(prove_trace_only(&config, &FibonacciAir {}, &mut challenger, trace, &pis).is_err());
+}
+
+#[test]
+fn test_recursive_verifier_layout() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm);
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let layout = RecursiveVerifierLayout::for_proof(&proof);
+    // Each challenge field element is 4 columns. The verifier samples alpha, zeta and a beta for
+    // each of the 3 FRI rounds, reads the trace's 2 columns at 2 points and the single quotient
+    // chunk, computes the folded constraints and the quotient, and then checks the query: its
+    // reduced opening, in each round an index bit, a point, a sibling and a folded evaluation,
+    // and finally the final polynomial.
+    let challenges = (2 + 3) * 4;
+    let opened_values = (2 * 2 + 1) * 4;
+    let constraint_check = 2 * 4;
+    let fri_query = 4 + 3 * (1 + 1 + 4 + 4) + 4;
+    assert_eq!(
+        layout.width(),
+        challenges + opened_values + constraint_check + fri_query
+    );
+
+    let air = RecursiveVerifierAir::<_, 4>::new(layout, ());
+    assert_eq!(BaseAir::<Val>::width(&air), layout.width());
+}
+
+#[test]
+fn test_proof_bytes_round_trip() {
+    let (config, perm) = make_config(6);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 6);
+    let pis = vec![
+        Val::zero(),
+        Val::one(),
+        trace.row_slice(trace.height() - 1)[1],
+    ];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    assert_eq!(proof.trace_height(), Some(1 << 6));
+
+    let bytes = proof.to_bytes();
+    // `size_in_bytes` counts each opened and public field element at its longest varint, which is
+    // five bytes for BabyBear, so it can't undercount, and overcounts by at most four bytes each.
+    let opened = proof.opened_values();
+    let num_elements = 4 * opened
+        .trace_opens
+        .iter()
+        .map(|(_, values)| values.len())
+        .chain(opened.quotient_chunks.iter().map(Vec::len))
+        .sum::<usize>()
+        + pis.len();
+    let estimate = proof.size_in_bytes();
+    assert!(estimate >= bytes.len());
+    assert!(estimate <= bytes.len() + 4 * num_elements);
+    let decoded = Proof::<MyConfig>::from_bytes(&bytes).expect("failed to decode proof");
+    assert_eq!(decoded.to_bytes(), bytes);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &decoded, &pis)
+        .expect("verification failed");
+
+    // The binary encoding should be at least a third smaller than the JSON one.
+    let json = serde_json::to_vec(&proof).unwrap();
+    assert!(
+        3 * bytes.len() < 2 * json.len(),
+        "binary proof is {} bytes, JSON proof is {} bytes",
+        bytes.len(),
+        json.len()
+    );
+
+    assert!(Proof::<MyConfig>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_proof_with_other_version_is_rejected() {
+    let (config, perm) = make_config(3);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm);
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    assert_eq!(proof.version(), PROOF_VERSION);
+
+    let mut raw: RawProof = postcard::from_bytes(&proof.to_bytes()).unwrap();
+    raw.version = PROOF_VERSION + 1;
+
+    let bytes = postcard::to_allocvec(&raw).unwrap();
+    assert!(matches!(
+        Proof::<MyConfig>::from_bytes(&bytes),
+        Err(ProofDecodeError::UnsupportedVersion { version }) if version == PROOF_VERSION + 1
+    ));
+
+    let json = serde_json::to_string(&raw).unwrap();
+    let err = serde_json::from_str::<Proof<MyConfig>>(&json).unwrap_err();
+    assert!(
+        err.to_string().contains("unsupported proof version"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_constraint_degrees() {
+    let mut builder = DegreeCheckingBuilder::<Val>::new(NUM_FIBONACCI_COLS, 3, 2);
+    FibonacciAir {}.eval(&mut builder);
+    // The transition constraints are degree 1. The boundary constraints are degree 2, since the
+    // first- and last-row selectors count as degree 1.
+    assert_eq!(builder.degrees(), [2, 2, 1, 1, 2]);
+}
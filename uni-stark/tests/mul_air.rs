@@ -9,7 +9,7 @@ use p3_commit::testing::TrivialPcs;
 use p3_commit::ExtensionMmcs;
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
-use p3_field::{AbstractField, Field};
+use p3_field::{AbstractExtensionField, AbstractField, Field};
 use p3_fri::{FriConfig, TwoAdicFriPcs};
 use p3_keccak::Keccak256Hash;
 use p3_matrix::dense::RowMajorMatrix;
@@ -20,7 +20,10 @@ use p3_poseidon2::Poseidon2;
 use p3_symmetric::{
     CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
 };
-use p3_uni_stark::{prove, verify, StarkConfig, StarkGenericConfig, Val, VerificationError};
+use p3_uni_stark::{
+    get_log_quotient_degree, prove, verify, Proof, StarkConfig, StarkGenericConfig, Val,
+    VerificationError,
+};
 use rand::distributions::{Distribution, Standard};
 use rand::{thread_rng, Rng};
 
@@ -115,12 +118,37 @@ impl<AB: AirBuilder> Air<AB> for MulAir {
     }
 }
 
+/// Checks `Proof::size_in_bytes` against the length of an actual encoding. It counts each opened
+/// value and public value at its longest varint, so it may be over by up to one byte less than that
+/// for each of their field elements, but never under.
+fn check_size_in_bytes<SC: StarkGenericConfig>(proof: &Proof<SC>, encoded_len: usize) {
+    let opened = proof.opened_values();
+    let num_opened = opened
+        .trace_opens
+        .iter()
+        .map(|(_, values)| values.len())
+        .chain(opened.quotient_chunks.iter().map(Vec::len))
+        .sum::<usize>();
+    let num_elements = num_opened * <SC::Challenge as AbstractExtensionField<Val<SC>>>::D
+        + proof.public_values().len();
+    let margin = num_elements * (<Val<SC> as Field>::bits().div_ceil(7) - 1);
+
+    let estimate = proof.size_in_bytes();
+    assert!(
+        (encoded_len..=encoded_len + margin).contains(&estimate),
+        "estimated {} bytes for a proof of {} bytes, with a margin of {}",
+        estimate,
+        encoded_len,
+        margin
+    );
+}
+
 fn do_test<SC: StarkGenericConfig>(
     config: SC,
     air: MulAir,
     log_height: usize,
     challenger: SC::Challenger,
-) -> Result<(), VerificationError>
+) -> Result<(), VerificationError<Val<SC>>>
 where
     SC::Challenger: Clone,
     Standard: Distribution<Val<SC>>,
@@ -132,6 +160,16 @@ where
 
     let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
     tracing::debug!("serialized_proof len: {} bytes", serialized_proof.len());
+    check_size_in_bytes(&proof, serialized_proof.len());
+    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, MulAir>(&air, 0);
+    assert_eq!(
+        proof.opened_values().quotient_chunks.len(),
+        1 << log_quotient_degree
+    );
+    assert_eq!(
+        proof.opened_values().trace_opens[0].1.len(),
+        <MulAir as BaseAir<Val<SC>>>::width(&air)
+    );
 
     let deserialized_proof =
         postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
@@ -146,7 +184,7 @@ where
     )
 }
 
-fn do_test_bb_trivial(degree: u64, log_n: usize) -> Result<(), VerificationError> {
+fn do_test_bb_trivial(degree: u64, log_n: usize) -> Result<(), VerificationError<BabyBear>> {
     type Val = BabyBear;
     type Challenge = BinomialExtensionField<Val, 4>;
 
@@ -177,17 +215,34 @@ fn do_test_bb_trivial(degree: u64, log_n: usize) -> Result<(), VerificationError
 }
 
 #[test]
-fn prove_bb_trivial_deg2() -> Result<(), VerificationError> {
+fn test_log_quotient_degree() {
+    let log_quotient_degree = |degree| {
+        let air = MulAir {
+            degree,
+            uses_boundary_constraints: false,
+            uses_transition_constraints: false,
+        };
+        get_log_quotient_degree::<BabyBear, MulAir>(&air, 0)
+    };
+    // The quotient has degree (constraint_degree - 1) n, padded to a power of two.
+    assert_eq!(log_quotient_degree(2), 0);
+    assert_eq!(log_quotient_degree(3), 1);
+    assert_eq!(log_quotient_degree(4), 2);
+    assert_eq!(log_quotient_degree(5), 2);
+}
+
+#[test]
+fn prove_bb_trivial_deg2() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_trivial(2, 10)
 }
 
 #[test]
-fn prove_bb_trivial_deg3() -> Result<(), VerificationError> {
+fn prove_bb_trivial_deg3() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_trivial(3, 10)
 }
 
 #[test]
-fn prove_bb_trivial_deg4() -> Result<(), VerificationError> {
+fn prove_bb_trivial_deg4() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_trivial(4, 10)
 }
 
@@ -195,7 +250,7 @@ fn do_test_bb_twoadic(
     log_blowup: usize,
     degree: u64,
     log_n: usize,
-) -> Result<(), VerificationError> {
+) -> Result<(), VerificationError<BabyBear>> {
     type Val = BabyBear;
     type Challenge = BinomialExtensionField<Val, 4>;
 
@@ -246,22 +301,22 @@ fn do_test_bb_twoadic(
 }
 
 #[test]
-fn prove_bb_twoadic_deg2() -> Result<(), VerificationError> {
+fn prove_bb_twoadic_deg2() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_twoadic(1, 2, 10)
 }
 
 #[test]
-fn prove_bb_twoadic_deg3() -> Result<(), VerificationError> {
+fn prove_bb_twoadic_deg3() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_twoadic(1, 3, 10)
 }
 
 #[test]
-fn prove_bb_twoadic_deg4() -> Result<(), VerificationError> {
+fn prove_bb_twoadic_deg4() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_twoadic(2, 4, 10)
 }
 
 #[test]
-fn prove_bb_twoadic_deg5() -> Result<(), VerificationError> {
+fn prove_bb_twoadic_deg5() -> Result<(), VerificationError<BabyBear>> {
     do_test_bb_twoadic(2, 5, 10)
 }
 
@@ -269,7 +324,7 @@ fn do_test_m31_circle(
     log_blowup: usize,
     degree: u64,
     log_n: usize,
-) -> Result<(), VerificationError> {
+) -> Result<(), VerificationError<Mersenne31>> {
     type Val = Mersenne31;
     // type Challenge = BinomialExtensionField<Val, 4>;
     type Challenge = Mersenne31;
@@ -322,11 +377,11 @@ fn do_test_m31_circle(
 }
 
 #[test]
-fn prove_m31_circle_deg2() -> Result<(), VerificationError> {
+fn prove_m31_circle_deg2() -> Result<(), VerificationError<Mersenne31>> {
     do_test_m31_circle(1, 2, 12)
 }
 
 #[test]
-fn prove_m31_circle_deg3() -> Result<(), VerificationError> {
+fn prove_m31_circle_deg3() -> Result<(), VerificationError<Mersenne31>> {
     do_test_m31_circle(1, 3, 14)
 }
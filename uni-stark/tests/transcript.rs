@@ -0,0 +1,298 @@
+//! Pins the order in which `prove` and `verify` feed the Fiat-Shamir transcript.
+//!
+//! The challenger is seeded with `SEED`, so the transcript is fully deterministic. A change to the
+//! transcript must be made to `prove` and `verify` alike, and `expected_shape` below updated to
+//! match. The trace commitment and `alpha` are also pinned to recorded values, which must be
+//! regenerated whenever the transcript, `SEED`, the permutation or the trace changes.
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_challenger::{CanObserve, CanSample, CanSampleBits, DuplexChallenger, FieldChallenger};
+use p3_commit::testing::MockPcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractExtensionField, AbstractField, ExtensionField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::{Hash, PaddingFreeSponge};
+use p3_uni_stark::{prove, verify, Proof, StarkConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const SEED: u64 = 0x5eed;
+const DIGEST_ELEMS: usize = 8;
+
+/// The commitment to `fixed_trace`, in canonical form.
+const EXPECTED_TRACE_COMMIT: [u32; DIGEST_ELEMS] = [
+    0x5eabb03c, 0x2becc5e1, 0x66439335, 0x0815e6d4, 0x5cdcc2f4, 0x22963e60, 0x49bc2ee1, 0x01724b32,
+];
+
+/// The base field coefficients of `alpha`, in canonical form, as sampled by the unseeded challenger.
+const EXPECTED_ALPHA: [u32; 4] = [0x25e2028d, 0x481ed0a9, 0x0fd88369, 0x18af225b];
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, DIGEST_ELEMS>;
+type Dft = Radix2DitParallel;
+type Pcs = MockPcs<Val, Dft, MyHash, DIGEST_ELEMS>;
+type MyConfig = StarkConfig<Pcs, Challenge, RecordingChallenger>;
+
+/// One interaction with the challenger, in base field elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Event {
+    Observe(Val),
+    Sample(Val),
+}
+
+/// A `DuplexChallenger` which records every element observed or sampled.
+#[derive(Clone)]
+struct RecordingChallenger {
+    inner: DuplexChallenger<Val, Perm, 16>,
+    events: Vec<Event>,
+}
+
+impl RecordingChallenger {
+    fn new(perm: Perm) -> Self {
+        Self {
+            inner: DuplexChallenger::new(perm),
+            events: vec![],
+        }
+    }
+}
+
+impl CanObserve<Val> for RecordingChallenger {
+    fn observe(&mut self, value: Val) {
+        self.events.push(Event::Observe(value));
+        self.inner.observe(value);
+    }
+}
+
+impl CanObserve<Hash<Val, Val, DIGEST_ELEMS>> for RecordingChallenger {
+    fn observe(&mut self, values: Hash<Val, Val, DIGEST_ELEMS>) {
+        for value in values {
+            self.observe(value);
+        }
+    }
+}
+
+impl<EF: ExtensionField<Val>> CanSample<EF> for RecordingChallenger {
+    fn sample(&mut self) -> EF {
+        EF::from_base_fn(|_| {
+            let value: Val = self.inner.sample();
+            self.events.push(Event::Sample(value));
+            value
+        })
+    }
+}
+
+impl CanSampleBits<usize> for RecordingChallenger {
+    fn sample_bits(&mut self, _bits: usize) -> usize {
+        panic!("uni-stark itself never samples bits, and MockPcs doesn't either");
+    }
+}
+
+impl FieldChallenger<Val> for RecordingChallenger {}
+
+/// A Fibonacci AIR with public values `a, b, x`, where `x` is the last row's right column.
+struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, x) = (pis[0], pis[1], pis[2]);
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_eq(local[0], a);
+        builder.when_first_row().assert_eq(local[1], b);
+        builder.when_transition().assert_eq(local[1], next[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + local[1], next[1]);
+        builder.when_last_row().assert_eq(local[1], x);
+    }
+}
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng(
+        8,
+        22,
+        DiffusionMatrixBabybear,
+        &mut StdRng::seed_from_u64(SEED),
+    );
+    let pcs = Pcs::new(Dft {}, MyHash::new(perm.clone()));
+    (MyConfig::new(pcs), perm)
+}
+
+/// The 8-row trace starting from `(0, 1)`, and its public values.
+fn fixed_trace() -> (RowMajorMatrix<Val>, Vec<Val>) {
+    let mut values = vec![Val::zero(), Val::one()];
+    for _ in 1..8 {
+        let (a, b) = (values[values.len() - 2], values[values.len() - 1]);
+        values.extend([b, a + b]);
+    }
+    let pis = [0, 1, 21].map(Val::from_canonical_u64).to_vec();
+    (RowMajorMatrix::new(values, 2), pis)
+}
+
+/// The transcript `prove` and `verify` should produce, with `None` standing for each sampled
+/// element and `Some(x)` for an observed `x`.
+fn expected_shape(proof: &Proof<MyConfig>, pis: &[Val]) -> Vec<Option<Val>> {
+    let sample_challenge = || vec![None; <Challenge as AbstractExtensionField<Val>>::D];
+    let commitments = proof.commitments();
+    let mut shape = vec![];
+    shape.extend(commitments.trace.into_iter().map(Some));
+    shape.extend(pis.iter().copied().map(Some));
+    // alpha
+    shape.extend(sample_challenge());
+    shape.extend(commitments.quotient_chunks.into_iter().map(Some));
+    // zeta
+    shape.extend(sample_challenge());
+    shape
+}
+
+fn shape(events: &[Event]) -> Vec<Option<Val>> {
+    events
+        .iter()
+        .map(|event| match event {
+            Event::Observe(value) => Some(*value),
+            Event::Sample(_) => None,
+        })
+        .collect()
+}
+
+fn samples(events: &[Event]) -> Vec<Val> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Sample(value) => Some(*value),
+            Event::Observe(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_transcript_order() {
+    let (config, perm) = setup();
+    let (trace, pis) = fixed_trace();
+
+    let mut prover_challenger = RecordingChallenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir, &mut prover_challenger, trace, &pis);
+    assert_eq!(
+        shape(&prover_challenger.events),
+        expected_shape(&proof, &pis)
+    );
+
+    // The verifier must replay exactly the same transcript, sampled challenges included.
+    let mut verifier_challenger = RecordingChallenger::new(perm);
+    verify(
+        &config,
+        &FibonacciAir,
+        &mut verifier_challenger,
+        &proof,
+        &pis,
+    )
+    .expect("verification failed");
+    assert_eq!(verifier_challenger.events, prover_challenger.events);
+}
+
+#[test]
+fn test_transcript_is_deterministic() {
+    let run = || {
+        let (config, perm) = setup();
+        let (trace, pis) = fixed_trace();
+        let mut challenger = RecordingChallenger::new(perm);
+        let proof = prove(&config, &FibonacciAir, &mut challenger, trace, &pis);
+        (proof.to_bytes(), challenger.events)
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn test_transcript_golden_values() {
+    let (config, perm) = setup();
+    let (trace, pis) = fixed_trace();
+
+    let mut challenger = RecordingChallenger::new(perm);
+    let proof = prove(&config, &FibonacciAir, &mut challenger, trace, &pis);
+
+    let trace_commit: Vec<Val> = proof.commitments().trace.into_iter().collect();
+    assert_eq!(
+        trace_commit,
+        EXPECTED_TRACE_COMMIT.map(Val::from_canonical_u32)
+    );
+    let alpha = &samples(&challenger.events)[..EXPECTED_ALPHA.len()];
+    assert_eq!(alpha, EXPECTED_ALPHA.map(Val::from_canonical_u32));
+}
+
+#[test]
+fn test_pre_seeded_transcript() {
+    let (config, perm) = setup();
+    let (trace, pis) = fixed_trace();
+    let domain_separator = Val::from_canonical_u32(0xd5);
+    let seeded = || {
+        let mut challenger = RecordingChallenger::new(perm.clone());
+        challenger.observe(domain_separator);
+        challenger
+    };
+
+    let mut prover_challenger = seeded();
+    let proof = prove(
+        &config,
+        &FibonacciAir,
+        &mut prover_challenger,
+        trace.clone(),
+        &pis,
+    );
+
+    // The transcript continues from the pre-seeded state, so its shape just gains a prefix, but
+    // every sampled challenge changes.
+    let mut expected = vec![Some(domain_separator)];
+    expected.extend(expected_shape(&proof, &pis));
+    assert_eq!(shape(&prover_challenger.events), expected);
+
+    let mut unseeded_challenger = RecordingChallenger::new(perm.clone());
+    prove(
+        &config,
+        &FibonacciAir,
+        &mut unseeded_challenger,
+        trace.clone(),
+        &pis,
+    );
+    let seeded_samples = samples(&prover_challenger.events);
+    let unseeded_samples = samples(&unseeded_challenger.events);
+    assert_eq!(seeded_samples.len(), unseeded_samples.len());
+    assert!(seeded_samples
+        .iter()
+        .zip(&unseeded_samples)
+        .all(|(a, b)| a != b));
+
+    // Pre-seeding is deterministic.
+    let mut again = seeded();
+    prove(&config, &FibonacciAir, &mut again, trace, &pis);
+    assert_eq!(again.events, prover_challenger.events);
+
+    // The verifier must start from the same state.
+    let mut verifier_challenger = seeded();
+    verify(
+        &config,
+        &FibonacciAir,
+        &mut verifier_challenger,
+        &proof,
+        &pis,
+    )
+    .expect("verification failed");
+    assert_eq!(verifier_challenger.events, prover_challenger.events);
+
+    let mut fresh_challenger = RecordingChallenger::new(perm);
+    assert!(verify(&config, &FibonacciAir, &mut fresh_challenger, &proof, &pis).is_err());
+}
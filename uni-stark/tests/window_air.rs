@@ -0,0 +1,130 @@
+//! An AIR which reads three rows at a time, so the trace is opened at three points.
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabybear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::MatrixRowSlices;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::Poseidon2;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    check_constraints, get_log_quotient_degree, prove, verify, ConstraintViolation, StarkConfig,
+};
+use rand::thread_rng;
+
+/// The Fibonacci sequence in a single column, with `x'' = x + x'` constrained across a window of
+/// three rows. The public values are the two seeds and the last term.
+struct SkipFibonacciAir;
+
+impl<F> BaseAir<F> for SkipFibonacciAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn window_size(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SkipFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, output) = (pis[0], pis[1], pis[2]);
+        let x = main.row_slice(0)[0];
+        let x_next = main.row_slice(1)[0];
+        let x_next_next = main.row_slice(2)[0];
+
+        builder.when_first_row().assert_eq(x, a);
+        builder.when_first_row().assert_eq(x_next, b);
+        builder
+            .when_transition_window(3)
+            .assert_eq(x + x_next, x_next_next);
+        builder.when_last_row().assert_eq(x, output);
+    }
+}
+
+fn generate_trace(n: usize) -> RowMajorMatrix<Val> {
+    let mut values = vec![Val::zero(), Val::one()];
+    while values.len() < n {
+        values.push(values[values.len() - 2] + values[values.len() - 1]);
+    }
+    RowMajorMatrix::new_col(values)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, DiffusionMatrixBabybear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+#[test]
+fn test_three_point_window() {
+    let perm = Perm::new_from_rng(8, 22, DiffusionMatrixBabybear, &mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft {};
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(3, dft, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+
+    let trace = generate_trace(1 << 3);
+    let pis = [0, 1, 13].map(Val::from_canonical_u64).to_vec();
+    assert_eq!(check_constraints(&SkipFibonacciAir, &trace, &pis), Ok(()));
+
+    // The constraints only have degree 2, but the window's transition selector needs a quotient
+    // of twice the trace's degree.
+    assert_eq!(get_log_quotient_degree::<Val, _>(&SkipFibonacciAir, 3), 1);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &SkipFibonacciAir, &mut challenger, trace, &pis);
+    let shifts = proof
+        .opened_values()
+        .trace_opens
+        .iter()
+        .map(|(shift, values)| {
+            assert_eq!(values.len(), 1);
+            *shift
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(shifts, [0, 1, 2]);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &SkipFibonacciAir, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[test]
+fn test_three_point_window_violation() {
+    // Row 3 no longer follows from rows 1 and 2, which only a window of three rows can see. The
+    // first such window to include it starts at row 1.
+    let mut trace = generate_trace(1 << 3);
+    trace.values[3] += Val::one();
+    let pis = [0, 1, 13].map(Val::from_canonical_u64).to_vec();
+    assert_eq!(
+        check_constraints(&SkipFibonacciAir, &trace, &pis),
+        Err(ConstraintViolation {
+            row: 1,
+            constraint: 2
+        })
+    );
+}